@@ -0,0 +1,448 @@
+use near_sdk::serde_json::json;
+use near_sdk::store::{LazyOption, LookupMap, Vector};
+use near_sdk::{
+    near, AccountId, env, require, Gas, NearToken, PanicOnDefault, Promise, PromiseError,
+};
+
+/// Gas attached to the `new_with_config` call on a freshly deployed license contract
+const INIT_GAS: Gas = Gas::from_tgas(30);
+/// Gas reserved for the `on_license_created` callback
+const CALLBACK_GAS: Gas = Gas::from_tgas(10);
+/// Storage allowance for a license contract's initial state, on top of its code
+const INITIAL_STATE_BYTES: u64 = 10_000;
+/// Maximum number of deployments returned by `get_deployments`
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// NEAR network the factory and its deployments are intended for.
+/// Mirrors the license contract's `Network`.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "lowercase")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+impl Network {
+    /// Account ID suffix of named accounts on this network
+    fn account_suffix(&self) -> &'static str {
+        match self {
+            Network::Mainnet => ".near",
+            Network::Testnet => ".testnet",
+        }
+    }
+}
+
+/// Settings forwarded to every license contract the factory deploys.
+/// Mirrors the license contract's `InitConfig`.
+#[near(serializers = [borsh, json])]
+#[serde(default)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InitConfig {
+    /// Account allowed to record wallet compliance statuses
+    pub compliance_officer: Option<AccountId>,
+    /// Whether wallets must be verified before a license can be granted to them
+    pub verification_required: bool,
+    /// Jurisdiction codes in which licenses may not be granted
+    pub blocked_jurisdictions: Vec<String>,
+}
+
+/// A license contract deployed by the factory.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deployment {
+    /// Product or customer name, the sub-account prefix
+    pub name: String,
+    /// Account the license contract is deployed to
+    pub account_id: AccountId,
+    /// Admin of the deployed license contract
+    pub admin: AccountId,
+    /// Hex-encoded sha256 of the deployed code
+    pub code_hash: String,
+    /// Block timestamp the deployment completed at (in nanoseconds)
+    pub created_at: u64,
+}
+
+/// Factory that deploys isolated license contracts as sub-accounts,
+/// one per product or customer.
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct LicenseFactory {
+    /// Account that can deploy license contracts and update factory settings
+    owner: AccountId,
+    /// Network deployments are initialized for
+    network: Network,
+    /// License contract code deployed to new sub-accounts
+    code: LazyOption<Vec<u8>>,
+    /// Settings forwarded to new deployments
+    default_config: InitConfig,
+    /// Mapping of names to completed deployments
+    deployments: LookupMap<String, Deployment>,
+    /// Names of completed deployments in the order they were created
+    deployment_names: Vector<String>,
+    /// Mapping of names with a deployment in flight to the attached deposit
+    pending: LookupMap<String, NearToken>,
+}
+
+/// Lowercase hex encoding of a byte slice
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[near]
+impl LicenseFactory {
+    /// Initialize the factory.
+    ///
+    /// # Arguments
+    /// * `owner` - The account that can deploy license contracts
+    /// * `expected_network` - The network this factory is intended for
+    ///
+    /// # Panics
+    /// Panics if the factory account doesn't belong to `expected_network`
+    #[init]
+    pub fn new(owner: AccountId, expected_network: Network) -> Self {
+        require!(
+            env::current_account_id().as_str().ends_with(expected_network.account_suffix()),
+            format!(
+                "Network mismatch: factory account {} is not a {:?} account",
+                env::current_account_id(),
+                expected_network
+            )
+        );
+
+        Self {
+            owner,
+            network: expected_network,
+            code: LazyOption::new(b"c", None),
+            default_config: InitConfig::default(),
+            deployments: LookupMap::new(b"d"),
+            deployment_names: Vector::new(b"n"),
+            pending: LookupMap::new(b"p"),
+        }
+    }
+
+    /// Store the license contract code used for new deployments.
+    /// The raw wasm is passed as the call's arguments rather than as JSON.
+    ///
+    /// # Panics
+    /// Panics if caller is not the owner or no code is given
+    pub fn set_license_code(&mut self) {
+        self.assert_owner();
+
+        let code = env::input().unwrap_or_default();
+        require!(!code.is_empty(), "License code must not be empty");
+        self.code.set(Some(code));
+    }
+
+    /// Replace the settings forwarded to new deployments.
+    /// Existing deployments are not affected.
+    ///
+    /// # Panics
+    /// Panics if caller is not the owner
+    pub fn set_default_config(&mut self, config: InitConfig) {
+        self.assert_owner();
+        self.default_config = config;
+    }
+
+    /// Deploy and initialize a license contract at `{name}.{factory}`.
+    /// The attached deposit funds the new account and must cover its code and initial state.
+    ///
+    /// # Arguments
+    /// * `name` - Product or customer name, used as the sub-account prefix
+    /// * `admin` - The admin of the new license contract
+    /// * `config` - Settings to initialize with, or `None` for the factory defaults
+    ///
+    /// # Panics
+    /// Panics if caller is not the owner, no code is set, the name is invalid or taken,
+    /// or the deposit doesn't cover storage
+    #[payable]
+    pub fn create_license_contract(
+        &mut self,
+        name: String,
+        admin: AccountId,
+        config: Option<InitConfig>,
+    ) -> Promise {
+        self.assert_owner();
+        require!(!name.contains('.'), "Name must not contain '.'");
+        let account_id: AccountId = format!("{}.{}", name, env::current_account_id())
+            .parse()
+            .unwrap_or_else(|_| env::panic_str("Invalid deployment name"));
+        require!(
+            !self.deployments.contains_key(&name) && !self.pending.contains_key(&name),
+            "A license contract with this name already exists"
+        );
+
+        let code = self.code.get().clone().unwrap_or_else(|| env::panic_str("License code not set"));
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= self.required_deposit(code.len() as u64),
+            "Attached deposit doesn't cover the license contract's storage"
+        );
+
+        let code_hash = to_hex(&env::sha256(&code));
+        let args = json!({
+            "admin": admin,
+            "expected_network": self.network,
+            "config": config.unwrap_or_else(|| self.default_config.clone()),
+        });
+        self.pending.insert(name.clone(), deposit);
+
+        Promise::new(account_id.clone())
+            .create_account()
+            .transfer(deposit)
+            .deploy_contract(code)
+            .function_call(
+                "new_with_config".to_string(),
+                args.to_string().into_bytes(),
+                NearToken::from_yoctonear(0),
+                INIT_GAS,
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(CALLBACK_GAS)
+                    .on_license_created(name, account_id, admin, code_hash),
+            )
+    }
+
+    /// Record a completed deployment, or refund the owner if it failed.
+    /// A failed deployment rolls back the whole batch, so the deposit is back on the factory.
+    #[private]
+    pub fn on_license_created(
+        &mut self,
+        name: String,
+        account_id: AccountId,
+        admin: AccountId,
+        code_hash: String,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) -> bool {
+        let deposit = self.pending.remove(&name).unwrap_or_default();
+
+        if result.is_err() {
+            if !deposit.is_zero() {
+                Promise::new(self.owner.clone()).transfer(deposit).detach();
+            }
+            return false;
+        }
+
+        self.deployments.insert(
+            name.clone(),
+            Deployment {
+                name: name.clone(),
+                account_id,
+                admin,
+                code_hash,
+                created_at: env::block_timestamp(),
+            },
+        );
+        self.deployment_names.push(name);
+        true
+    }
+
+    /// Get the deposit needed to deploy a license contract with the current code.
+    pub fn get_required_deposit(&self) -> NearToken {
+        let code_len = self.code.get().as_ref().map_or(0, |code| code.len() as u64);
+        self.required_deposit(code_len)
+    }
+
+    /// Get the hex-encoded sha256 of the license contract code, if set.
+    pub fn get_license_code_hash(&self) -> Option<String> {
+        self.code.get().as_ref().map(|code| to_hex(&env::sha256(code)))
+    }
+
+    /// Get the settings forwarded to new deployments.
+    pub fn get_default_config(&self) -> InitConfig {
+        self.default_config.clone()
+    }
+
+    /// Get the deployment recorded under a name, if any.
+    pub fn get_deployment(&self, name: String) -> Option<Deployment> {
+        self.deployments.get(&name).cloned()
+    }
+
+    /// Get the number of completed deployments.
+    pub fn get_deployment_count(&self) -> u32 {
+        self.deployment_names.len()
+    }
+
+    /// Get a page of completed deployments, oldest first.
+    ///
+    /// # Arguments
+    /// * `from_index` - Position to start from (default 0)
+    /// * `limit` - Maximum number of deployments to return (default and maximum 100)
+    pub fn get_deployments(&self, from_index: Option<u32>, limit: Option<u32>) -> Vec<Deployment> {
+        let start = from_index.unwrap_or(0);
+        let end = start
+            .saturating_add(limit.unwrap_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE))
+            .min(self.deployment_names.len());
+
+        (start..end)
+            .filter_map(|i| self.deployment_names.get(i))
+            .filter_map(|name| self.deployments.get(name).cloned())
+            .collect()
+    }
+
+    /// Get the factory owner.
+    pub fn get_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+
+    /// Get the network deployments are initialized for.
+    pub fn get_network(&self) -> Network {
+        self.network
+    }
+
+    /// Panics unless the caller is the owner
+    fn assert_owner(&self) {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Unauthorized: only owner can perform this action"
+        );
+    }
+
+    /// Deposit covering the storage of `code_len` bytes of code plus initial state
+    fn required_deposit(&self, code_len: u64) -> NearToken {
+        env::storage_byte_cost().saturating_mul((code_len + INITIAL_STATE_BYTES) as u128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    const CODE: &[u8] = b"\0asm license";
+
+    fn owner() -> AccountId {
+        "owner.near".parse().unwrap()
+    }
+
+    fn customer() -> AccountId {
+        "customer.near".parse().unwrap()
+    }
+
+    fn setup_context(predecessor: &AccountId, deposit: NearToken, input: &[u8]) {
+        let mut context = VMContextBuilder::new();
+        context
+            .current_account_id("factory.near".parse().unwrap())
+            .predecessor_account_id(predecessor.clone())
+            .attached_deposit(deposit);
+        let mut context = context.build();
+        context.input = input.to_vec().into();
+        testing_env!(context);
+    }
+
+    fn setup_factory() -> LicenseFactory {
+        setup_context(&owner(), NearToken::from_yoctonear(0), CODE);
+        let mut factory = LicenseFactory::new(owner(), Network::Mainnet);
+        factory.set_license_code();
+        factory
+    }
+
+    #[test]
+    fn test_set_license_code() {
+        let factory = setup_factory();
+
+        assert_eq!(factory.get_license_code_hash(), Some(to_hex(&env::sha256(CODE))));
+        assert!(factory.get_required_deposit() > NearToken::from_yoctonear(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Network mismatch")]
+    fn test_new_rejects_wrong_network() {
+        setup_context(&owner(), NearToken::from_yoctonear(0), &[]);
+        LicenseFactory::new(owner(), Network::Testnet);
+    }
+
+    #[test]
+    fn test_create_license_contract() {
+        let mut factory = setup_factory();
+        let deposit = factory.get_required_deposit();
+
+        setup_context(&owner(), deposit, &[]);
+        let _ = factory.create_license_contract("acme".to_string(), customer(), None);
+        assert!(factory.pending.contains_key("acme"));
+
+        let account_id: AccountId = "acme.factory.near".parse().unwrap();
+        let code_hash = factory.get_license_code_hash().unwrap();
+        assert!(factory.on_license_created(
+            "acme".to_string(),
+            account_id.clone(),
+            customer(),
+            code_hash.clone(),
+            Ok(())
+        ));
+
+        assert!(!factory.pending.contains_key("acme"));
+        assert_eq!(factory.get_deployment_count(), 1);
+        let deployment = factory.get_deployment("acme".to_string()).unwrap();
+        assert_eq!(deployment.account_id, account_id);
+        assert_eq!(deployment.admin, customer());
+        assert_eq!(deployment.code_hash, code_hash);
+        assert_eq!(factory.get_deployments(None, None), vec![deployment]);
+    }
+
+    #[test]
+    fn test_failed_deployment_is_not_recorded() {
+        let mut factory = setup_factory();
+        let deposit = factory.get_required_deposit();
+
+        setup_context(&owner(), deposit, &[]);
+        let _ = factory.create_license_contract("acme".to_string(), customer(), None);
+        assert!(!factory.on_license_created(
+            "acme".to_string(),
+            "acme.factory.near".parse().unwrap(),
+            customer(),
+            String::new(),
+            Err(PromiseError::Failed)
+        ));
+
+        assert!(!factory.pending.contains_key("acme"));
+        assert_eq!(factory.get_deployment_count(), 0);
+
+        // The name can be used again
+        let _ = factory.create_license_contract("acme".to_string(), customer(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "A license contract with this name already exists")]
+    fn test_create_rejects_pending_name() {
+        let mut factory = setup_factory();
+        let deposit = factory.get_required_deposit();
+
+        setup_context(&owner(), deposit, &[]);
+        let _ = factory.create_license_contract("acme".to_string(), customer(), None);
+        let _ = factory.create_license_contract("acme".to_string(), customer(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attached deposit doesn't cover the license contract's storage")]
+    fn test_create_rejects_insufficient_deposit() {
+        let mut factory = setup_factory();
+
+        setup_context(&owner(), NearToken::from_yoctonear(1), &[]);
+        let _ = factory.create_license_contract("acme".to_string(), customer(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only owner can perform this action")]
+    fn test_create_unauthorized() {
+        let mut factory = setup_factory();
+
+        setup_context(&customer(), NearToken::from_near(10), &[]);
+        let _ = factory.create_license_contract("acme".to_string(), customer(), None);
+    }
+
+    #[test]
+    fn test_default_config() {
+        let mut factory = setup_factory();
+        let config = InitConfig {
+            compliance_officer: Some(customer()),
+            verification_required: true,
+            blocked_jurisdictions: vec!["CU".to_string()],
+        };
+
+        factory.set_default_config(config.clone());
+        assert_eq!(factory.get_default_config(), config);
+    }
+}
@@ -0,0 +1,152 @@
+//! The `#[licensed]` attribute, re-exported and documented by the `license-gate` crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, parse_quote, Error, Expr, FnArg, Ident, ImplItem, ImplItemFn, ItemImpl, Pat, Token, Visibility};
+
+/// Method attributes that don't survive splitting a method into the license check
+/// and its callback
+const UNSUPPORTED_ATTRIBUTES: [&str; 5] = ["init", "payable", "private", "handle_result", "result_serializer"];
+
+/// Arguments of `#[licensed(contract = <expr>)]`
+struct LicensedArgs {
+    /// Expression for the license contract's `AccountId`, evaluated with `self` in scope
+    contract: Expr,
+}
+
+impl Parse for LicensedArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: Ident = input.parse()?;
+        if key != "contract" {
+            return Err(Error::new(key.span(), "expected `contract = <license contract account>`"));
+        }
+        input.parse::<Token![=]>()?;
+        Ok(Self { contract: input.parse()? })
+    }
+}
+
+/// Gate every public method of a contract impl block on the caller holding a license.
+/// See the `license-gate` crate for usage.
+#[proc_macro_attribute]
+pub fn licensed(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as LicensedArgs);
+    let item = parse_macro_input!(item as ItemImpl);
+    expand(args, item).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Split each public method into its license check and callback, and hand the
+/// block to `#[near]` so both are exported
+fn expand(args: LicensedArgs, mut item: ItemImpl) -> syn::Result<TokenStream2> {
+    if item.trait_.is_some() || !item.generics.params.is_empty() {
+        return Err(Error::new(
+            item.span(),
+            "#[licensed] applies to an inherent impl block without generics",
+        ));
+    }
+
+    let mut items = Vec::new();
+    for impl_item in std::mem::take(&mut item.items) {
+        match impl_item {
+            ImplItem::Fn(method) if matches!(method.vis, Visibility::Public(_)) => {
+                let (entry, callback) = split_method(&args.contract, method)?;
+                items.push(ImplItem::Fn(entry));
+                items.push(ImplItem::Fn(callback));
+            }
+            other => items.push(other),
+        }
+    }
+    item.items = items;
+
+    Ok(quote! {
+        #[::near_sdk::near]
+        #item
+    })
+}
+
+/// Turn `method` into an entry point, which asks the license contract whether the
+/// caller is licensed, and a private callback running the original body if so
+fn split_method(contract: &Expr, mut method: ImplItemFn) -> syn::Result<(ImplItemFn, ImplItemFn)> {
+    let signature = &method.sig;
+    if signature.receiver().is_none() || !signature.generics.params.is_empty() || signature.asyncness.is_some() {
+        return Err(Error::new(
+            signature.span(),
+            "#[licensed] methods take self and have no generics",
+        ));
+    }
+    if let Some(attr) = method
+        .attrs
+        .iter()
+        .find(|attr| UNSUPPORTED_ATTRIBUTES.iter().any(|name| attr.path().is_ident(name)))
+    {
+        return Err(Error::new(attr.span(), "attribute is not supported on #[licensed] methods"));
+    }
+
+    // Every argument is forwarded to the callback by name; the one marked `#[caller]`
+    // is filled with the checked caller instead of being taken from the input
+    let caller = Ident::new("caller", Span::mixed_site());
+    let mut forwarded = Vec::new();
+    let mut entry_inputs = Vec::new();
+    let mut has_caller = false;
+    for input in method.sig.inputs.iter_mut() {
+        let FnArg::Typed(arg) = input else {
+            continue;
+        };
+        let Pat::Ident(pat) = &*arg.pat else {
+            return Err(Error::new(arg.pat.span(), "#[licensed] method arguments must be plain identifiers"));
+        };
+        let name = pat.ident.clone();
+        let marked = arg.attrs.iter().any(|attr| attr.path().is_ident("caller"));
+        arg.attrs.retain(|attr| !attr.path().is_ident("caller"));
+        if marked {
+            if has_caller {
+                return Err(Error::new(arg.span(), "only one argument can be #[caller]"));
+            }
+            has_caller = true;
+            forwarded.push(quote!(#caller.clone()));
+        } else {
+            entry_inputs.push(arg.clone());
+            forwarded.push(quote!(#name));
+        }
+    }
+    method.sig.inputs[0] = parse_quote!(&mut self);
+
+    let name = method.sig.ident.clone();
+    let callback_name = format_ident!("{}_licensed_callback", name);
+    let docs: Vec<_> = method.attrs.iter().filter(|attr| attr.path().is_ident("doc")).collect();
+    let other_attrs: Vec<_> = method.attrs.iter().filter(|attr| !attr.path().is_ident("doc")).cloned().collect();
+    let entry: ImplItemFn = parse_quote! {
+        #(#docs)*
+        ///
+        /// Only callable by a licensed account; the call fails with
+        /// `license_gate::UNLICENSED_ERROR` otherwise.
+        #(#other_attrs)*
+        pub fn #name(&mut self, #(#entry_inputs),*) -> ::near_sdk::Promise {
+            let #caller = ::near_sdk::env::predecessor_account_id();
+            ::license_gate::check_license(#contract, &#caller).then(
+                Self::ext(::near_sdk::env::current_account_id()).#callback_name(#(#forwarded),*),
+            )
+        }
+    };
+
+    let mut callback = method;
+    callback.sig.ident = callback_name;
+    callback.sig.inputs.insert(
+        1,
+        parse_quote!(#[callback_result] license_check: Result<bool, ::near_sdk::PromiseError>),
+    );
+    let body = &callback.block;
+    callback.block = parse_quote!({
+        if !::license_gate::license_check_passed(license_check) {
+            ::near_sdk::env::panic_str(::license_gate::UNLICENSED_ERROR);
+        }
+        #body
+    });
+    let doc = format!(" Callback of `{}`, running it once the caller's license is confirmed.", name);
+    callback.attrs = other_attrs;
+    callback.attrs.push(parse_quote!(#[doc = #doc]));
+    callback.attrs.push(parse_quote!(#[private]));
+    Ok((entry, callback))
+}
@@ -0,0 +1,212 @@
+//! Helpers for contracts that gate their methods on Hopper licenses.
+//!
+//! # Licensed methods
+//!
+//! `#[licensed]` gates every public method of an impl block on its caller holding a
+//! license. Each method becomes a call asking the license contract's `is_licensed`
+//! about the caller, followed by a private `<method>_licensed_callback` running the
+//! original body, which fails with [`UNLICENSED_ERROR`] if the caller isn't licensed.
+//! The macro applies `#[near]` to the block itself, since the callbacks must be
+//! exported too; keep the contract's other methods in a separate `#[near]` block.
+//!
+//! ```ignore
+//! #[licensed(contract = self.license_contract.clone())]
+//! impl Consumer {
+//!     pub fn export_report(&mut self, #[caller] caller: AccountId, format: String) -> String {
+//!         // Runs in the callback, so `caller` is the licensed account, not the predecessor
+//!     }
+//! }
+//! ```
+//!
+//! The method's caller is passed to the argument marked `#[caller]`, if any; inside
+//! the body the predecessor is the contract itself. Methods return a `Promise` for
+//! their original result, and can't be `#[payable]`, `#[private]` or `#[init]`.
+//!
+//! # Commitment licenses
+//!
+//! A holder keeps a secret hash chain built for one verifier contract and has the
+//! admin grant a license to its head with `grant_license_to_commitment`. To prove the
+//! license to the verifier, the holder reveals the next link back; the verifier
+//! forwards it with `verify_opening` and reads the outcome with `opening_verified` in
+//! its callback. Each link is the sha256 of the verifier's account ID followed by the
+//! next link, so the license contract only accepts an opening from that verifier, and
+//! moves the license to it: every opening proves the license once and is worthless to
+//! whoever observes it.
+
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde_json::json;
+use near_sdk::{env, AccountId, AccountIdRef, Gas, NearToken, Promise, PromiseError};
+
+pub use license_gate_macros::licensed;
+
+// Lets the code `#[licensed]` generates name this crate from within it, e.g. in tests
+extern crate self as license_gate;
+
+/// Gas attached to the `verify_license_opening` call on the license contract
+pub const VERIFY_GAS: Gas = Gas::from_tgas(5);
+
+/// Gas attached to the `is_licensed` call on the license contract
+pub const LICENSE_CHECK_GAS: Gas = Gas::from_tgas(5);
+
+/// Error a `#[licensed]` method fails with when its caller holds no license
+pub const UNLICENSED_ERROR: &str = "Unauthorized: caller does not hold an active license";
+
+/// A holder's hash chain: the commitment to have licensed and the openings that
+/// prove it, in the order they are revealed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpeningChain {
+    /// Hex-encoded head of the chain, passed to `grant_license_to_commitment`
+    pub commitment: String,
+    /// Links whose link for the verifier is the commitment, then the previous opening, and so on
+    pub openings: Vec<[u8; 32]>,
+}
+
+/// The chain link before `opening` for `verifier`: sha256 of the verifier's account ID
+/// followed by the opening. Matches what the license contract checks.
+pub fn chain_link(verifier: &AccountIdRef, opening: &[u8; 32]) -> [u8; 32] {
+    let mut preimage = verifier.as_bytes().to_vec();
+    preimage.extend_from_slice(opening);
+    env::sha256_array(preimage)
+}
+
+/// Build a hash chain of `length` openings for `verifier` from a random secret seed.
+/// The seed itself is the last opening.
+pub fn opening_chain(verifier: &AccountIdRef, seed: [u8; 32], length: usize) -> OpeningChain {
+    let mut openings = vec![seed];
+    for _ in 0..length {
+        openings.push(chain_link(verifier, openings.last().unwrap()));
+    }
+    let head = openings.pop().unwrap();
+    openings.reverse();
+
+    OpeningChain {
+        commitment: head.iter().map(|byte| format!("{:02x}", byte)).collect(),
+        openings,
+    }
+}
+
+/// Ask a license contract to verify, and consume, an opening revealed by a holder.
+/// Must be called from the verifier the holder's chain was built for.
+/// Chain `.then()` a callback on the verifier that passes its result to `opening_verified`.
+pub fn verify_opening(license_contract: AccountId, opening: [u8; 32]) -> Promise {
+    Promise::new(license_contract).function_call(
+        "verify_license_opening".to_string(),
+        json!({ "opening": Base64VecU8(opening.to_vec()) }).to_string().into_bytes(),
+        NearToken::from_yoctonear(0),
+        VERIFY_GAS,
+    )
+}
+
+/// Interpret the `verify_license_opening` result in the verifier's callback.
+/// A failed call counts as unlicensed.
+pub fn opening_verified(result: Result<bool, PromiseError>) -> bool {
+    matches!(result, Ok(true))
+}
+
+/// Ask a license contract whether a wallet is licensed.
+/// Chain `.then()` a callback that passes its result to `license_check_passed`.
+pub fn check_license(license_contract: AccountId, wallet: &AccountIdRef) -> Promise {
+    Promise::new(license_contract).function_call(
+        "is_licensed".to_string(),
+        json!({ "wallet_address": wallet }).to_string().into_bytes(),
+        NearToken::from_yoctonear(0),
+        LICENSE_CHECK_GAS,
+    )
+}
+
+/// Interpret the `is_licensed` result in the callback. A failed call counts as unlicensed.
+pub fn license_check_passed(result: Result<bool, PromiseError>) -> bool {
+    matches!(result, Ok(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{near, testing_env, PanicOnDefault};
+
+    #[near(contract_state)]
+    #[derive(PanicOnDefault)]
+    struct Consumer {
+        license_contract: AccountId,
+        exports: u32,
+    }
+
+    #[licensed(contract = self.license_contract.clone())]
+    impl Consumer {
+        pub fn export_report(&mut self, #[caller] caller: AccountId, pages: u32) -> String {
+            self.exports += pages;
+            format!("{} exported {} pages", caller, pages)
+        }
+    }
+
+    fn consumer() -> Consumer {
+        Consumer {
+            license_contract: "license.near".parse().unwrap(),
+            exports: 0,
+        }
+    }
+
+    fn setup_context(predecessor: &str) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id("consumer.near".parse().unwrap())
+            .predecessor_account_id(predecessor.parse().unwrap())
+            .build());
+    }
+
+    #[test]
+    fn test_licensed_method_runs_for_licensed_caller() {
+        let mut contract = consumer();
+        setup_context("user.near");
+        let _ = contract.export_report(3);
+
+        // The license contract confirmed the caller, so the body runs in the callback
+        setup_context("consumer.near");
+        let report = contract.export_report_licensed_callback(Ok(true), "user.near".parse().unwrap(), 3);
+        assert_eq!(report, "user.near exported 3 pages");
+        assert_eq!(contract.exports, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: caller does not hold an active license")]
+    fn test_licensed_method_rejects_unlicensed_caller() {
+        let mut contract = consumer();
+
+        setup_context("consumer.near");
+        contract.export_report_licensed_callback(Ok(false), "user.near".parse().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_opening_chain_links_back_to_commitment() {
+        let verifier: AccountId = "verifier.near".parse().unwrap();
+        let chain = opening_chain(&verifier, [7; 32], 3);
+
+        assert_eq!(chain.openings.len(), 3);
+        assert_eq!(chain.openings[2], [7; 32]);
+        let head: String = chain_link(&verifier, &chain.openings[0])
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        assert_eq!(chain.commitment, head);
+        for pair in chain.openings.windows(2) {
+            assert_eq!(chain_link(&verifier, &pair[1]), pair[0]);
+        }
+        // A chain built for another verifier doesn't match
+        let other: AccountId = "other.near".parse().unwrap();
+        assert_ne!(chain_link(&other, &chain.openings[0]), chain_link(&verifier, &chain.openings[0]));
+    }
+
+    #[test]
+    fn test_opening_verified() {
+        assert!(opening_verified(Ok(true)));
+        assert!(!opening_verified(Ok(false)));
+        assert!(!opening_verified(Err(PromiseError::Failed)));
+    }
+
+    #[test]
+    fn test_license_check_passed() {
+        assert!(license_check_passed(Ok(true)));
+        assert!(!license_check_passed(Ok(false)));
+        assert!(!license_check_passed(Err(PromiseError::Failed)));
+    }
+}
@@ -0,0 +1,186 @@
+use near_sdk::{near, env};
+
+use crate::{LicenseContract, LicenseContractExt, ONE_DAY_NS};
+
+/// Maximum number of days returned by `get_daily_active`
+const MAX_SERIES_DAYS: u32 = 366;
+
+/// Number of active licenses on a given day.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DailyActive {
+    /// Days since the Unix epoch (UTC)
+    pub day: u64,
+    /// Licenses active at any point during the day
+    pub active: u64,
+}
+
+/// UTC day (days since the Unix epoch) containing a timestamp
+fn day_of(timestamp: u64) -> u64 {
+    timestamp / ONE_DAY_NS
+}
+
+/// Last UTC day during which a license expiring at `expiry` is still active
+fn last_active_day(expiry: u64) -> u64 {
+    day_of(expiry.saturating_sub(1))
+}
+
+#[near]
+impl LicenseContract {
+    /// Get the number of active timestamp licenses per day, oldest first.
+    /// Counters roll forward lazily on grants; days since the last grant are
+    /// derived from the pending expiry buckets. Block-height and epoch licenses
+    /// are not counted.
+    ///
+    /// # Arguments
+    /// * `from_day` - First day (days since the Unix epoch) to include
+    /// * `limit` - Number of days to include (capped at 366)
+    pub fn get_daily_active(&self, from_day: u64, limit: u32) -> Vec<DailyActive> {
+        let today = day_of(env::block_timestamp());
+        let limit = limit.min(MAX_SERIES_DAYS) as u64;
+        let end = today.min(from_day.saturating_add(limit.saturating_sub(1)));
+        if limit == 0 || from_day > end {
+            return Vec::new();
+        }
+
+        // Replay the unrolled days in memory up to the requested range
+        let mut active = self.active_licenses;
+        let mut day = self.activity_day;
+        while day < from_day {
+            active = active.saturating_sub(self.expiry_buckets.get(&day).copied().unwrap_or(0));
+            day += 1;
+        }
+
+        (from_day..=end)
+            .map(|day| {
+                let count = if day < self.activity_day {
+                    self.daily_active.get(&day).copied().unwrap_or(0)
+                } else {
+                    let count = active;
+                    active = active.saturating_sub(self.expiry_buckets.get(&day).copied().unwrap_or(0));
+                    count
+                };
+                DailyActive { day, active: count }
+            })
+            .collect()
+    }
+
+    /// Get the number of currently active timestamp licenses.
+    pub fn get_active_license_count(&self) -> u64 {
+        self.get_daily_active(day_of(env::block_timestamp()), 1)[0].active
+    }
+
+    /// Update the activity counters for a timestamp license moving from `old_expiry`
+    /// to `new_expiry`
+    pub(crate) fn track_activity(&mut self, old_expiry: Option<u64>, new_expiry: u64) {
+        self.roll_activity();
+
+        match old_expiry.filter(|&expiry| expiry > env::block_timestamp()) {
+            Some(expiry) => self.adjust_expiry_bucket(last_active_day(expiry), -1),
+            None => self.active_licenses += 1,
+        }
+        // A zero-length grant made at midnight still counts for the day it was made on
+        self.adjust_expiry_bucket(last_active_day(new_expiry).max(self.activity_day), 1);
+    }
+
+    /// Update the activity counters for a timestamp license expiring at `expiry` being removed
+    pub(crate) fn release_activity(&mut self, expiry: u64) {
+        self.roll_activity();
+
+        if expiry > env::block_timestamp() {
+            self.adjust_expiry_bucket(last_active_day(expiry), -1);
+            self.active_licenses = self.active_licenses.saturating_sub(1);
+        }
+    }
+
+    /// Close out every day before today, recording its active count and
+    /// dropping the licenses that expired during it
+    fn roll_activity(&mut self) {
+        let today = day_of(env::block_timestamp());
+        while self.activity_day < today {
+            let day = self.activity_day;
+            self.daily_active.insert(day, self.active_licenses);
+            if let Some(expired) = self.expiry_buckets.remove(&day) {
+                self.active_licenses = self.active_licenses.saturating_sub(expired);
+            }
+            self.activity_day += 1;
+        }
+    }
+
+    /// Add `delta` to the number of licenses expiring on `day`
+    fn adjust_expiry_bucket(&mut self, day: u64, delta: i64) {
+        let count = self.expiry_buckets.get(&day).copied().unwrap_or(0).saturating_add_signed(delta);
+        if count == 0 {
+            self.expiry_buckets.remove(&day);
+        } else {
+            self.expiry_buckets.insert(day, count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context};
+    use crate::Network;
+
+    const DAY_100: u64 = 100 * ONE_DAY_NS;
+
+    fn wallet(i: u32) -> String {
+        format!("wallet{}.near", i)
+    }
+
+    fn series(contract: &LicenseContract, from_day: u64, limit: u32) -> Vec<u64> {
+        contract.get_daily_active(from_day, limit).iter().map(|d| d.active).collect()
+    }
+
+    #[test]
+    fn test_daily_active_series() {
+        setup_context(&admin(), DAY_100);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license(wallet(0), 1, None, None);
+        contract.grant_license(wallet(1), 3, None, None);
+        assert_eq!(contract.get_active_license_count(), 2);
+
+        // Extending an active license doesn't count it twice
+        contract.grant_license(wallet(0), 1, None, None);
+        assert_eq!(contract.get_active_license_count(), 2);
+
+        // Days after the last grant are derived without any writes
+        setup_context(&admin(), DAY_100 + 5 * ONE_DAY_NS);
+        assert_eq!(series(&contract, 100, 10), vec![2, 2, 1, 0, 0, 0]);
+
+        // A later grant rolls the counters forward and records the past days
+        contract.grant_license(wallet(2), 1, None, None);
+        assert_eq!(series(&contract, 100, 10), vec![2, 2, 1, 0, 0, 1]);
+        assert_eq!(contract.daily_active.get(&102), Some(&1));
+    }
+
+    #[test]
+    fn test_expired_license_counts_again_when_renewed() {
+        setup_context(&admin(), DAY_100);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(wallet(0), 1, None, None);
+
+        setup_context(&admin(), DAY_100 + 3 * ONE_DAY_NS);
+        assert_eq!(contract.get_active_license_count(), 0);
+
+        contract.grant_license(wallet(0), 1, None, None);
+        assert_eq!(contract.get_active_license_count(), 1);
+        assert_eq!(series(&contract, 99, 5), vec![0, 1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_zero_length_grant_at_midnight_expires_with_the_day() {
+        setup_context(&admin(), DAY_100);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(wallet(0), 0, None, None);
+        assert_eq!(contract.get_active_license_count(), 1);
+
+        setup_context(&admin(), DAY_100 + ONE_DAY_NS);
+        contract.grant_license(wallet(1), 1, None, None);
+        assert_eq!(contract.get_active_license_count(), 1);
+        assert_eq!(series(&contract, 100, 2), vec![1, 1]);
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use near_sdk::{near, AccountId, env, require};
+
+use crate::events::LicenseEvent;
+use crate::{LicenseContract, LicenseContractExt};
+
+#[near]
+impl LicenseContract {
+    /// Approve an operator, e.g. a marketplace or escrow contract, to transfer the
+    /// caller's license on their behalf, in the style of NEP-178. Approving an
+    /// operator again replaces its approval ID. Approvals are cleared whenever the
+    /// license changes wallets or is burned.
+    ///
+    /// # Returns
+    /// The approval ID, which the operator must present to transfer the license
+    ///
+    /// # Panics
+    /// Panics if the caller has no active license
+    pub fn approve_operator(&mut self, operator_id: AccountId) -> u64 {
+        self.assert_not_shut_down();
+        let key = self.wallet_key(env::predecessor_account_id().as_str());
+        require!(self.holds_license(&key), "No active license to approve an operator for");
+
+        let approval_id = self.next_approval_id;
+        self.next_approval_id += 1;
+        let mut approvals = self.approvals.remove(&key).unwrap_or_default();
+        approvals.insert(operator_id.clone(), approval_id);
+        self.approvals.insert(key.clone(), approvals);
+
+        LicenseEvent::OperatorApproved {
+            wallet_address: key,
+            operator_id,
+            approval_id,
+        }
+        .emit();
+        approval_id
+    }
+
+    /// Revoke an operator's approval to transfer the caller's license.
+    ///
+    /// # Panics
+    /// Panics if the operator isn't approved
+    pub fn revoke_operator(&mut self, operator_id: AccountId) {
+        self.assert_not_shut_down();
+        let key = self.wallet_key(env::predecessor_account_id().as_str());
+        let mut approvals = self.approvals.remove(&key).unwrap_or_default();
+        require!(approvals.remove(&operator_id).is_some(), "Operator is not approved");
+        if !approvals.is_empty() {
+            self.approvals.insert(key.clone(), approvals);
+        }
+
+        LicenseEvent::OperatorRevoked {
+            wallet_address: key,
+            operator_id,
+        }
+        .emit();
+    }
+
+    /// Revoke every operator approval on the caller's license.
+    pub fn revoke_all_operators(&mut self) {
+        self.assert_not_shut_down();
+        let key = self.wallet_key(env::predecessor_account_id().as_str());
+        let mut operators: Vec<AccountId> = self.approvals.remove(&key).unwrap_or_default().into_keys().collect();
+        operators.sort();
+
+        for operator_id in operators {
+            LicenseEvent::OperatorRevoked {
+                wallet_address: key.clone(),
+                operator_id,
+            }
+            .emit();
+        }
+    }
+
+    /// Transfer a license as an approved operator. The transfer fee is charged to
+    /// the operator, and any deposit above it is refunded to the operator.
+    ///
+    /// # Arguments
+    /// * `owner_wallet` - The wallet currently holding the license
+    /// * `new_wallet` - The wallet address to transfer the license to
+    /// * `approval_id` - The approval ID returned by `approve_operator`
+    ///
+    /// # Panics
+    /// Panics if the caller isn't approved under `approval_id`, or the license
+    /// couldn't be transferred by its holder
+    #[payable]
+    pub fn operator_transfer_license(&mut self, owner_wallet: String, new_wallet: String, approval_id: u64) {
+        self.assert_not_shut_down();
+        let caller = env::predecessor_account_id();
+        let from_key = self.wallet_key(&owner_wallet);
+        require!(
+            self.approvals
+                .get(&from_key)
+                .and_then(|approvals| approvals.get(&caller))
+                == Some(&approval_id),
+            "Operator is not approved under this approval ID"
+        );
+
+        let to_key = self.wallet_key(&new_wallet);
+        self.transfer_for_fee(caller, from_key, to_key);
+    }
+
+    /// Check whether an operator is approved to transfer a wallet's license,
+    /// optionally under a specific approval ID.
+    pub fn is_operator_approved(&self, wallet_address: String, operator_id: AccountId, approval_id: Option<u64>) -> bool {
+        self.approvals
+            .get(&self.wallet_key(&wallet_address))
+            .and_then(|approvals| approvals.get(&operator_id))
+            .is_some_and(|&id| approval_id.is_none_or(|expected| expected == id))
+    }
+
+    /// Get the operators approved to transfer a wallet's license, with their approval IDs.
+    pub fn get_operator_approvals(&self, wallet_address: String) -> HashMap<AccountId, u64> {
+        self.approvals
+            .get(&self.wallet_key(&wallet_address))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+
+    fn marketplace() -> AccountId {
+        "market.near".parse().unwrap()
+    }
+
+    fn setup_contract() -> (LicenseContract, u64) {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+
+        setup_context(&user(), 0);
+        let approval_id = contract.approve_operator(marketplace());
+        (contract, approval_id)
+    }
+
+    #[test]
+    fn test_operator_transfer_clears_approvals() {
+        let (mut contract, approval_id) = setup_contract();
+        assert!(contract.is_operator_approved(user_str(), marketplace(), Some(approval_id)));
+        assert!(!contract.is_operator_approved(user_str(), marketplace(), Some(approval_id + 1)));
+
+        setup_context(&marketplace(), 0);
+        contract.operator_transfer_license(user_str(), "buyer.near".to_string(), approval_id);
+
+        assert!(!contract.is_licensed(user_str()));
+        assert!(contract.is_licensed("buyer.near".to_string()));
+        assert!(contract.get_operator_approvals(user_str()).is_empty());
+        assert!(contract.get_operator_approvals("buyer.near".to_string()).is_empty());
+    }
+
+    #[test]
+    fn test_reapproval_issues_new_id() {
+        let (mut contract, approval_id) = setup_contract();
+        let second_id = contract.approve_operator(marketplace());
+        assert_ne!(second_id, approval_id);
+        assert!(contract.is_operator_approved(user_str(), marketplace(), None));
+        assert!(!contract.is_operator_approved(user_str(), marketplace(), Some(approval_id)));
+
+        contract.approve_operator("escrow.near".parse().unwrap());
+        contract.revoke_all_operators();
+        assert!(contract.get_operator_approvals(user_str()).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Operator is not approved under this approval ID")]
+    fn test_revoked_operator_cannot_transfer() {
+        let (mut contract, approval_id) = setup_contract();
+        contract.revoke_operator(marketplace());
+
+        setup_context(&marketplace(), 0);
+        contract.operator_transfer_license(user_str(), "buyer.near".to_string(), approval_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Operator is not approved under this approval ID")]
+    fn test_stale_approval_id_rejected() {
+        let (mut contract, approval_id) = setup_contract();
+        contract.approve_operator(marketplace());
+
+        setup_context(&marketplace(), 0);
+        contract.operator_transfer_license(user_str(), "buyer.near".to_string(), approval_id);
+    }
+}
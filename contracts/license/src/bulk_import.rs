@@ -0,0 +1,258 @@
+use near_sdk::{near, env, require};
+
+use crate::checksum::ExpiryKind;
+use crate::jobs::batch_gas_exhausted;
+use crate::{GasClass, GrantSource, JobKind, JobStatus, LicenseContract, LicenseContractExt};
+
+/// Maximum number of legacy entries accepted per `import_legacy_licenses` call
+const MAX_LEGACY_BATCH: usize = 200;
+
+/// A license from the legacy system, keyed by wallet address.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegacyLicense {
+    pub wallet_address: String,
+    /// Expiry timestamp in nanoseconds
+    pub expiry: u64,
+}
+
+/// Progress of the legacy import, for resuming and reconciling it.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegacyImportProgress {
+    /// Token to pass with the next batch; equals the number of entries processed
+    pub cursor_token: String,
+    /// Entries processed so far, imported or not
+    pub processed: u64,
+    /// Entries written as new licenses
+    pub imported: u64,
+    /// Entries skipped because the wallet already had a license or appeared earlier
+    pub duplicates: u64,
+    /// Whether the import was closed with `finish_job`
+    pub finished: bool,
+}
+
+impl From<&JobStatus> for LegacyImportProgress {
+    fn from(job: &JobStatus) -> Self {
+        Self {
+            cursor_token: job.cursor.to_string(),
+            processed: job.processed,
+            imported: job.applied,
+            duplicates: job.processed - job.applied,
+            finished: !job.is_running(),
+        }
+    }
+}
+
+#[near]
+impl LicenseContract {
+    /// Import timestamp licenses from the legacy system in consecutive batches.
+    /// Each batch must carry the cursor token returned by the previous one, so a batch
+    /// retried after its transaction already landed is rejected instead of double-counted;
+    /// if an outcome is lost, `get_legacy_import_progress` tells where to resume.
+    /// Wallets that already hold a license, including migrated ones not yet indexed, are
+    /// skipped as duplicates. If gas runs low the batch stops early; resubmit the
+    /// unprocessed entries with the returned token.
+    ///
+    /// Runs as the `legacy_import` job; close it with `finish_job` once the data set
+    /// is exhausted, after which the next batch starts a new import at token "0".
+    ///
+    /// # Arguments
+    /// * `entries` - The next entries of the legacy data set (at most 200)
+    /// * `cursor_token` - Token from the previous batch, or `None` for the first batch
+    ///
+    /// # Returns
+    /// The import progress after this batch
+    ///
+    /// # Panics
+    /// Panics if the contract is paused, caller lacks the grant licenses permission, the
+    /// batch is too large, the cursor token doesn't match the current import position, an
+    /// imported entry's wallet is ineligible for a license, or an entry runs longer than the
+    /// long grant threshold (grant those with `propose_long_grant` instead)
+    pub fn import_legacy_licenses(
+        &mut self,
+        entries: Vec<LegacyLicense>,
+        cursor_token: Option<String>,
+    ) -> LegacyImportProgress {
+        self.assert_can_grant();
+        require!(entries.len() <= MAX_LEGACY_BATCH, "Too many entries in one import");
+        let mut job = self.begin_batch(JobKind::LegacyImport);
+        require!(
+            cursor_token.unwrap_or_else(|| "0".to_string()) == job.cursor.to_string(),
+            format!("Stale import cursor, resume from {}", job.cursor)
+        );
+
+        let now = env::block_timestamp();
+        let (mut processed, mut imported) = (0, 0);
+        for entry in &entries {
+            if batch_gas_exhausted() {
+                break;
+            }
+            processed += 1;
+
+            let key = self.wallet_key(&entry.wallet_address);
+            // Entries carried over by `migrate` hold a license without being indexed yet
+            let migrated = self.licenses.contains_key(&key)
+                || self.height_licenses.contains_key(&key)
+                || self.epoch_licenses.contains_key(&key);
+            if migrated || !self.index_wallet(&key) {
+                continue;
+            }
+
+            self.assert_eligible(&key);
+            self.assert_term_within_grant_threshold(ExpiryKind::Timestamp, entry.expiry);
+            if entry.expiry > now {
+                self.track_activity(None, entry.expiry);
+            }
+            self.write_expiry(ExpiryKind::Timestamp, &key, entry.expiry);
+            self.assign_serial(&key);
+            self.record_provenance(&key, GrantSource::Migration, None);
+            imported += 1;
+        }
+
+        job.cursor += processed;
+        job.processed += processed;
+        job.applied += imported;
+        self.metrics.imported += imported;
+        let job = self.end_batch(job);
+        self.record_gas(GasClass::Import);
+
+        LegacyImportProgress::from(&job)
+    }
+
+    /// Get the progress of the current or last legacy import, if any.
+    pub fn get_legacy_import_progress(&self) -> Option<LegacyImportProgress> {
+        self.get_job_status(JobKind::LegacyImport).as_ref().map(LegacyImportProgress::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::{Network, ONE_DAY_NS};
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{testing_env, Gas};
+
+    fn entries(range: std::ops::Range<u32>) -> Vec<LegacyLicense> {
+        range
+            .map(|i| LegacyLicense {
+                wallet_address: format!("wallet{}.near", i),
+                expiry: (i as u64 + 1) * ONE_DAY_NS,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_import_legacy_in_batches() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license("wallet1.near".to_string(), 30, None, None);
+
+        let progress = contract.import_legacy_licenses(entries(0..3), None);
+        assert_eq!(progress.cursor_token, "3");
+
+        // Wallet 2 repeats within the data set
+        let mut batch = entries(3..5);
+        batch.push(entries(2..3).remove(0));
+        let progress = contract.import_legacy_licenses(batch, Some(progress.cursor_token));
+
+        assert_eq!(
+            progress,
+            LegacyImportProgress {
+                cursor_token: "6".to_string(),
+                processed: 6,
+                imported: 4,
+                duplicates: 2,
+                finished: false,
+            }
+        );
+        assert_eq!(contract.get_legacy_import_progress(), Some(progress));
+        // The existing license is left untouched
+        assert_eq!(contract.get_expiry("wallet1.near".to_string()), Some(30 * ONE_DAY_NS));
+        assert_eq!(contract.get_expiry("wallet4.near".to_string()), Some(5 * ONE_DAY_NS));
+        assert_eq!(contract.get_active_license_count(), 5);
+        assert_eq!(contract.get_metrics().imported, 4);
+    }
+
+    #[test]
+    fn test_import_legacy_skips_unindexed_licenses() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        // Simulate entries carried over by `migrate` before the index was backfilled
+        contract.licenses.insert("wallet0.near".to_string(), 100 * ONE_DAY_NS);
+        contract.height_licenses.insert("wallet1.near".to_string(), 1_000);
+        contract.epoch_licenses.insert("wallet2.near".to_string(), 10);
+
+        let progress = contract.import_legacy_licenses(entries(0..4), None);
+
+        assert_eq!((progress.imported, progress.duplicates), (1, 3));
+        assert_eq!(contract.get_expiry("wallet0.near".to_string()), Some(100 * ONE_DAY_NS));
+        assert_eq!(contract.get_expiry("wallet1.near".to_string()), None);
+        assert_eq!(contract.get_expiry("wallet3.near".to_string()), Some(4 * ONE_DAY_NS));
+    }
+
+    #[test]
+    fn test_import_legacy_stops_when_gas_runs_low() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(admin())
+            .prepaid_gas(Gas::from_tgas(15))
+            .build());
+        let progress = contract.import_legacy_licenses(entries(0..3), None);
+
+        assert_eq!((progress.cursor_token.as_str(), progress.imported), ("0", 0));
+        assert_eq!(contract.get_expiry("wallet0.near".to_string()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Grants longer than 2 days need the co-signer, use propose_long_grant")]
+    fn test_import_legacy_rejects_entry_past_grant_threshold() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_long_grant_threshold(Some(2));
+
+        contract.import_legacy_licenses(entries(0..3), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Stale import cursor, resume from 3")]
+    fn test_import_legacy_rejects_replayed_batch() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.import_legacy_licenses(entries(0..3), None);
+        contract.import_legacy_licenses(entries(0..3), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn test_import_legacy_rejected_while_paused() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.pause();
+
+        contract.import_legacy_licenses(entries(0..3), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Wallet must complete compliance verification before being licensed")]
+    fn test_import_legacy_rejects_ineligible_entry() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_verification_required(true);
+
+        contract.import_legacy_licenses(entries(0..3), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_import_legacy_unauthorized() {
+        setup_context(&user(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.import_legacy_licenses(vec![LegacyLicense { wallet_address: user_str(), expiry: 1 }], None);
+    }
+}
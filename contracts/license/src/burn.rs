@@ -0,0 +1,151 @@
+use near_sdk::{near, env, require};
+
+use crate::checksum::ExpiryKind;
+use crate::events::LicenseEvent;
+use crate::{LicenseContract, LicenseContractExt};
+
+#[near]
+impl LicenseContract {
+    /// Permanently destroy the caller's license, e.g. to prove an unused seat was
+    /// decommissioned. Every license entry (timestamp, block-height and epoch) is
+    /// removed along with the license's listing, rental offer, operator approvals
+    /// and recovery setup, its credential is revoked, and the storage deposit recorded
+    /// against it, if any, is refunded to its depositor (see `deposit_license_storage`).
+    /// Its provenance and transfer history are kept for auditing.
+    ///
+    /// # Panics
+    /// Panics if the contract is paused, the caller has no license entry,
+    /// or the license is under a lien or lent out
+    pub fn burn_license(&mut self) {
+        self.assert_not_paused();
+        let holder = env::predecessor_account_id();
+        let key = self.wallet_key(holder.as_str());
+        self.assert_no_lien(&key);
+        require!(!self.is_lent_out(&key), "License is lent out");
+
+        let mut burned = false;
+        for kind in ExpiryKind::ALL {
+            if let Some(expiry) = self.take_expiry(kind, &key) {
+                if kind == ExpiryKind::Timestamp {
+                    self.release_activity(expiry);
+                }
+                burned = true;
+            }
+        }
+        require!(burned, "No license to burn");
+
+        self.retire_serial(&key);
+        self.listings.remove(&key);
+        self.rental_offers.remove(&key);
+        self.lien_authorizations.remove(&key);
+        self.approvals.remove(&key);
+        self.recovery_guardians.remove(&key);
+        self.recovery_requests.remove(&key);
+        self.last_transfers.remove(&key);
+        self.metrics.burns += 1;
+
+        LicenseEvent::LicenseBurned { wallet_address: key.clone() }.emit();
+        self.revoke_wallet_credential(&key, "License burned");
+        self.refund_storage_deposit(&key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::{Network, ONE_DAY_NS};
+    use crate::ReceiptKind;
+    use near_sdk::test_utils::{get_logs, VMContextBuilder};
+    use near_sdk::{testing_env, AccountId, NearToken};
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+        contract.grant_license_blocks(user_str(), 100, None, None);
+        contract
+    }
+
+    #[test]
+    fn test_burn_license() {
+        let mut contract = setup_contract();
+        let empty_checksum = LicenseContract::new(admin(), Network::Mainnet).get_state_checksum();
+
+        setup_context(&user(), ONE_DAY_NS);
+        contract.burn_license();
+
+        assert!(!contract.is_licensed(user_str()));
+        assert_eq!(contract.get_expiry(user_str()), None);
+        assert_eq!(contract.get_expiry_height(user_str()), None);
+        assert_eq!(contract.get_active_license_count(), 0);
+        assert_eq!(contract.get_state_checksum(), empty_checksum);
+        assert_eq!(contract.get_metrics().burns, 1);
+        assert!(get_logs()[0].contains(r#""event":"license_burned""#));
+    }
+
+    #[test]
+    fn test_burn_revokes_credential() {
+        let mut contract = setup_contract();
+
+        setup_context(&user(), ONE_DAY_NS);
+        contract.burn_license();
+
+        let credential = contract.get_credential(0).unwrap();
+        assert!(credential.revoked);
+        assert_eq!(credential.revocation_memo.as_deref(), Some("License burned"));
+    }
+
+    #[test]
+    fn test_burn_refunds_storage_deposit_to_depositor() {
+        let mut contract = setup_contract();
+        let sponsor: AccountId = "sponsor.near".parse().unwrap();
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(sponsor.clone())
+            .attached_deposit(NearToken::from_millinear(10))
+            .build());
+        contract.deposit_license_storage(user_str());
+
+        setup_context(&user(), 0);
+        contract.burn_license();
+
+        let receipt = contract.get_receipt(0).unwrap();
+        assert_eq!((receipt.kind, receipt.account), (ReceiptKind::StorageRefund, sponsor));
+        assert_eq!(receipt.amount, NearToken::from_millinear(10));
+        assert_eq!(contract.get_license_storage_deposit(user_str()), None);
+    }
+
+    #[test]
+    fn test_burn_without_deposit_refunds_nothing() {
+        let mut contract = setup_contract();
+
+        setup_context(&user(), 0);
+        contract.burn_license();
+
+        assert_eq!(contract.get_receipt(0), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "No license to burn")]
+    fn test_burn_without_license() {
+        let mut contract = setup_contract();
+
+        setup_context(&"other.near".parse::<AccountId>().unwrap(), 0);
+        contract.burn_license();
+    }
+
+    #[test]
+    #[should_panic(expected = "License is under a lien")]
+    fn test_burn_under_lien() {
+        let mut contract = setup_contract();
+        let lender: AccountId = "lending.near".parse().unwrap();
+        contract.set_lien_holder_approval(lender.clone(), true);
+        setup_context(&user(), 0);
+        contract.authorize_lien(Some(lender.clone()));
+        setup_context(&lender, 0);
+        contract.place_lien(user_str());
+
+        setup_context(&user(), 0);
+        contract.burn_license();
+    }
+}
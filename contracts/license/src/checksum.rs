@@ -0,0 +1,158 @@
+use near_sdk::{near, env};
+
+use crate::privacy::to_hex;
+use crate::{LicenseContract, LicenseContractExt};
+
+/// Which kind of expiry a license entry holds, and so which map it lives in
+#[near(serializers = [json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExpiryKind {
+    /// Block timestamp in nanoseconds
+    Timestamp,
+    /// Block height
+    Height,
+    /// Epoch height
+    Epoch,
+}
+
+impl ExpiryKind {
+    pub(crate) const ALL: [ExpiryKind; 3] = [ExpiryKind::Timestamp, ExpiryKind::Height, ExpiryKind::Epoch];
+
+    /// Whether an expiry of this kind has passed
+    pub(crate) fn has_passed(self, expiry: u64) -> bool {
+        let now = match self {
+            ExpiryKind::Timestamp => env::block_timestamp(),
+            ExpiryKind::Height => env::block_height(),
+            ExpiryKind::Epoch => env::epoch_height(),
+        };
+        expiry <= now
+    }
+
+    /// Tag distinguishing equal entries in different maps within the checksum
+    fn tag(self) -> u8 {
+        match self {
+            ExpiryKind::Timestamp => b'l',
+            ExpiryKind::Height => b'h',
+            ExpiryKind::Epoch => b'e',
+        }
+    }
+}
+
+/// Checksum contribution of a single license entry: sha256(tag || expiry || key)
+fn entry_digest(kind: ExpiryKind, key: &str, expiry: u64) -> Vec<u8> {
+    let mut preimage = Vec::with_capacity(9 + key.len());
+    preimage.push(kind.tag());
+    preimage.extend_from_slice(&expiry.to_le_bytes());
+    preimage.extend_from_slice(key.as_bytes());
+    env::sha256(&preimage)
+}
+
+#[near]
+impl LicenseContract {
+    /// Get the checksum over every timestamp, block-height and epoch license entry,
+    /// as hex. It is the XOR of the entries' digests, so it is independent of write
+    /// order: two deployments holding the same entries report the same checksum,
+    /// e.g. after a migration or a bulk import.
+    pub fn get_state_checksum(&self) -> String {
+        to_hex(&self.state_checksum)
+    }
+
+    /// Read the expiry of a license entry
+    pub(crate) fn read_expiry(&self, kind: ExpiryKind, key: &str) -> Option<u64> {
+        match kind {
+            ExpiryKind::Timestamp => self.licenses.get(key),
+            ExpiryKind::Height => self.height_licenses.get(key),
+            ExpiryKind::Epoch => self.epoch_licenses.get(key),
+        }
+        .copied()
+    }
+
+    /// Write the expiry of a license entry, keeping the state checksum and tenure up to date
+    pub(crate) fn write_expiry(&mut self, kind: ExpiryKind, key: &str, expiry: u64) {
+        if kind == ExpiryKind::Timestamp {
+            self.cover_tenure(key, expiry);
+        }
+        let previous = match kind {
+            ExpiryKind::Timestamp => self.licenses.insert(key.to_string(), expiry),
+            ExpiryKind::Height => self.height_licenses.insert(key.to_string(), expiry),
+            ExpiryKind::Epoch => self.epoch_licenses.insert(key.to_string(), expiry),
+        };
+        if let Some(previous) = previous {
+            self.fold_into_checksum(kind, key, previous);
+        }
+        self.fold_into_checksum(kind, key, expiry);
+    }
+
+    /// Remove a license entry, keeping the state checksum and tenure up to date
+    pub(crate) fn take_expiry(&mut self, kind: ExpiryKind, key: &str) -> Option<u64> {
+        if kind == ExpiryKind::Timestamp {
+            self.end_tenure(key);
+        }
+        let removed = match kind {
+            ExpiryKind::Timestamp => self.licenses.remove(key),
+            ExpiryKind::Height => self.height_licenses.remove(key),
+            ExpiryKind::Epoch => self.epoch_licenses.remove(key),
+        };
+        if let Some(expiry) = removed {
+            self.fold_into_checksum(kind, key, expiry);
+        }
+        removed
+    }
+
+    /// XOR an entry's digest into the checksum, adding it if absent and removing it if present
+    pub(crate) fn fold_into_checksum(&mut self, kind: ExpiryKind, key: &str, expiry: u64) {
+        for (byte, digest_byte) in self.state_checksum.iter_mut().zip(entry_digest(kind, key, expiry)) {
+            *byte ^= digest_byte;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+
+    const EMPTY_CHECKSUM: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+    fn wallet(i: u32) -> String {
+        format!("wallet{}.near", i)
+    }
+
+    #[test]
+    fn test_checksum_is_order_independent() {
+        setup_context(&admin(), 0);
+        let mut first = LicenseContract::new(admin(), Network::Mainnet);
+        let mut second = LicenseContract::new(admin(), Network::Mainnet);
+        assert_eq!(first.get_state_checksum(), EMPTY_CHECKSUM);
+
+        first.grant_license(wallet(0), 30, None, None);
+        first.grant_license_blocks(wallet(1), 100, None, None);
+        second.grant_license_blocks(wallet(1), 100, None, None);
+        second.grant_license(wallet(0), 30, None, None);
+        assert_eq!(first.get_state_checksum(), second.get_state_checksum());
+        assert_ne!(first.get_state_checksum(), EMPTY_CHECKSUM);
+
+        // Extending replaces the old entry's contribution
+        first.grant_license(wallet(0), 30, None, None);
+        assert_ne!(first.get_state_checksum(), second.get_state_checksum());
+        second.grant_license(wallet(0), 30, None, None);
+        assert_eq!(first.get_state_checksum(), second.get_state_checksum());
+    }
+
+    #[test]
+    fn test_checksum_follows_transfers() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+        let expiry = contract.get_expiry(user_str()).unwrap();
+
+        setup_context(&user(), 0);
+        contract.transfer_license(wallet(0));
+
+        let mut expected = LicenseContract::new(admin(), Network::Mainnet);
+        expected.write_expiry(ExpiryKind::Timestamp, &wallet(0), expiry);
+        assert_eq!(contract.get_state_checksum(), expected.get_state_checksum());
+    }
+}
@@ -0,0 +1,292 @@
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::{near, AccountIdRef, env, require};
+
+use crate::events::LicenseEvent;
+use crate::privacy::to_hex;
+use crate::provenance::assert_valid_memo;
+use crate::{assert_valid_correlation_id, ExpiryKind, GasClass, LicenseContract, LicenseContractExt, ONE_DAY_NS};
+
+/// The chain link before `opening` for the given verifier: sha256 of the verifier's
+/// account ID followed by the opening. Binding the chain to the verifier means an
+/// opening observed in a transaction is worthless to any other caller
+fn chain_link(verifier: &AccountIdRef, opening: &[u8; 32]) -> [u8; 32] {
+    let mut preimage = verifier.as_bytes().to_vec();
+    preimage.extend_from_slice(opening);
+    env::sha256_array(preimage)
+}
+
+/// Parse a hex-encoded 32-byte commitment
+pub(crate) fn parse_commitment(commitment: &str) -> [u8; 32] {
+    let bytes = commitment.as_bytes();
+    // Checked up front: `from_str_radix` also takes a leading `+`, which would give
+    // a commitment several spellings
+    require!(
+        bytes.len() == 64 && bytes.iter().all(u8::is_ascii_hexdigit),
+        "Commitment must be 32 bytes of hex"
+    );
+
+    let mut parsed = [0u8; 32];
+    for (i, pair) in bytes.chunks(2).enumerate() {
+        let pair = std::str::from_utf8(pair).unwrap_or_default();
+        parsed[i] = u8::from_str_radix(pair, 16)
+            .unwrap_or_else(|_| env::panic_str("Commitment must be 32 bytes of hex"));
+    }
+    parsed
+}
+
+#[near]
+impl LicenseContract {
+    /// Grant a license bound to a commitment instead of a wallet, so the license is
+    /// never linked to a wallet on-chain. The commitment is the head of a hash chain
+    /// the holder keeps secret, built for the verifier account that will check it (see
+    /// the `license-gate` crate); each verification reveals the next link back, see
+    /// `verify_license_opening`.
+    /// Extends from the current expiry if the commitment is still licensed; to extend
+    /// a license that was verified since, pass the last revealed opening.
+    ///
+    /// # Arguments
+    /// * `commitment` - Hex-encoded head of the holder's hash chain
+    /// * `duration_days` - Number of days to grant the license for
+    /// * `correlation_id` - Optional order or payment ID echoed in the `license_granted` event
+    /// * `memo` - Optional justification (e.g. a support ticket) echoed in the `license_granted` event
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, the commitment is malformed, the duration overflows,
+    /// the remaining term would exceed the long grant threshold, the correlation ID or memo is too long, or wallet
+    /// verification or jurisdiction restrictions are active (commitments can't satisfy them)
+    pub fn grant_license_to_commitment(
+        &mut self,
+        commitment: String,
+        duration_days: u32,
+        correlation_id: Option<String>,
+        memo: Option<String>,
+    ) {
+        self.assert_can_grant();
+        let duration_ns = (duration_days as u64)
+            .checked_mul(ONE_DAY_NS)
+            .unwrap_or_else(|| env::panic_str("Expiry timestamp overflow"));
+        require!(
+            !self.verification_required && self.blocked_jurisdictions.is_empty(),
+            "Commitment licenses are unavailable while compliance restrictions are active"
+        );
+        assert_valid_correlation_id(&correlation_id);
+        assert_valid_memo(&memo);
+
+        let commitment = parse_commitment(&commitment);
+        let current_timestamp = env::block_timestamp();
+
+        let previous_expiry = self.commitment_licenses.get(&commitment).copied();
+        let base_timestamp = previous_expiry
+            .filter(|&expiry| expiry > current_timestamp)
+            .unwrap_or(current_timestamp);
+
+        let new_expiry = base_timestamp
+            .checked_add(duration_ns)
+            .unwrap_or_else(|| env::panic_str("Expiry timestamp overflow"));
+        self.assert_term_within_grant_threshold(ExpiryKind::Timestamp, new_expiry);
+        self.commitment_licenses.insert(commitment, new_expiry);
+        self.metrics.grants += 1;
+
+        LicenseEvent::LicenseGranted {
+            wallet_address: to_hex(&commitment),
+            expiry_kind: ExpiryKind::Timestamp,
+            previous_expiry,
+            new_expiry,
+            serial: None,
+            correlation_id,
+            memo,
+        }
+        .emit();
+        self.record_gas(GasClass::Grant);
+    }
+
+    /// Verify an opening revealed by a commitment license holder to the calling verifier
+    /// contract. The opening is the link of the holder's hash chain whose sha256, prefixed
+    /// with the caller's account ID, is the licensed commitment; once verified, the license
+    /// moves to the opening itself, so the holder's next proof reveals the link before it.
+    /// Each opening thus proves the license once, and only to the verifier the chain was
+    /// built for, so one seen in a transaction can't be replayed or used up by others.
+    ///
+    /// # Arguments
+    /// * `opening` - The 32-byte link whose sha256 with the caller's account ID is the
+    ///   current commitment
+    ///
+    /// # Returns
+    /// `true` if the opening's link for the caller held a license that hasn't expired,
+    /// `false` otherwise
+    ///
+    /// # Panics
+    /// Panics if the contract is shut down
+    pub fn verify_license_opening(&mut self, opening: Base64VecU8) -> bool {
+        self.assert_not_shut_down();
+        let Ok(next) = <[u8; 32]>::try_from(opening.0.as_slice()) else {
+            return false;
+        };
+        let commitment = chain_link(&env::predecessor_account_id(), &next);
+        match self.commitment_licenses.get(&commitment).copied() {
+            Some(expiry) if expiry > env::block_timestamp() => {
+                self.commitment_licenses.remove(&commitment);
+                self.commitment_licenses.insert(next, expiry);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Get the raw expiry timestamp of a commitment license.
+    ///
+    /// # Arguments
+    /// * `commitment` - Hex-encoded commitment
+    ///
+    /// # Returns
+    /// `Some(timestamp)` if the commitment has a license entry, `None` otherwise
+    pub fn get_commitment_expiry(&self, commitment: String) -> Option<u64> {
+        self.commitment_licenses.get(&parse_commitment(&commitment)).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user};
+    use crate::Network;
+    use near_sdk::AccountId;
+
+    fn verifier() -> AccountId {
+        "verifier.near".parse().unwrap()
+    }
+
+    /// Hash chain of three links for `verifier()` from a fixed seed: the commitment
+    /// and the openings in the order they are revealed
+    fn chain() -> (String, Vec<Vec<u8>>) {
+        let mut links = vec![[7u8; 32]];
+        for _ in 0..3 {
+            links.push(chain_link(&verifier(), links.last().unwrap()));
+        }
+        let commitment = to_hex(&links.pop().unwrap());
+        links.reverse();
+        (commitment, links.iter().map(|link| link.to_vec()).collect())
+    }
+
+    #[test]
+    fn test_commitment_license_verification() {
+        let (commitment, openings) = chain();
+        setup_context(&admin(), 1_000_000_000);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license_to_commitment(commitment.clone(), 30, None, None);
+        assert_eq!(
+            contract.get_commitment_expiry(commitment),
+            Some(1_000_000_000 + 30 * ONE_DAY_NS)
+        );
+
+        // The verifier the chain was built for can check the next opening
+        setup_context(&verifier(), 1_000_000_000);
+        assert!(!contract.verify_license_opening(Base64VecU8(b"wrong".to_vec())));
+        assert!(contract.verify_license_opening(Base64VecU8(openings[0].clone())));
+
+        // The license expires like any other
+        setup_context(&verifier(), 1_000_000_000 + 30 * ONE_DAY_NS);
+        assert!(!contract.verify_license_opening(Base64VecU8(openings[1].clone())));
+    }
+
+    #[test]
+    fn test_opening_cannot_be_replayed() {
+        let (commitment, openings) = chain();
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license_to_commitment(commitment, 30, None, None);
+
+        setup_context(&verifier(), 0);
+        assert!(contract.verify_license_opening(Base64VecU8(openings[0].clone())));
+        assert!(!contract.verify_license_opening(Base64VecU8(openings[0].clone())));
+
+        // The holder moves on to the next link, which extends under the last opening
+        assert!(contract.verify_license_opening(Base64VecU8(openings[1].clone())));
+        setup_context(&admin(), 0);
+        contract.grant_license_to_commitment(to_hex(&openings[1]), 30, None, None);
+        assert_eq!(contract.get_commitment_expiry(to_hex(&openings[1])), Some(60 * ONE_DAY_NS));
+        setup_context(&verifier(), 0);
+        assert!(contract.verify_license_opening(Base64VecU8(openings[2].clone())));
+    }
+
+    #[test]
+    fn test_opening_bound_to_verifier() {
+        let (commitment, openings) = chain();
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license_to_commitment(commitment.clone(), 30, None, None);
+
+        // Someone who observed the opening can neither prove nor use up the license
+        setup_context(&user(), 0);
+        assert!(!contract.verify_license_opening(Base64VecU8(openings[0].clone())));
+        assert_eq!(contract.get_commitment_expiry(commitment), Some(30 * ONE_DAY_NS));
+
+        setup_context(&verifier(), 0);
+        assert!(contract.verify_license_opening(Base64VecU8(openings[0].clone())));
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is shut down")]
+    fn test_verification_rejected_after_shutdown() {
+        let (commitment, openings) = chain();
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license_to_commitment(commitment, 30, None, None);
+        contract.shut_down = true;
+
+        setup_context(&verifier(), 0);
+        contract.verify_license_opening(Base64VecU8(openings[0].clone()));
+    }
+
+    #[test]
+    fn test_commitment_grant_emits_license_granted_event() {
+        let (commitment, _) = chain();
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license_to_commitment(commitment.clone(), 1, Some("in_123".to_string()), None);
+        let log = near_sdk::test_utils::get_logs().pop().unwrap();
+        assert!(log.contains(r#""event":"license_granted""#));
+        assert!(log.contains(&format!(r#""wallet_address":"{}""#, commitment)));
+        assert!(log.contains(r#""correlation_id":"in_123""#));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expiry timestamp overflow")]
+    fn test_commitment_grant_overflow() {
+        let (commitment, _) = chain();
+        setup_context(&admin(), u64::MAX - ONE_DAY_NS);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license_to_commitment(commitment, 2, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Commitment must be 32 bytes of hex")]
+    fn test_malformed_commitment() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license_to_commitment("zz".repeat(32), 30, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Commitment must be 32 bytes of hex")]
+    fn test_signed_commitment_rejected() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license_to_commitment("+f".repeat(32), 30, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Commitment licenses are unavailable while compliance restrictions are active")]
+    fn test_commitment_rejected_when_verification_required() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_verification_required(true);
+
+        contract.grant_license_to_commitment(chain().0, 30, None, None);
+    }
+}
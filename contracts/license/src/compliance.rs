@@ -0,0 +1,176 @@
+use near_sdk::{near, AccountId, require};
+
+use crate::{AdminAction, LicenseContract, LicenseContractExt, Permission};
+
+/// Verification status of a wallet, as recorded by the compliance officer.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ComplianceStatus {
+    /// No verification has been started
+    #[default]
+    Unverified,
+    /// Verification has been submitted and is under review
+    Pending,
+    /// Verification completed successfully
+    Verified,
+    /// Verification was rejected
+    Rejected,
+}
+
+#[near]
+impl LicenseContract {
+    /// Set (or clear) the compliance officer account.
+    ///
+    /// # Arguments
+    /// * `account_id` - The account allowed to set compliance statuses, or `None` to remove the role
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or an action delay is set
+    pub fn set_compliance_officer(&mut self, account_id: Option<AccountId>) {
+        self.submit_action(AdminAction::SetComplianceOfficer { account_id });
+    }
+
+    /// Require wallets to be `Verified` before a license can be granted to them.
+    ///
+    /// # Arguments
+    /// * `required` - Whether verification is required for new grants
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or an action delay is set
+    pub fn set_verification_required(&mut self, required: bool) {
+        self.submit_action(AdminAction::SetVerificationRequired { required });
+    }
+
+    /// Record the compliance status of a wallet.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet address whose status is being set
+    /// * `status` - The new compliance status
+    ///
+    /// # Panics
+    /// Panics if caller may not set compliance status
+    pub fn set_compliance_status(&mut self, wallet_address: String, status: ComplianceStatus) {
+        self.assert_permitted(Permission::SetComplianceStatus);
+
+        let key = self.wallet_key(&wallet_address);
+        if status == ComplianceStatus::Unverified {
+            self.compliance.remove(&key);
+        } else {
+            self.compliance.insert(key, status);
+        }
+    }
+
+    /// Get the compliance status of a wallet.
+    ///
+    /// # Returns
+    /// The recorded status, `Unverified` if none has been recorded
+    pub fn get_compliance_status(&self, wallet_address: String) -> ComplianceStatus {
+        self.compliance.get(&self.wallet_key(&wallet_address)).copied().unwrap_or_default()
+    }
+
+    /// Get the current compliance officer account, if any.
+    pub fn get_compliance_officer(&self) -> Option<AccountId> {
+        self.compliance_officer.clone()
+    }
+
+    /// Check whether wallets must be verified before a license can be granted.
+    pub fn is_verification_required(&self) -> bool {
+        self.verification_required
+    }
+
+    /// Check whether a wallet must complete verification before it can be licensed.
+    /// Apps use this to decide whether to prompt the user for verification.
+    ///
+    /// # Returns
+    /// `true` if verification is required and the wallet isn't `Verified`, `false` otherwise
+    pub fn needs_verification(&self, wallet_address: String) -> bool {
+        self.verification_required
+            && self.get_compliance_status(wallet_address) != ComplianceStatus::Verified
+    }
+
+    /// Panics if verification is required and the wallet stored under `key` isn't `Verified`
+    pub(crate) fn assert_compliant(&self, key: &str) {
+        require!(
+            !self.verification_required
+                || self.compliance.get(key) == Some(&ComplianceStatus::Verified),
+            "Wallet must complete compliance verification before being licensed"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+
+    fn officer() -> AccountId {
+        "compliance.near".parse().unwrap()
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_compliance_officer(Some(officer()));
+        contract
+    }
+
+    #[test]
+    fn test_set_compliance_status() {
+        let mut contract = setup_contract();
+        assert_eq!(contract.get_compliance_status(user_str()), ComplianceStatus::Unverified);
+
+        setup_context(&officer(), 0);
+        contract.set_compliance_status(user_str(), ComplianceStatus::Verified);
+        assert_eq!(contract.get_compliance_status(user_str()), ComplianceStatus::Verified);
+
+        contract.set_compliance_status(user_str(), ComplianceStatus::Unverified);
+        assert_eq!(contract.get_compliance_status(user_str()), ComplianceStatus::Unverified);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only compliance officer can set compliance status")]
+    fn test_set_compliance_status_unauthorized() {
+        let mut contract = setup_contract();
+
+        // Even the admin can't set compliance statuses
+        contract.set_compliance_status(user_str(), ComplianceStatus::Verified);
+    }
+
+    #[test]
+    fn test_verification_required_for_grant() {
+        let mut contract = setup_contract();
+        contract.set_verification_required(true);
+        assert!(contract.needs_verification(user_str()));
+
+        setup_context(&officer(), 0);
+        contract.set_compliance_status(user_str(), ComplianceStatus::Verified);
+        assert!(!contract.needs_verification(user_str()));
+
+        setup_context(&admin(), 0);
+        contract.grant_license(user_str(), 30, None, None);
+        assert!(contract.is_licensed(user_str()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Wallet must complete compliance verification before being licensed")]
+    fn test_grant_rejected_for_unverified_wallet() {
+        let mut contract = setup_contract();
+        contract.set_verification_required(true);
+
+        setup_context(&officer(), 0);
+        contract.set_compliance_status(user_str(), ComplianceStatus::Pending);
+
+        setup_context(&admin(), 0);
+        contract.grant_license(user_str(), 30, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_set_compliance_officer_unauthorized() {
+        let mut contract = setup_contract();
+
+        setup_context(&user(), 0);
+        contract.set_compliance_officer(Some(user()));
+    }
+}
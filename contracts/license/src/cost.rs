@@ -0,0 +1,198 @@
+use near_sdk::{near, AccountId, env, Gas, NearToken};
+
+use crate::{LicenseContract, LicenseContractExt, ONE_DAY_NS};
+
+/// Storage the runtime charges per record on top of its key and value
+const RECORD_OVERHEAD_BYTES: i64 = 40;
+/// Storage of a day-keyed counter record (one-byte prefix, u64 day, u64 count)
+const BUCKET_RECORD_BYTES: i64 = 1 + 8 + 8 + RECORD_OVERHEAD_BYTES;
+/// Gas budget for a grant, excluding rolling the activity counters forward
+const GRANT_BASE_GAS: Gas = Gas::from_tgas(10);
+/// Gas budget for closing out one day of activity counters
+const ROLL_DAY_GAS: Gas = Gas::from_ggas(500);
+
+/// Projected cost of a single license grant.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GrantCostEstimate {
+    /// Net storage the grant adds, in bytes
+    pub storage_bytes: u64,
+    /// Cost of `storage_bytes` at the current storage price
+    pub storage_cost: NearToken,
+    /// Gas budget to attach to the grant call
+    pub gas: Gas,
+}
+
+/// Storage taken by a record with a key and value of the given borsh-serialized lengths
+fn record_bytes(key_len: usize, value_len: usize) -> i64 {
+    key_len as i64 + value_len as i64 + RECORD_OVERHEAD_BYTES
+}
+
+/// Borsh-serialized length of a string
+fn string_len(s: &str) -> usize {
+    4 + s.len()
+}
+
+/// Borsh-serialized length of a provenance entry without a memo: granter, source,
+/// timestamp and the absent memo
+fn provenance_len(granted_by: &AccountId) -> usize {
+    string_len(granted_by.as_str()) + 1 + 8 + 1
+}
+
+#[near]
+impl LicenseContract {
+    /// Estimate the storage and gas of granting a license with `grant_license`,
+    /// e.g. to budget a bulk import before running it. The estimate reflects the
+    /// current state: costs differ for wallets that were licensed before.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet address the license would be granted to
+    /// * `duration_days` - Number of days the license would be granted for
+    pub fn estimate_grant_cost(&self, wallet_address: String, duration_days: u32) -> GrantCostEstimate {
+        let key = self.wallet_key(&wallet_address);
+        let now = env::block_timestamp();
+        let today = now / ONE_DAY_NS;
+        let mut bytes = 0i64;
+
+        // Activity counters roll forward to today, recording each skipped day and
+        // dropping its expiry bucket
+        let rolled_days = today.saturating_sub(self.activity_day);
+        for day in self.activity_day..today {
+            bytes += BUCKET_RECORD_BYTES;
+            if self.expiry_buckets.contains_key(&day) {
+                bytes -= BUCKET_RECORD_BYTES;
+            }
+        }
+
+        // New or replaced license entry, and the expiry bucket it moves between
+        let existing = self.licenses.get(&key).copied();
+        let old_expiry = existing.filter(|&expiry| expiry > now);
+        let duration_ns = (duration_days as u64).saturating_mul(ONE_DAY_NS);
+        let new_expiry = old_expiry.unwrap_or(now).saturating_add(duration_ns);
+        if existing.is_none() {
+            bytes += record_bytes(1 + string_len(&key), 8);
+        }
+        let old_bucket = old_expiry.map(|expiry| (expiry - 1) / ONE_DAY_NS);
+        let old_bucket_emptied = old_bucket.is_some_and(|day| self.expiry_buckets.get(&day) == Some(&1));
+        if old_bucket_emptied {
+            bytes -= BUCKET_RECORD_BYTES;
+        }
+        let new_bucket = (new_expiry - 1) / ONE_DAY_NS;
+        if !self.expiry_buckets.contains_key(&new_bucket) || (old_bucket == Some(new_bucket) && old_bucket_emptied) {
+            bytes += BUCKET_RECORD_BYTES;
+        }
+
+        // Export index entries for a first-time wallet
+        if !self.wallet_positions.contains_key(&key) {
+            bytes += record_bytes(1 + 4, string_len(&key));
+            bytes += record_bytes(1 + string_len(&key), 4);
+        }
+
+        // Serial number of a new license, indexed both ways
+        if !self.serials.contains_key(&key) {
+            bytes += record_bytes(1 + string_len(&key), 8);
+            bytes += record_bytes(1 + 8, string_len(&key));
+        }
+
+        // Tenure aggregates for a wallet that never held a timestamp license
+        if !self.tenures.contains_key(&key) {
+            bytes += record_bytes(1 + string_len(&key), 4 * 8);
+        }
+
+        // Credential anchor: subject, hex sha256, issuance timestamp, revoked flag, no memo.
+        // An unrevoked credential is superseded in place
+        let live_credential = self
+            .wallet_credentials
+            .get(&key)
+            .is_some_and(|&index| self.credentials.get(index).is_some_and(|anchor| !anchor.revoked));
+        if !live_credential {
+            bytes += record_bytes(1 + 4, string_len(&key) + (4 + 64) + 8 + 1 + 1);
+        }
+        if !self.wallet_credentials.contains_key(&key) {
+            bytes += record_bytes(1 + string_len(&key), 4);
+        }
+
+        // Provenance naming the admin, replacing any previous entry; memos aren't estimated
+        bytes += match self.provenance.get(&key) {
+            Some(previous) => provenance_len(&self.admin) as i64 - provenance_len(&previous.granted_by) as i64,
+            None => record_bytes(1 + string_len(&key), provenance_len(&self.admin)),
+        };
+
+        let storage_bytes = bytes.max(0) as u64;
+        GrantCostEstimate {
+            storage_bytes,
+            storage_cost: env::storage_byte_cost().saturating_mul(storage_bytes as u128),
+            gas: GRANT_BASE_GAS.saturating_add(ROLL_DAY_GAS.saturating_mul(rolled_days)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user_str};
+    use crate::Network;
+    use near_sdk::json_types::Base64VecU8;
+
+    /// Grant a license and check the estimate against the storage actually used
+    fn assert_estimate_matches(contract: &mut LicenseContract, wallet_address: String, duration_days: u32) {
+        let estimate = contract.estimate_grant_cost(wallet_address.clone(), duration_days);
+
+        let before = env::storage_usage();
+        contract.grant_license(wallet_address, duration_days, None, None);
+        contract.licenses.flush();
+        contract.wallets.flush();
+        contract.wallet_positions.flush();
+        contract.credentials.flush();
+        contract.wallet_credentials.flush();
+        contract.expiry_buckets.flush();
+        contract.daily_active.flush();
+        contract.provenance.flush();
+        contract.tenures.flush();
+        contract.serials.flush();
+        contract.serial_wallets.flush();
+
+        assert_eq!(estimate.storage_bytes, env::storage_usage() - before);
+        assert_eq!(
+            estimate.storage_cost,
+            env::storage_byte_cost().saturating_mul(estimate.storage_bytes as u128)
+        );
+    }
+
+    #[test]
+    fn test_estimate_matches_storage_used() {
+        setup_context(&admin(), 10 * ONE_DAY_NS);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        // First-time wallet
+        assert_estimate_matches(&mut contract, user_str(), 30);
+        // Extension into a new bucket, emptying the old one
+        assert_estimate_matches(&mut contract, user_str(), 30);
+        // Another wallet expiring on a day that already has a bucket
+        assert_estimate_matches(&mut contract, "other.near".to_string(), 60);
+
+        // Days skipped since the last grant are closed out
+        setup_context(&admin(), 15 * ONE_DAY_NS);
+        let estimate = contract.estimate_grant_cost("third.near".to_string(), 1);
+        assert_eq!(estimate.gas, GRANT_BASE_GAS.saturating_add(ROLL_DAY_GAS.saturating_mul(5)));
+        assert_estimate_matches(&mut contract, "third.near".to_string(), 1);
+    }
+
+    #[test]
+    fn test_estimate_longest_duration_does_not_overflow() {
+        setup_context(&admin(), ONE_DAY_NS);
+        let contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        let estimate = contract.estimate_grant_cost(user_str(), u32::MAX);
+        assert!(estimate.storage_bytes > 0);
+    }
+
+    #[test]
+    fn test_estimate_in_privacy_mode() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.enable_privacy_mode(Base64VecU8::from(vec![7u8; 16]));
+
+        assert_estimate_matches(&mut contract, user_str(), 30);
+    }
+}
@@ -0,0 +1,300 @@
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde_json::json;
+use near_sdk::{near, env, require};
+
+use crate::events::LicenseEvent;
+use crate::privacy::to_hex;
+use crate::provenance::assert_valid_memo;
+use crate::{LicenseContract, LicenseContractExt, Permission};
+
+/// Maximum number of status list entries returned per page (one byte holds eight)
+const MAX_STATUS_LIST_PAGE: u32 = 8 * 1024;
+
+/// On-chain anchor of an issued license credential.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CredentialAnchor {
+    /// Wallet address (or hash, in privacy mode) the credential was issued to
+    pub subject: String,
+    /// Hex-encoded sha256 of the credential JSON emitted at issuance
+    pub credential_hash: String,
+    /// Issuance timestamp in nanoseconds
+    pub issued_at: u64,
+    pub revoked: bool,
+    /// Justification supplied with the revocation, e.g. a ticket number
+    pub revocation_memo: Option<String>,
+}
+
+/// Format a nanosecond timestamp as an ISO 8601 UTC date-time (e.g. "2026-01-18T00:00:00Z")
+fn format_iso8601(timestamp_ns: u64) -> String {
+    let secs = timestamp_ns / 1_000_000_000;
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since the Unix epoch (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60
+    )
+}
+
+#[near]
+impl LicenseContract {
+    /// Revoke a previously issued credential in the status list.
+    ///
+    /// # Arguments
+    /// * `status_index` - Status list index of the credential
+    /// * `memo` - Optional justification (e.g. a support ticket) stored with the credential
+    ///
+    /// # Panics
+    /// Panics if caller may not revoke credentials, no credential exists at the index
+    /// or the memo is too long
+    pub fn revoke_credential(&mut self, status_index: u32, memo: Option<String>) {
+        self.assert_permitted(Permission::RevokeCredentials);
+        assert_valid_memo(&memo);
+
+        let anchor = self
+            .credentials
+            .get_mut(status_index)
+            .unwrap_or_else(|| env::panic_str("Unknown credential"));
+        if !anchor.revoked {
+            anchor.revoked = true;
+            self.metrics.revocations += 1;
+        }
+        anchor.revocation_memo = memo.clone();
+
+        LicenseEvent::CredentialRevoked { status_index, memo }.emit();
+    }
+
+    /// Get the on-chain anchor of a credential.
+    ///
+    /// # Arguments
+    /// * `status_index` - Status list index of the credential
+    pub fn get_credential(&self, status_index: u32) -> Option<CredentialAnchor> {
+        self.credentials.get(status_index).cloned()
+    }
+
+    /// Get the number of status list entries, i.e. the index the next new credential will get.
+    pub fn get_credential_count(&self) -> u32 {
+        self.credentials.len()
+    }
+
+    /// Get a page of the revocation status list as a bitstring, following the
+    /// W3C Bitstring Status List layout (most significant bit first, 1 = revoked)
+    /// but uncompressed.
+    ///
+    /// # Arguments
+    /// * `from_index` - First status index to include; must be a multiple of 8
+    /// * `limit` - Number of entries to include (capped at 8192)
+    ///
+    /// # Panics
+    /// Panics if `from_index` isn't byte-aligned
+    pub fn get_status_list(&self, from_index: u32, limit: u32) -> Base64VecU8 {
+        require!(from_index & 7 == 0, "from_index must be a multiple of 8");
+
+        let end = self.credentials.len().min(from_index.saturating_add(limit.min(MAX_STATUS_LIST_PAGE)));
+        let mut bits = vec![0u8; (end.saturating_sub(from_index) as usize).div_ceil(8)];
+        for index in from_index..end {
+            if self.credentials.get(index).is_some_and(|anchor| anchor.revoked) {
+                let offset = (index - from_index) as usize;
+                bits[offset / 8] |= 0x80 >> (offset % 8);
+            }
+        }
+        Base64VecU8(bits)
+    }
+
+    /// Issue a license credential for the wallet stored under `key`, anchoring its
+    /// hash on-chain and emitting the full credential in an event. A wallet's
+    /// unrevoked credential is superseded under the same status index, so the status
+    /// list grows by one entry per license rather than per extension.
+    pub(crate) fn issue_credential(&mut self, key: &str, expiry: u64) {
+        let superseded = self
+            .wallet_credentials
+            .get(key)
+            .copied()
+            .filter(|&index| self.credentials.get(index).is_some_and(|anchor| !anchor.revoked));
+        let status_index = superseded.unwrap_or(self.credentials.len());
+        let contract_id = env::current_account_id();
+        let issued_at = env::block_timestamp();
+
+        let credential = json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "type": ["VerifiableCredential", "HopperLicenseCredential"],
+            "issuer": format!("did:near:{}", contract_id),
+            "validFrom": format_iso8601(issued_at),
+            "validUntil": format_iso8601(expiry),
+            "credentialSubject": {
+                "id": key,
+                "licenseContract": contract_id,
+                "expiresAt": expiry,
+            },
+            "credentialStatus": {
+                "type": "BitstringStatusListEntry",
+                "statusPurpose": "revocation",
+                "statusListIndex": status_index.to_string(),
+                "statusListCredential": format!("near:{}/get_status_list", contract_id),
+            },
+        })
+        .to_string();
+        let credential_hash = to_hex(&env::sha256(credential.as_bytes()));
+
+        let anchor = CredentialAnchor {
+            subject: key.to_string(),
+            credential_hash: credential_hash.clone(),
+            issued_at,
+            revoked: false,
+            revocation_memo: None,
+        };
+        match superseded.and_then(|index| self.credentials.get_mut(index)) {
+            Some(previous) => *previous = anchor,
+            None => {
+                self.credentials.push(anchor);
+                self.wallet_credentials.insert(key.to_string(), status_index);
+            }
+        }
+
+        LicenseEvent::CredentialIssued {
+            status_index,
+            credential_hash,
+            credential,
+        }
+        .emit();
+    }
+
+    /// Revoke the latest credential of the wallet stored under `key`, e.g. once its
+    /// license left the wallet, so it no longer verifies offline
+    pub(crate) fn revoke_wallet_credential(&mut self, key: &str, memo: &str) {
+        let Some(status_index) = self.wallet_credentials.remove(key) else {
+            return;
+        };
+        if let Some(anchor) = self.credentials.get_mut(status_index).filter(|anchor| !anchor.revoked) {
+            anchor.revoked = true;
+            anchor.revocation_memo = Some(memo.to_string());
+            self.metrics.revocations += 1;
+            LicenseEvent::CredentialRevoked {
+                status_index,
+                memo: Some(memo.to_string()),
+            }
+            .emit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::{Network, ONE_DAY_NS};
+    use near_sdk::test_utils::get_logs;
+
+    #[test]
+    fn test_format_iso8601() {
+        assert_eq!(format_iso8601(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_iso8601(1_768_694_400 * 1_000_000_000), "2026-01-18T00:00:00Z");
+        assert_eq!(format_iso8601(951_827_696 * 1_000_000_000), "2000-02-29T12:34:56Z");
+    }
+
+    #[test]
+    fn test_grant_issues_credential() {
+        setup_context(&admin(), 1_000_000_000);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license(user_str(), 30, None, None);
+
+        let anchor = contract.get_credential(0).unwrap();
+        assert_eq!(anchor.subject, user_str());
+        assert!(!anchor.revoked);
+
+        // The anchored hash matches the credential carried by the event,
+        // which precedes the license_granted event
+        let log = get_logs()[0].clone();
+        let event: near_sdk::serde_json::Value =
+            near_sdk::serde_json::from_str(log.strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+        assert_eq!(event["event"], "credential_issued");
+        let credential = event["data"]["credential"].as_str().unwrap();
+        assert_eq!(to_hex(&env::sha256(credential.as_bytes())), anchor.credential_hash);
+
+        let credential: near_sdk::serde_json::Value = near_sdk::serde_json::from_str(credential).unwrap();
+        assert_eq!(credential["credentialSubject"]["expiresAt"], 1_000_000_000 + 30 * ONE_DAY_NS);
+    }
+
+    #[test]
+    fn test_extension_supersedes_credential() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+        let first = contract.get_credential(0).unwrap();
+
+        contract.grant_license(user_str(), 30, None, None);
+        assert_eq!(contract.get_credential_count(), 1);
+        assert_ne!(contract.get_credential(0).unwrap().credential_hash, first.credential_hash);
+
+        // A revoked credential is kept, and the next grant issues a new one
+        contract.revoke_credential(0, None);
+        contract.grant_license(user_str(), 30, None, None);
+        assert_eq!(contract.get_credential_count(), 2);
+        assert!(contract.get_credential(0).unwrap().revoked);
+        assert!(!contract.get_credential(1).unwrap().revoked);
+    }
+
+    #[test]
+    fn test_transfer_revokes_sender_credential() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+
+        setup_context(&user(), 0);
+        contract.transfer_license("new.near".to_string());
+
+        let revoked = contract.get_credential(0).unwrap();
+        assert!(revoked.revoked);
+        assert_eq!(revoked.revocation_memo, Some("License moved to another wallet".to_string()));
+        let issued = contract.get_credential(1).unwrap();
+        assert_eq!(issued.subject, "new.near");
+        assert!(!issued.revoked);
+        assert_eq!(contract.get_status_list(0, 8).0, vec![0b1000_0000]);
+    }
+
+    #[test]
+    fn test_revoke_credential_updates_status_list() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        for i in 0..10 {
+            contract.grant_license(format!("wallet{}.near", i), 1, None, None);
+        }
+        assert_eq!(contract.get_credential_count(), 10);
+
+        contract.revoke_credential(1, Some("SUP-7".to_string()));
+        contract.revoke_credential(9, None);
+
+        assert!(contract.get_credential(1).unwrap().revoked);
+        assert_eq!(contract.get_credential(1).unwrap().revocation_memo, Some("SUP-7".to_string()));
+        assert_eq!(contract.get_status_list(0, 100).0, vec![0b0100_0000, 0b0100_0000]);
+        assert_eq!(contract.get_status_list(8, 100).0, vec![0b0100_0000]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_revoke_credential_unauthorized() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 1, None, None);
+
+        setup_context(&user(), 0);
+        contract.revoke_credential(0, None);
+    }
+}
@@ -0,0 +1,178 @@
+use near_sdk::{near, AccountId, env, require};
+
+use crate::{LicenseContract, LicenseContractExt};
+
+/// DID method prefix for NEAR account DIDs
+const DID_NEAR_PREFIX: &str = "did:near:";
+/// Maximum number of wallets that can be linked to a single DID
+const MAX_WALLETS_PER_DID: usize = 10;
+
+/// Resolve a `did:near:<account_id>` DID to its controlling account.
+///
+/// # Returns
+/// `Some(account_id)` for a well-formed `did:near` DID, `None` otherwise
+pub fn parse_did_near(did: &str) -> Option<AccountId> {
+    did.strip_prefix(DID_NEAR_PREFIX)?.parse().ok()
+}
+
+#[near]
+impl LicenseContract {
+    /// Link a wallet to a DID, so the wallet is licensed whenever the DID is.
+    /// Licenses are granted to the DID itself (e.g. `grant_license("did:near:alice.near", 30)`).
+    ///
+    /// # Arguments
+    /// * `did` - A `did:near:<account_id>` DID controlled by the caller
+    /// * `wallet_address` - The wallet address to link
+    ///
+    /// # Panics
+    /// Panics if caller doesn't control the DID, the wallet is already linked to
+    /// a DID, or the DID already has the maximum number of linked wallets
+    pub fn link_wallet_to_did(&mut self, did: String, wallet_address: String) {
+        self.assert_not_shut_down();
+        Self::assert_did_controller(&did);
+
+        let did_key = self.wallet_key(&did);
+        let wallet_key = self.wallet_key(&wallet_address);
+        require!(
+            !self.wallet_dids.contains_key(&wallet_key),
+            "Wallet is already linked to a DID"
+        );
+
+        let mut wallets = self.did_wallets.get(&did_key).cloned().unwrap_or_default();
+        require!(
+            wallets.len() < MAX_WALLETS_PER_DID,
+            "DID has reached the maximum number of linked wallets"
+        );
+        wallets.push(wallet_key.clone());
+
+        self.did_wallets.insert(did_key.clone(), wallets);
+        self.wallet_dids.insert(wallet_key, did_key);
+    }
+
+    /// Unlink a wallet from a DID.
+    ///
+    /// # Arguments
+    /// * `did` - A `did:near:<account_id>` DID controlled by the caller
+    /// * `wallet_address` - The wallet address to unlink
+    ///
+    /// # Panics
+    /// Panics if caller doesn't control the DID or the wallet isn't linked to it
+    pub fn unlink_wallet_from_did(&mut self, did: String, wallet_address: String) {
+        self.assert_not_shut_down();
+        Self::assert_did_controller(&did);
+
+        let did_key = self.wallet_key(&did);
+        let wallet_key = self.wallet_key(&wallet_address);
+        require!(
+            self.wallet_dids.get(&wallet_key) == Some(&did_key),
+            "Wallet is not linked to this DID"
+        );
+
+        let mut wallets = self.did_wallets.get(&did_key).cloned().unwrap_or_default();
+        wallets.retain(|linked| linked != &wallet_key);
+        if wallets.is_empty() {
+            self.did_wallets.remove(&did_key);
+        } else {
+            self.did_wallets.insert(did_key, wallets);
+        }
+        self.wallet_dids.remove(&wallet_key);
+    }
+
+    /// Resolve a DID to the wallets linked to it.
+    ///
+    /// # Returns
+    /// The linked wallet addresses (hashes in privacy mode)
+    pub fn resolve_did(&self, did: String) -> Vec<String> {
+        self.did_wallets.get(&self.wallet_key(&did)).cloned().unwrap_or_default()
+    }
+
+    /// Get the DID a wallet is linked to, if any.
+    ///
+    /// # Returns
+    /// The DID (its hash in privacy mode), or `None` if the wallet isn't linked
+    pub fn get_wallet_did(&self, wallet_address: String) -> Option<String> {
+        self.wallet_dids.get(&self.wallet_key(&wallet_address)).cloned()
+    }
+
+    /// Get the account controlling a `did:near` DID.
+    ///
+    /// # Returns
+    /// `Some(account_id)` for a well-formed `did:near` DID, `None` otherwise
+    pub fn get_did_controller(did: String) -> Option<AccountId> {
+        parse_did_near(&did)
+    }
+
+    /// Check if the DID linked to the wallet stored under `key` is licensed
+    pub(crate) fn is_linked_did_licensed(&self, key: &str) -> bool {
+        self.wallet_dids
+            .get(key)
+            .map(|did_key| self.is_key_licensed(did_key))
+            .unwrap_or(false)
+    }
+
+    /// Panics unless the caller controls the given `did:near` DID
+    fn assert_did_controller(did: &str) {
+        let controller = parse_did_near(did)
+            .unwrap_or_else(|| env::panic_str("Only did:near DIDs are supported"));
+        require!(
+            env::predecessor_account_id() == controller,
+            "Unauthorized: only the DID controller can manage linked wallets"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, evm_address, setup_context, user, user_str};
+    use crate::Network;
+
+    fn did() -> String {
+        "did:near:user.near".to_string()
+    }
+
+    #[test]
+    fn test_parse_did_near() {
+        assert_eq!(parse_did_near(&did()), Some(user()));
+        assert_eq!(parse_did_near("did:key:z6Mk"), None);
+        assert_eq!(parse_did_near("did:near:NOT VALID"), None);
+    }
+
+    #[test]
+    fn test_linked_wallet_inherits_did_license() {
+        setup_context(&admin(), 1_000_000_000);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(did(), 30, None, None);
+        assert!(contract.is_licensed(did()));
+        assert!(!contract.is_licensed(evm_address()));
+
+        setup_context(&user(), 1_000_000_000);
+        contract.link_wallet_to_did(did(), evm_address());
+        assert_eq!(contract.resolve_did(did()), vec![evm_address()]);
+        assert_eq!(contract.get_wallet_did(evm_address()), Some(did()));
+        assert!(contract.is_licensed(evm_address()));
+
+        contract.unlink_wallet_from_did(did(), evm_address());
+        assert!(contract.resolve_did(did()).is_empty());
+        assert!(!contract.is_licensed(evm_address()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only the DID controller can manage linked wallets")]
+    fn test_link_requires_controller() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.link_wallet_to_did(did(), user_str());
+    }
+
+    #[test]
+    #[should_panic(expected = "Wallet is already linked to a DID")]
+    fn test_wallet_linked_once() {
+        setup_context(&user(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.link_wallet_to_did(did(), evm_address());
+        contract.link_wallet_to_did(did(), evm_address());
+    }
+}
@@ -0,0 +1,781 @@
+use near_sdk::{near, AccountId, NearToken};
+
+use crate::{AdminAction, ExpiryKind, Permission, ReceiptKind, Role};
+
+/// NEP-297 standard name of every license contract event
+pub const EVENT_STANDARD: &str = "hopper_license";
+
+/// Current version of the license event schema.
+///
+/// Compatibility guarantees for indexers:
+/// - Within a major version, event names and existing data fields are never renamed,
+///   removed or retyped; adding fields to an event only bumps the minor version.
+/// - New events start out at the current version.
+/// - Any breaking change bumps the major version of the affected events.
+///
+/// The `event_version` attributes below must match this constant, which the
+/// schema tests check.
+///
+/// Changes:
+/// - 1.1.0: `memo` added to `license_granted` and `credential_revoked`
+pub const EVENT_VERSION: &str = "1.1.0";
+
+/// Events emitted by the license contract, formatted according to NEP-297.
+/// Field names are part of the schema, see `EVENT_VERSION`.
+#[near(event_json(standard = "hopper_license"))]
+pub enum LicenseEvent {
+    /// The list of blocked jurisdictions was replaced
+    #[event_version("1.1.0")]
+    BlockedJurisdictionsUpdated {
+        blocked_jurisdictions: Vec<String>,
+        updated_by: AccountId,
+    },
+
+    /// A wallet's attested jurisdiction was set or cleared
+    #[event_version("1.1.0")]
+    WalletJurisdictionSet {
+        wallet_address: String,
+        jurisdiction: Option<String>,
+        attested_by: AccountId,
+    },
+
+    /// A license credential was issued; its sha256 is anchored on-chain
+    #[event_version("1.1.0")]
+    CredentialIssued {
+        status_index: u32,
+        credential_hash: String,
+        credential: String,
+    },
+
+    /// A license credential was revoked in the status list
+    #[event_version("1.1.0")]
+    CredentialRevoked { status_index: u32, memo: Option<String> },
+
+    /// A guardian proposed moving a wallet's license to a new wallet
+    #[event_version("1.1.0")]
+    RecoveryProposed {
+        wallet_address: String,
+        new_wallet: String,
+        guardian: AccountId,
+    },
+
+    /// A guardian approved a pending recovery
+    #[event_version("1.1.0")]
+    RecoveryApproved {
+        wallet_address: String,
+        new_wallet: String,
+        guardian: AccountId,
+    },
+
+    /// A recovery was executed and the license moved to the new wallet
+    #[event_version("1.1.0")]
+    RecoveryExecuted {
+        wallet_address: String,
+        new_wallet: String,
+    },
+
+    /// The wallet owner cancelled a pending recovery
+    #[event_version("1.1.0")]
+    RecoveryCancelled { wallet_address: String },
+
+    /// A license was granted or extended. `previous_expiry` is the stored expiry before
+    /// the grant, even if it had already passed; both expiries are in the unit of `expiry_kind`.
+    /// `serial` is the license's serial number, absent for commitment licenses
+    #[event_version("1.1.0")]
+    LicenseGranted {
+        wallet_address: String,
+        expiry_kind: ExpiryKind,
+        previous_expiry: Option<u64>,
+        new_expiry: u64,
+        serial: Option<u64>,
+        correlation_id: Option<String>,
+        memo: Option<String>,
+    },
+
+    /// An opted-in wallet's timestamp license is about to expire
+    #[event_version("1.1.0")]
+    LicenseExpiringSoon {
+        wallet_address: String,
+        expiry: u64,
+        preference_hash: String,
+    },
+
+    /// A holder transferred their license to another wallet. `serial` is the license's
+    /// serial number, absent for licenses migrated before serials were assigned
+    #[event_version("1.1.0")]
+    LicenseTransferred {
+        from: String,
+        to: String,
+        serial: Option<u64>,
+        fee: NearToken,
+    },
+
+    /// A holder listed their license for resale
+    #[event_version("1.1.0")]
+    LicenseListed { wallet_address: String, price: NearToken },
+
+    /// A holder withdrew their license from sale
+    #[event_version("1.1.0")]
+    LicenseDelisted { wallet_address: String },
+
+    /// A listed license was bought
+    #[event_version("1.1.0")]
+    LicenseSold {
+        from: String,
+        to: String,
+        price: NearToken,
+        royalty: NearToken,
+    },
+
+    /// A license was lent out for a fixed term
+    #[event_version("1.1.0")]
+    LicenseRented {
+        lender: String,
+        renter: String,
+        ends_at: u64,
+        payment: NearToken,
+    },
+
+    /// The payment held for an ended rental was paid out to the lender
+    #[event_version("1.1.0")]
+    RentalSettled {
+        lender: String,
+        renter: String,
+        payment: NearToken,
+    },
+
+    /// A lending contract placed a lien on a license
+    #[event_version("1.1.0")]
+    LienPlaced { wallet_address: String, lien_holder: AccountId },
+
+    /// A lending contract released its lien on a license
+    #[event_version("1.1.0")]
+    LienReleased { wallet_address: String, lien_holder: AccountId },
+
+    /// A holder approved an operator to transfer their license
+    #[event_version("1.1.0")]
+    OperatorApproved {
+        wallet_address: String,
+        operator_id: AccountId,
+        approval_id: u64,
+    },
+
+    /// A holder revoked an operator's approval to transfer their license
+    #[event_version("1.1.0")]
+    OperatorRevoked { wallet_address: String, operator_id: AccountId },
+
+    /// An app feature was turned on or off
+    #[event_version("1.1.0")]
+    FeatureUpdated { feature_id: String, enabled: bool },
+
+    /// An app feature was rolled out to a share of licensed wallets
+    #[event_version("1.1.0")]
+    FeatureRolloutUpdated { feature_id: String, rollout_bps: u16 },
+
+    /// A holder destroyed their license
+    #[event_version("1.1.0")]
+    LicenseBurned { wallet_address: String },
+
+    /// License transfers were frozen or unfrozen
+    #[event_version("1.1.0")]
+    TransferFreezeChanged { frozen: bool, changed_by: AccountId },
+
+    /// A monetary operation was recorded under a receipt ID
+    #[event_version("1.1.0")]
+    ReceiptIssued {
+        receipt_id: u64,
+        kind: ReceiptKind,
+        account: AccountId,
+        amount: NearToken,
+    },
+
+    /// The admin or another privileged role changed hands
+    #[event_version("1.1.0")]
+    RoleChanged {
+        role: Role,
+        account: Option<AccountId>,
+        changed_by: AccountId,
+    },
+
+    /// An admin action was queued behind the action delay
+    #[event_version("1.1.0")]
+    ActionQueued {
+        action_id: u64,
+        action: AdminAction,
+        executable_at: u64,
+    },
+
+    /// A queued admin action was executed
+    #[event_version("1.1.0")]
+    ActionExecuted { action_id: u64 },
+
+    /// A queued admin action was withdrawn by the admin
+    #[event_version("1.1.0")]
+    ActionCancelled { action_id: u64, cancelled_by: AccountId },
+
+    /// The guardian vetoed a queued admin action
+    #[event_version("1.1.0")]
+    ActionVetoed {
+        action_id: u64,
+        vetoed_by: AccountId,
+        reason: Option<String>,
+    },
+
+    /// The contract was permanently shut down
+    #[event_version("1.1.0")]
+    ContractShutDown { shut_down_by: AccountId, recovery_account: AccountId },
+
+    /// The contract was paused or unpaused
+    #[event_version("1.1.0")]
+    PauseChanged { paused: bool, changed_by: AccountId },
+
+    /// The admin proposed a grant longer than the long grant threshold
+    #[event_version("1.1.0")]
+    LongGrantProposed {
+        grant_id: u64,
+        wallet_address: String,
+        duration_days: u32,
+    },
+
+    /// The grant co-signer approved a long grant
+    #[event_version("1.1.0")]
+    LongGrantApproved { grant_id: u64, approved_by: AccountId },
+
+    /// A pending long grant was withdrawn
+    #[event_version("1.1.0")]
+    LongGrantCancelled { grant_id: u64, cancelled_by: AccountId },
+
+    /// The roles allowed to perform an operation were changed
+    #[event_version("1.1.0")]
+    PermissionChanged { permission: Permission, roles: Vec<Role> },
+
+    /// A floating pool was created or its terms changed
+    #[event_version("1.1.0")]
+    PoolUpdated {
+        pool_id: u64,
+        manager: AccountId,
+        seats: u32,
+        expires_at: u64,
+    },
+
+    /// A wallet checked out a seat of a floating pool
+    #[event_version("1.1.0")]
+    SeatCheckedOut { pool_id: u64, wallet_address: String },
+
+    /// A seat of a floating pool was checked in
+    #[event_version("1.1.0")]
+    SeatCheckedIn { pool_id: u64, wallet_address: String },
+
+    /// A stale seat of a floating pool was taken back from its holder
+    #[event_version("1.1.0")]
+    SeatReclaimed {
+        pool_id: u64,
+        wallet_address: String,
+        reclaimed_by: AccountId,
+    },
+
+    /// A new license was given its serial number
+    #[event_version("1.1.0")]
+    SerialAssigned { serial: u64, wallet_address: String },
+
+    /// A payment receipt was redeemed and can't be redeemed again
+    #[event_version("1.1.0")]
+    ReceiptRedeemed { receipt_id: u64, redeemed_by: AccountId },
+
+    /// The number of role holders that must approve each call to an operation was changed
+    #[event_version("1.1.0")]
+    PermissionThresholdChanged { permission: Permission, threshold: u8 },
+
+    /// A role holder approved a call to an operation that needs several approvals
+    #[event_version("1.1.0")]
+    OperationApproved {
+        permission: Permission,
+        call_hash: String,
+        approved_by: AccountId,
+    },
+
+    /// The grant co-signer approved a change of the co-signer or long grant threshold
+    #[event_version("1.1.0")]
+    QuorumChangeApproved { action: AdminAction, approved_by: AccountId },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user};
+    use near_sdk::serde_json::{self, json, Value};
+    use near_sdk::test_utils::get_logs;
+
+    /// Emit an event and check its envelope, name and exact data fields
+    fn assert_schema(event: LicenseEvent, name: &str, data: Value) {
+        setup_context(&admin(), 0);
+        event.emit();
+
+        let logs = get_logs();
+        let log: Value = serde_json::from_str(logs[0].strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+        assert_eq!(
+            log,
+            json!({
+                "standard": EVENT_STANDARD,
+                "version": EVENT_VERSION,
+                "event": name,
+                "data": data,
+            })
+        );
+    }
+
+    #[test]
+    fn test_jurisdiction_event_schema() {
+        assert_schema(
+            LicenseEvent::BlockedJurisdictionsUpdated {
+                blocked_jurisdictions: vec!["CU".to_string()],
+                updated_by: admin(),
+            },
+            "blocked_jurisdictions_updated",
+            json!({ "blocked_jurisdictions": ["CU"], "updated_by": "admin.near" }),
+        );
+        assert_schema(
+            LicenseEvent::WalletJurisdictionSet {
+                wallet_address: "user.near".to_string(),
+                jurisdiction: None,
+                attested_by: admin(),
+            },
+            "wallet_jurisdiction_set",
+            json!({ "wallet_address": "user.near", "jurisdiction": null, "attested_by": "admin.near" }),
+        );
+    }
+
+    #[test]
+    fn test_credential_event_schema() {
+        assert_schema(
+            LicenseEvent::CredentialIssued {
+                status_index: 3,
+                credential_hash: "ab".to_string(),
+                credential: "{}".to_string(),
+            },
+            "credential_issued",
+            json!({ "status_index": 3, "credential_hash": "ab", "credential": "{}" }),
+        );
+        assert_schema(
+            LicenseEvent::CredentialRevoked {
+                status_index: 3,
+                memo: Some("SUP-7".to_string()),
+            },
+            "credential_revoked",
+            json!({ "status_index": 3, "memo": "SUP-7" }),
+        );
+    }
+
+    #[test]
+    fn test_recovery_event_schema() {
+        assert_schema(
+            LicenseEvent::RecoveryProposed {
+                wallet_address: "lost.near".to_string(),
+                new_wallet: "new.near".to_string(),
+                guardian: user(),
+            },
+            "recovery_proposed",
+            json!({ "wallet_address": "lost.near", "new_wallet": "new.near", "guardian": "user.near" }),
+        );
+        assert_schema(
+            LicenseEvent::RecoveryApproved {
+                wallet_address: "lost.near".to_string(),
+                new_wallet: "new.near".to_string(),
+                guardian: user(),
+            },
+            "recovery_approved",
+            json!({ "wallet_address": "lost.near", "new_wallet": "new.near", "guardian": "user.near" }),
+        );
+        assert_schema(
+            LicenseEvent::RecoveryExecuted {
+                wallet_address: "lost.near".to_string(),
+                new_wallet: "new.near".to_string(),
+            },
+            "recovery_executed",
+            json!({ "wallet_address": "lost.near", "new_wallet": "new.near" }),
+        );
+        assert_schema(
+            LicenseEvent::RecoveryCancelled { wallet_address: "lost.near".to_string() },
+            "recovery_cancelled",
+            json!({ "wallet_address": "lost.near" }),
+        );
+    }
+
+    #[test]
+    fn test_grant_event_schema() {
+        assert_schema(
+            LicenseEvent::LicenseGranted {
+                wallet_address: "user.near".to_string(),
+                expiry_kind: ExpiryKind::Height,
+                previous_expiry: None,
+                new_expiry: 100,
+                serial: Some(7),
+                correlation_id: Some("in_123".to_string()),
+                memo: None,
+            },
+            "license_granted",
+            json!({
+                "wallet_address": "user.near",
+                "expiry_kind": "height",
+                "previous_expiry": null,
+                "new_expiry": 100,
+                "serial": 7,
+                "correlation_id": "in_123",
+                "memo": null,
+            }),
+        );
+    }
+
+    #[test]
+    fn test_market_event_schema() {
+        assert_schema(
+            LicenseEvent::LicenseListed {
+                wallet_address: "user.near".to_string(),
+                price: NearToken::from_yoctonear(5),
+            },
+            "license_listed",
+            json!({ "wallet_address": "user.near", "price": "5" }),
+        );
+        assert_schema(
+            LicenseEvent::LicenseDelisted { wallet_address: "user.near".to_string() },
+            "license_delisted",
+            json!({ "wallet_address": "user.near" }),
+        );
+        assert_schema(
+            LicenseEvent::LicenseSold {
+                from: "old.near".to_string(),
+                to: "new.near".to_string(),
+                price: NearToken::from_yoctonear(5),
+                royalty: NearToken::from_yoctonear(1),
+            },
+            "license_sold",
+            json!({ "from": "old.near", "to": "new.near", "price": "5", "royalty": "1" }),
+        );
+    }
+
+    #[test]
+    fn test_rental_event_schema() {
+        assert_schema(
+            LicenseEvent::LicenseRented {
+                lender: "user.near".to_string(),
+                renter: "renter.near".to_string(),
+                ends_at: 100,
+                payment: NearToken::from_yoctonear(5),
+            },
+            "license_rented",
+            json!({ "lender": "user.near", "renter": "renter.near", "ends_at": 100, "payment": "5" }),
+        );
+        assert_schema(
+            LicenseEvent::RentalSettled {
+                lender: "user.near".to_string(),
+                renter: "renter.near".to_string(),
+                payment: NearToken::from_yoctonear(5),
+            },
+            "rental_settled",
+            json!({ "lender": "user.near", "renter": "renter.near", "payment": "5" }),
+        );
+    }
+
+    #[test]
+    fn test_lien_event_schema() {
+        assert_schema(
+            LicenseEvent::LienPlaced {
+                wallet_address: "user.near".to_string(),
+                lien_holder: "lending.near".parse().unwrap(),
+            },
+            "lien_placed",
+            json!({ "wallet_address": "user.near", "lien_holder": "lending.near" }),
+        );
+        assert_schema(
+            LicenseEvent::LienReleased {
+                wallet_address: "user.near".to_string(),
+                lien_holder: "lending.near".parse().unwrap(),
+            },
+            "lien_released",
+            json!({ "wallet_address": "user.near", "lien_holder": "lending.near" }),
+        );
+    }
+
+    #[test]
+    fn test_operator_event_schema() {
+        assert_schema(
+            LicenseEvent::OperatorApproved {
+                wallet_address: "user.near".to_string(),
+                operator_id: "market.near".parse().unwrap(),
+                approval_id: 3,
+            },
+            "operator_approved",
+            json!({ "wallet_address": "user.near", "operator_id": "market.near", "approval_id": 3 }),
+        );
+        assert_schema(
+            LicenseEvent::OperatorRevoked {
+                wallet_address: "user.near".to_string(),
+                operator_id: "market.near".parse().unwrap(),
+            },
+            "operator_revoked",
+            json!({ "wallet_address": "user.near", "operator_id": "market.near" }),
+        );
+    }
+
+    #[test]
+    fn test_role_event_schema() {
+        assert_schema(
+            LicenseEvent::RoleChanged {
+                role: Role::ComplianceOfficer,
+                account: None,
+                changed_by: "admin.near".parse().unwrap(),
+            },
+            "role_changed",
+            json!({ "role": "compliance_officer", "account": null, "changed_by": "admin.near" }),
+        );
+    }
+
+    #[test]
+    fn test_timelock_event_schema() {
+        assert_schema(
+            LicenseEvent::ActionQueued {
+                action_id: 1,
+                action: AdminAction::SetResaleRoyalty { royalty_bps: 500 },
+                executable_at: 10,
+            },
+            "action_queued",
+            json!({ "action_id": 1, "action": { "set_resale_royalty": { "royalty_bps": 500 } }, "executable_at": 10 }),
+        );
+        assert_schema(LicenseEvent::ActionExecuted { action_id: 1 }, "action_executed", json!({ "action_id": 1 }));
+        assert_schema(
+            LicenseEvent::ActionCancelled {
+                action_id: 1,
+                cancelled_by: "admin.near".parse().unwrap(),
+            },
+            "action_cancelled",
+            json!({ "action_id": 1, "cancelled_by": "admin.near" }),
+        );
+        assert_schema(
+            LicenseEvent::ActionVetoed {
+                action_id: 1,
+                vetoed_by: "guardian.near".parse().unwrap(),
+                reason: None,
+            },
+            "action_vetoed",
+            json!({ "action_id": 1, "vetoed_by": "guardian.near", "reason": null }),
+        );
+    }
+
+    #[test]
+    fn test_shutdown_event_schema() {
+        assert_schema(
+            LicenseEvent::ContractShutDown {
+                shut_down_by: "dao.near".parse().unwrap(),
+                recovery_account: "treasury.near".parse().unwrap(),
+            },
+            "contract_shut_down",
+            json!({ "shut_down_by": "dao.near", "recovery_account": "treasury.near" }),
+        );
+    }
+
+    #[test]
+    fn test_feature_event_schema() {
+        assert_schema(
+            LicenseEvent::FeatureUpdated {
+                feature_id: "export".to_string(),
+                enabled: true,
+            },
+            "feature_updated",
+            json!({ "feature_id": "export", "enabled": true }),
+        );
+        assert_schema(
+            LicenseEvent::FeatureRolloutUpdated {
+                feature_id: "beta".to_string(),
+                rollout_bps: 2_500,
+            },
+            "feature_rollout_updated",
+            json!({ "feature_id": "beta", "rollout_bps": 2500 }),
+        );
+    }
+
+    #[test]
+    fn test_burn_event_schema() {
+        assert_schema(
+            LicenseEvent::LicenseBurned { wallet_address: "user.near".to_string() },
+            "license_burned",
+            json!({ "wallet_address": "user.near" }),
+        );
+    }
+
+    #[test]
+    fn test_transfer_freeze_event_schema() {
+        assert_schema(
+            LicenseEvent::TransferFreezeChanged {
+                frozen: true,
+                changed_by: "registry.near".parse().unwrap(),
+            },
+            "transfer_freeze_changed",
+            json!({ "frozen": true, "changed_by": "registry.near" }),
+        );
+    }
+
+    #[test]
+    fn test_receipt_event_schema() {
+        assert_schema(
+            LicenseEvent::ReceiptIssued {
+                receipt_id: 4,
+                kind: ReceiptKind::TransferFee,
+                account: "user.near".parse().unwrap(),
+                amount: NearToken::from_yoctonear(5),
+            },
+            "receipt_issued",
+            json!({ "receipt_id": 4, "kind": "transfer_fee", "account": "user.near", "amount": "5" }),
+        );
+    }
+
+    #[test]
+    fn test_reminder_event_schema() {
+        assert_schema(
+            LicenseEvent::LicenseExpiringSoon {
+                wallet_address: "user.near".to_string(),
+                expiry: 100,
+                preference_hash: "ab".to_string(),
+            },
+            "license_expiring_soon",
+            json!({ "wallet_address": "user.near", "expiry": 100, "preference_hash": "ab" }),
+        );
+    }
+
+    #[test]
+    fn test_transfer_and_pause_event_schema() {
+        assert_schema(
+            LicenseEvent::LicenseTransferred {
+                from: "old.near".to_string(),
+                to: "new.near".to_string(),
+                serial: Some(7),
+                fee: NearToken::from_yoctonear(5),
+            },
+            "license_transferred",
+            json!({ "from": "old.near", "to": "new.near", "serial": 7, "fee": "5" }),
+        );
+        assert_schema(
+            LicenseEvent::PauseChanged { paused: true, changed_by: admin() },
+            "pause_changed",
+            json!({ "paused": true, "changed_by": "admin.near" }),
+        );
+    }
+
+    #[test]
+    fn test_long_grant_event_schema() {
+        assert_schema(
+            LicenseEvent::LongGrantProposed {
+                grant_id: 0,
+                wallet_address: "user.near".to_string(),
+                duration_days: 3650,
+            },
+            "long_grant_proposed",
+            json!({ "grant_id": 0, "wallet_address": "user.near", "duration_days": 3650 }),
+        );
+        assert_schema(
+            LicenseEvent::LongGrantApproved { grant_id: 0, approved_by: "dao.near".parse().unwrap() },
+            "long_grant_approved",
+            json!({ "grant_id": 0, "approved_by": "dao.near" }),
+        );
+        assert_schema(
+            LicenseEvent::LongGrantCancelled { grant_id: 0, cancelled_by: admin() },
+            "long_grant_cancelled",
+            json!({ "grant_id": 0, "cancelled_by": "admin.near" }),
+        );
+        assert_schema(
+            LicenseEvent::QuorumChangeApproved {
+                action: AdminAction::SetLongGrantThreshold { days: Some(365) },
+                approved_by: "dao.near".parse().unwrap(),
+            },
+            "quorum_change_approved",
+            json!({ "action": { "set_long_grant_threshold": { "days": 365 } }, "approved_by": "dao.near" }),
+        );
+    }
+
+    #[test]
+    fn test_permission_event_schema() {
+        assert_schema(
+            LicenseEvent::PermissionChanged {
+                permission: Permission::RevokeCredentials,
+                roles: vec![Role::Admin, Role::Pauser],
+            },
+            "permission_changed",
+            json!({ "permission": "revoke_credentials", "roles": ["admin", "pauser"] }),
+        );
+    }
+
+    #[test]
+    fn test_pool_event_schema() {
+        assert_schema(
+            LicenseEvent::PoolUpdated {
+                pool_id: 0,
+                manager: "org.near".parse().unwrap(),
+                seats: 5,
+                expires_at: 100,
+            },
+            "pool_updated",
+            json!({ "pool_id": 0, "manager": "org.near", "seats": 5, "expires_at": 100 }),
+        );
+        assert_schema(
+            LicenseEvent::SeatCheckedOut { pool_id: 0, wallet_address: "user.near".to_string() },
+            "seat_checked_out",
+            json!({ "pool_id": 0, "wallet_address": "user.near" }),
+        );
+        assert_schema(
+            LicenseEvent::SeatCheckedIn { pool_id: 0, wallet_address: "user.near".to_string() },
+            "seat_checked_in",
+            json!({ "pool_id": 0, "wallet_address": "user.near" }),
+        );
+        assert_schema(
+            LicenseEvent::SeatReclaimed {
+                pool_id: 0,
+                wallet_address: "user.near".to_string(),
+                reclaimed_by: "member.near".parse().unwrap(),
+            },
+            "seat_reclaimed",
+            json!({ "pool_id": 0, "wallet_address": "user.near", "reclaimed_by": "member.near" }),
+        );
+    }
+
+    #[test]
+    fn test_serial_event_schema() {
+        assert_schema(
+            LicenseEvent::SerialAssigned { serial: 1, wallet_address: "user.near".to_string() },
+            "serial_assigned",
+            json!({ "serial": 1, "wallet_address": "user.near" }),
+        );
+    }
+
+    #[test]
+    fn test_receipt_redeemed_event_schema() {
+        assert_schema(
+            LicenseEvent::ReceiptRedeemed { receipt_id: 4, redeemed_by: admin() },
+            "receipt_redeemed",
+            json!({ "receipt_id": 4, "redeemed_by": "admin.near" }),
+        );
+    }
+
+    #[test]
+    fn test_permission_threshold_event_schema() {
+        assert_schema(
+            LicenseEvent::PermissionThresholdChanged {
+                permission: Permission::GrantLicenses,
+                threshold: 2,
+            },
+            "permission_threshold_changed",
+            json!({ "permission": "grant_licenses", "threshold": 2 }),
+        );
+    }
+
+    #[test]
+    fn test_operation_approved_event_schema() {
+        assert_schema(
+            LicenseEvent::OperationApproved {
+                permission: Permission::GrantLicenses,
+                call_hash: "ab".repeat(32),
+                approved_by: admin(),
+            },
+            "operation_approved",
+            json!({ "permission": "grant_licenses", "call_hash": "ab".repeat(32), "approved_by": "admin.near" }),
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use near_sdk::{near, env, require};
+
+use crate::checksum::ExpiryKind;
+use crate::jobs::batch_gas_exhausted;
+use crate::{ComplianceStatus, GasClass, GrantSource, JobKind, LicenseContract, LicenseContractExt};
+
+/// Default number of records returned per export page
+const DEFAULT_EXPORT_LIMIT: u32 = 50;
+/// Maximum number of records returned per export page
+const MAX_EXPORT_LIMIT: u32 = 200;
+
+/// Full license record of a wallet, as returned by `export_licenses`.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LicenseRecord {
+    /// Wallet address, or its hash when privacy mode is enabled
+    pub wallet_address: String,
+    /// Expiry timestamp in nanoseconds, if the wallet has a timestamp license
+    pub expiry: Option<u64>,
+    /// Expiry block height, if the wallet has a block-height license
+    pub expiry_height: Option<u64>,
+    /// Expiry epoch, if the wallet has an epoch license
+    pub expiry_epoch: Option<u64>,
+    /// Serial number of the license, if it has one; missing in records exported
+    /// before serials existed
+    #[serde(default)]
+    pub serial: Option<u64>,
+    /// Whether the wallet is currently licensed under any mode
+    pub active: bool,
+    pub compliance_status: ComplianceStatus,
+    pub jurisdiction: Option<String>,
+}
+
+#[near]
+impl LicenseContract {
+    /// Export license records in the order wallets were first licensed.
+    /// Paging through with the last returned wallet as `from_key` reconstructs
+    /// every record the contract holds; `import_licenses` replays the pages into
+    /// a fresh deployment.
+    ///
+    /// In privacy mode records carry wallet hashes rather than addresses.
+    ///
+    /// # Arguments
+    /// * `from_key` - Wallet address (or hash, in privacy mode) to continue after, or `None` to start from the beginning
+    /// * `limit` - Maximum number of records to return (default 50, capped at 200)
+    ///
+    /// # Panics
+    /// Panics if `from_key` isn't an indexed wallet
+    pub fn export_licenses(&self, from_key: Option<String>, limit: Option<u32>) -> Vec<LicenseRecord> {
+        let start = match from_key {
+            Some(key) => self
+                .wallet_positions
+                .get(&key)
+                .map(|&position| position + 1)
+                .unwrap_or_else(|| env::panic_str("Unknown export key")),
+            None => 0,
+        };
+        let limit = limit.unwrap_or(DEFAULT_EXPORT_LIMIT).min(MAX_EXPORT_LIMIT);
+
+        (start..self.wallets.len().min(start.saturating_add(limit)))
+            .filter_map(|position| self.wallets.get(position))
+            .map(|wallet_address| self.license_record(wallet_address))
+            .collect()
+    }
+
+    /// Get the number of wallets in the export index.
+    pub fn get_indexed_wallet_count(&self) -> u32 {
+        self.wallets.len()
+    }
+
+    /// Add wallets licensed before the export index existed to the index,
+    /// their entries to the state checksum and their timestamp licenses to the
+    /// activity counters and tenure, and give their licenses serial numbers.
+    /// Wallets without a license entry, or already indexed, are skipped.
+    ///
+    /// # Arguments
+    /// * `wallet_addresses` - Wallet addresses to index
+    ///
+    /// # Returns
+    /// The number of wallets newly indexed
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin
+    pub fn backfill_wallet_index(&mut self, wallet_addresses: Vec<String>) -> u32 {
+        self.assert_admin();
+
+        let mut indexed = 0;
+        for wallet_address in wallet_addresses {
+            let key = self.wallet_key(&wallet_address);
+            let has_license = self.licenses.contains_key(&key)
+                || self.height_licenses.contains_key(&key)
+                || self.epoch_licenses.contains_key(&key);
+
+            if has_license && self.index_wallet(&key) {
+                // Entries this old predate the state checksum, activity counters and tenure too
+                for kind in ExpiryKind::ALL {
+                    if let Some(expiry) = self.read_expiry(kind, &key) {
+                        self.fold_into_checksum(kind, &key, expiry);
+                    }
+                }
+                if let Some(expiry) = self.licenses.get(&key).copied() {
+                    if expiry > env::block_timestamp() {
+                        self.track_activity(None, expiry);
+                    }
+                    self.cover_tenure(&key, expiry);
+                }
+                self.assign_serial(&key);
+                indexed += 1;
+            }
+        }
+        indexed
+    }
+
+    /// Import license records exported from another deployment, e.g. for disaster
+    /// recovery or to seed a test environment. Records are written under their exported
+    /// keys as-is, so a source in privacy mode needs privacy mode enabled here with the
+    /// same salt first. Imports are held to the same checks as grants: each record must be
+    /// eligible here, judged by its exported compliance status and jurisdiction.
+    /// Credentials aren't reissued.
+    ///
+    /// # Arguments
+    /// * `records` - A page of `export_licenses` output (at most 200 records)
+    ///
+    /// # Returns
+    /// The number of records imported. If gas runs low the import stops early and
+    /// this is less than the page length; resubmit the remaining records.
+    ///
+    /// # Panics
+    /// Panics if the contract is paused, caller lacks the grant licenses permission, the
+    /// page is too large, a legacy import is running, a record's wallet or serial already
+    /// exists in this deployment, a record's wallet is ineligible, or a record runs longer
+    /// than the long grant threshold (grant those with `propose_long_grant` instead)
+    pub fn import_licenses(&mut self, records: Vec<LicenseRecord>) -> u32 {
+        self.assert_can_grant();
+        require!(records.len() <= MAX_EXPORT_LIMIT as usize, "Too many records in one import");
+        // A legacy import writing into the index would make the restore fail partway
+        self.assert_job_idle(JobKind::LegacyImport);
+
+        let mut imported = 0;
+        for record in &records {
+            if batch_gas_exhausted() {
+                break;
+            }
+            imported += 1;
+
+            let key = &record.wallet_address;
+            require!(
+                self.index_wallet(key),
+                format!("Wallet {} already exists in this deployment", key)
+            );
+            if record.compliance_status != ComplianceStatus::Unverified {
+                self.compliance.insert(key.clone(), record.compliance_status);
+            }
+            if let Some(jurisdiction) = &record.jurisdiction {
+                self.jurisdictions.insert(key.clone(), jurisdiction.clone());
+            }
+            self.assert_eligible(key);
+
+            for (kind, expiry) in [
+                (ExpiryKind::Timestamp, record.expiry),
+                (ExpiryKind::Height, record.expiry_height),
+                (ExpiryKind::Epoch, record.expiry_epoch),
+            ] {
+                if let Some(expiry) = expiry {
+                    self.assert_term_within_grant_threshold(kind, expiry);
+                }
+            }
+            if let Some(expiry) = record.expiry {
+                // Lapsed records are restored but don't count as active
+                if expiry > env::block_timestamp() {
+                    self.track_activity(None, expiry);
+                }
+                self.write_expiry(ExpiryKind::Timestamp, key, expiry);
+            }
+            if let Some(expiry) = record.expiry_height {
+                self.write_expiry(ExpiryKind::Height, key, expiry);
+            }
+            if let Some(expiry) = record.expiry_epoch {
+                self.write_expiry(ExpiryKind::Epoch, key, expiry);
+            }
+            match record.serial {
+                Some(serial) => self.restore_serial(key, serial),
+                None => self.assign_serial(key),
+            }
+            self.record_provenance(key, GrantSource::Migration, None);
+        }
+        self.metrics.imported += imported as u64;
+        self.record_gas(GasClass::Import);
+        imported
+    }
+
+    /// Append a wallet key to the export index if it isn't there yet.
+    ///
+    /// # Returns
+    /// `true` if the key was newly indexed
+    pub(crate) fn index_wallet(&mut self, key: &str) -> bool {
+        if self.wallet_positions.contains_key(key) {
+            return false;
+        }
+        self.wallet_positions.insert(key.to_string(), self.wallets.len());
+        self.wallets.push(key.to_string());
+        true
+    }
+
+    /// Assemble the full license record stored under a wallet key
+    pub(crate) fn license_record(&self, key: &str) -> LicenseRecord {
+        LicenseRecord {
+            wallet_address: key.to_string(),
+            expiry: self.licenses.get(key).copied(),
+            expiry_height: self.height_licenses.get(key).copied(),
+            expiry_epoch: self.epoch_licenses.get(key).copied(),
+            serial: self.serials.get(key).copied(),
+            active: self.is_key_licensed(key),
+            compliance_status: self.compliance.get(key).copied().unwrap_or_default(),
+            jurisdiction: self.jurisdictions.get(key).cloned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user_str};
+    use crate::{Network, ONE_DAY_NS};
+
+    fn wallet(i: u32) -> String {
+        format!("wallet{}.near", i)
+    }
+
+    #[test]
+    fn test_export_licenses_paginates_in_grant_order() {
+        setup_context(&admin(), 1_000_000_000);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        for i in 0..5 {
+            contract.grant_license(wallet(i), 30, None, None);
+        }
+        // Extending an existing license doesn't re-index it
+        contract.grant_license(wallet(0), 30, None, None);
+        assert_eq!(contract.get_indexed_wallet_count(), 5);
+
+        let first_page = contract.export_licenses(None, Some(2));
+        assert_eq!(first_page.len(), 2);
+        assert_eq!(first_page[0].wallet_address, wallet(0));
+        assert_eq!(first_page[0].expiry, Some(1_000_000_000 + 60 * ONE_DAY_NS));
+        assert!(first_page[0].active);
+        assert_eq!(first_page[1].wallet_address, wallet(1));
+
+        let second_page = contract.export_licenses(Some(wallet(1)), Some(2));
+        assert_eq!(
+            second_page.iter().map(|r| r.wallet_address.clone()).collect::<Vec<_>>(),
+            vec![wallet(2), wallet(3)]
+        );
+
+        let last_page = contract.export_licenses(Some(wallet(3)), Some(2));
+        assert_eq!(last_page.len(), 1);
+        assert!(contract.export_licenses(Some(wallet(4)), None).is_empty());
+    }
+
+    #[test]
+    fn test_backfill_wallet_index() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        // Simulate an entry written before the index existed
+        contract.licenses.insert(user_str(), 100);
+
+        let indexed = contract.backfill_wallet_index(vec![user_str(), user_str(), wallet(9)]);
+        assert_eq!(indexed, 1);
+        assert_eq!(contract.export_licenses(None, None)[0].expiry, Some(100));
+        assert_eq!(contract.get_serial(user_str()), Some(1));
+    }
+
+    #[test]
+    fn test_backfill_restores_activity_and_tenure() {
+        setup_context(&admin(), ONE_DAY_NS);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.licenses.insert(user_str(), 31 * ONE_DAY_NS);
+        contract.licenses.insert(wallet(0), 100);
+
+        contract.backfill_wallet_index(vec![user_str(), wallet(0)]);
+        // Only the unexpired entry counts as active
+        assert_eq!(contract.get_active_license_count(), 1);
+        assert_eq!(contract.get_daily_active(1, 1)[0].active, 1);
+
+        setup_context(&admin(), 11 * ONE_DAY_NS);
+        assert_eq!(contract.get_tenure(user_str()).unwrap().streak_days, 10);
+    }
+
+    #[test]
+    fn test_import_reproduces_export() {
+        setup_context(&admin(), 1_000_000_000);
+        let mut source = LicenseContract::new(admin(), Network::Mainnet);
+        for i in 0..3 {
+            source.grant_license(wallet(i), 30, None, None);
+        }
+        source.grant_license_blocks(wallet(1), 100, None, None);
+        source.jurisdictions.insert(wallet(2), "US".to_string());
+
+        let mut target = LicenseContract::new(admin(), Network::Mainnet);
+        let mut from_key = None;
+        loop {
+            let page = source.export_licenses(from_key, Some(2));
+            if page.is_empty() {
+                break;
+            }
+            from_key = page.last().map(|record| record.wallet_address.clone());
+            target.import_licenses(page);
+        }
+
+        assert_eq!(target.export_licenses(None, None), source.export_licenses(None, None));
+        assert_eq!(target.get_active_license_count(), 3);
+        assert_eq!(target.get_state_checksum(), source.get_state_checksum());
+    }
+
+    #[test]
+    fn test_import_skips_activity_of_expired_records() {
+        setup_context(&admin(), ONE_DAY_NS);
+        let mut source = LicenseContract::new(admin(), Network::Mainnet);
+        source.grant_license(wallet(0), 1, None, None);
+        source.grant_license(wallet(1), 30, None, None);
+
+        setup_context(&admin(), 5 * ONE_DAY_NS);
+        let mut target = LicenseContract::new(admin(), Network::Mainnet);
+        target.import_licenses(source.export_licenses(None, None));
+
+        assert_eq!(target.get_expiry(wallet(0)), Some(2 * ONE_DAY_NS));
+        assert_eq!(target.get_active_license_count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Grants longer than 30 days need the co-signer, use propose_long_grant")]
+    fn test_import_rejects_record_past_grant_threshold() {
+        setup_context(&admin(), 0);
+        let mut source = LicenseContract::new(admin(), Network::Mainnet);
+        source.grant_license(user_str(), 60, None, None);
+
+        let mut target = LicenseContract::new(admin(), Network::Mainnet);
+        target.set_long_grant_threshold(Some(30));
+        target.import_licenses(source.export_licenses(None, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "already exists in this deployment")]
+    fn test_import_rejects_existing_wallet() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+
+        let records = contract.export_licenses(None, None);
+        contract.import_licenses(records);
+    }
+
+    #[test]
+    #[should_panic(expected = "Conflicting job legacy_import is running")]
+    fn test_import_rejected_during_legacy_import() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        let mut source = LicenseContract::new(admin(), Network::Mainnet);
+        source.grant_license(user_str(), 30, None, None);
+
+        contract.import_legacy_licenses(Vec::new(), None);
+        contract.import_licenses(source.export_licenses(None, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn test_import_rejected_while_paused() {
+        setup_context(&admin(), 0);
+        let mut source = LicenseContract::new(admin(), Network::Mainnet);
+        source.grant_license(user_str(), 30, None, None);
+
+        let mut target = LicenseContract::new(admin(), Network::Mainnet);
+        target.pause();
+        target.import_licenses(source.export_licenses(None, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "Licenses are not available in jurisdiction US")]
+    fn test_import_rejects_ineligible_record() {
+        setup_context(&admin(), 0);
+        let mut source = LicenseContract::new(admin(), Network::Mainnet);
+        source.grant_license(user_str(), 30, None, None);
+        source.jurisdictions.insert(user_str(), "US".to_string());
+
+        let mut target = LicenseContract::new(admin(), Network::Mainnet);
+        target.set_blocked_jurisdictions(vec!["US".to_string()]);
+        target.import_licenses(source.export_licenses(None, None));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown export key")]
+    fn test_export_unknown_key() {
+        setup_context(&admin(), 0);
+        let contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.export_licenses(Some(user_str()), None);
+    }
+}
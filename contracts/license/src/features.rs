@@ -0,0 +1,166 @@
+use near_sdk::{near, env, require};
+
+use crate::events::LicenseEvent;
+use crate::market::MAX_BPS;
+use crate::{AdminAction, LicenseContract, LicenseContractExt};
+
+/// Maximum length of a feature ID
+const MAX_FEATURE_ID_LEN: usize = 64;
+
+/// Panics unless a feature ID is 1 to 64 lowercase letters, digits, `_` or `-`
+pub(crate) fn assert_valid_feature_id(feature_id: &str) {
+    require!(
+        (1..=MAX_FEATURE_ID_LEN).contains(&feature_id.len())
+            && feature_id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-'),
+        format!("Invalid feature ID: {}", feature_id)
+    );
+}
+
+/// Deterministic rollout bucket of a wallet for a feature, in basis points. Hashing
+/// the feature ID in gives each feature its own cohort
+fn rollout_bucket(feature_id: &str, key: &str) -> u16 {
+    let digest = env::sha256(format!("{}:{}", feature_id, key).as_bytes());
+    let value = u64::from_be_bytes(digest[..8].try_into().unwrap());
+    (value % MAX_BPS as u64) as u16
+}
+
+#[near]
+impl LicenseContract {
+    /// Turn an app feature on or off for every licensed wallet, replacing any partial rollout.
+    ///
+    /// # Arguments
+    /// * `feature_id` - 1 to 64 lowercase letters, digits, `_` or `-`
+    /// * `enabled` - Whether licensed wallets get the feature
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, or the feature ID is malformed
+    pub fn set_feature(&mut self, feature_id: String, enabled: bool) {
+        self.submit_action(AdminAction::SetFeature { feature_id, enabled });
+    }
+
+    /// Enable an app feature for a deterministic share of licensed wallets, chosen
+    /// by hashing each wallet with the feature ID. Raising the share keeps every
+    /// wallet that already had the feature.
+    ///
+    /// # Arguments
+    /// * `feature_id` - 1 to 64 lowercase letters, digits, `_` or `-`
+    /// * `rollout_bps` - Share of licensed wallets that get the feature, in basis points
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, the feature ID is malformed,
+    /// or the share exceeds 100%
+    pub fn set_feature_rollout(&mut self, feature_id: String, rollout_bps: u16) {
+        self.submit_action(AdminAction::SetFeatureRollout { feature_id, rollout_bps });
+    }
+
+    /// Check whether a wallet may use an app feature: the feature is enabled, the
+    /// wallet falls within its rollout, and the wallet is licensed, by its own license
+    /// or a rental. Apps should gate on this rather than combining the checks themselves.
+    pub fn has_feature(&self, wallet_address: String, feature_id: String) -> bool {
+        let key = self.wallet_key(&wallet_address);
+        self.features
+            .get(&feature_id)
+            .is_some_and(|&rollout_bps| rollout_bucket(&feature_id, &key) < rollout_bps)
+            && self.is_key_licensed(&key)
+    }
+
+    /// Check whether an app feature is enabled for at least some wallets.
+    pub fn is_feature_enabled(&self, feature_id: String) -> bool {
+        self.features.contains_key(&feature_id)
+    }
+
+    /// Get the share of licensed wallets an app feature is rolled out to, in basis points.
+    pub fn get_feature_rollout(&self, feature_id: String) -> u16 {
+        self.features.get(&feature_id).copied().unwrap_or(0)
+    }
+
+    /// Roll a feature out to a share of licensed wallets, removing it at 0, and
+    /// announce the change with `event`
+    pub(crate) fn apply_feature_rollout(&mut self, feature_id: String, rollout_bps: u16, event: LicenseEvent) {
+        if rollout_bps > 0 {
+            self.features.insert(feature_id, rollout_bps);
+        } else {
+            self.features.remove(&feature_id);
+        }
+        event.emit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user_str};
+    use crate::{Network, ONE_DAY_NS};
+
+    #[test]
+    fn test_has_feature() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+        assert!(!contract.has_feature(user_str(), "export".to_string()));
+
+        contract.set_feature("export".to_string(), true);
+        assert!(contract.has_feature(user_str(), "export".to_string()));
+        assert!(!contract.has_feature("other.near".to_string(), "export".to_string()));
+
+        setup_context(&admin(), 31 * ONE_DAY_NS);
+        assert!(!contract.has_feature(user_str(), "export".to_string()));
+
+        contract.grant_license(user_str(), 30, None, None);
+        contract.set_feature("export".to_string(), false);
+        assert!(!contract.has_feature(user_str(), "export".to_string()));
+    }
+
+    #[test]
+    fn test_feature_rollout_is_deterministic_and_monotonic() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        let wallets: Vec<String> = (0..200).map(|i| format!("wallet{}.near", i)).collect();
+        for wallet in &wallets {
+            setup_context(&admin(), 0);
+            contract.grant_license(wallet.clone(), 30, None, None);
+        }
+        let cohort = |contract: &LicenseContract| -> Vec<String> {
+            wallets
+                .iter()
+                .filter(|wallet| contract.has_feature(wallet.to_string(), "beta".to_string()))
+                .cloned()
+                .collect()
+        };
+
+        contract.set_feature_rollout("beta".to_string(), 2_500);
+        let quarter = cohort(&contract);
+        assert!((30..70).contains(&quarter.len()));
+        assert_eq!(cohort(&contract), quarter);
+
+        contract.set_feature_rollout("beta".to_string(), 5_000);
+        let half = cohort(&contract);
+        assert!(quarter.iter().all(|wallet| half.contains(wallet)));
+        assert!(half.len() > quarter.len());
+        assert_eq!(contract.get_feature_rollout("beta".to_string()), 5_000);
+
+        contract.set_feature_rollout("beta".to_string(), 0);
+        assert!(cohort(&contract).is_empty());
+        assert!(!contract.is_feature_enabled("beta".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Rollout cannot exceed 10000 basis points")]
+    fn test_rollout_above_whole() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.set_feature_rollout("beta".to_string(), 10_001);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid feature ID: Export!")]
+    fn test_invalid_feature_id() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.set_feature("Export!".to_string(), true);
+    }
+}
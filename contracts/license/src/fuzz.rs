@@ -0,0 +1,204 @@
+//! Fuzz tests feeding generated input into the parsing behind public methods:
+//! hex commitments, jurisdiction codes, `did:near` DIDs, and the JSON and borsh
+//! forms of argument and state types. Input may only be rejected with the
+//! contract's own error, and whatever is accepted must round-trip.
+
+use std::fmt::Debug;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::de::DeserializeOwned;
+use near_sdk::serde::Serialize;
+use near_sdk::{borsh, serde_json, NearToken};
+
+use crate::commitment::parse_commitment;
+use crate::invariants::Rng;
+use crate::jurisdiction::normalize_jurisdiction;
+use crate::privacy::to_hex;
+use crate::{parse_did_near, AdminAction, ComplianceStatus, LegacyLicense, LicenseRecord, Permission, Role};
+
+/// Generated inputs per target
+const CASES: u64 = 512;
+/// Mutations applied to a serialized seed value, at most
+const MUTATIONS: u64 = 6;
+
+/// Fragments mixed into otherwise well-formed text: signs, separators, whitespace,
+/// control characters and multi-byte UTF-8
+const ODD_FRAGMENTS: [&str; 12] = ["+", "-", " ", "\t", "\0", ".", ":", "_", "é", "€", "ß", "İ"];
+
+/// Bytes that tend to break JSON and borsh structure when substituted in
+const ODD_BYTES: [u8; 16] = [
+    b'"', b'{', b'}', b'[', b']', b':', b',', b'\\', b'-', b'e', b'0', b'9', 0x00, 0x7f, 0xc3, 0xff,
+];
+
+/// Run `parse` on `input`, returning what it accepted. Rejections must panic with a
+/// message starting with `error`; any other panic (an overflow, an out-of-bounds
+/// index, a failed unwrap) fails the test with the input that caused it
+fn accepted<T>(input: &impl Debug, error: Option<&str>, parse: impl FnOnce() -> T) -> Option<T> {
+    match catch_unwind(AssertUnwindSafe(parse)) {
+        Ok(parsed) => Some(parsed),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| payload.downcast_ref::<&str>().map(|message| message.to_string()))
+                .unwrap_or_default();
+            // `require!` panics with the message itself, `env::panic_str` through the
+            // mocked host, which wraps it in a `GuestPanic`
+            let contract_error = message.split_once("GuestPanic { panic_msg: \"").map_or(&*message, |(_, rest)| rest);
+            if !error.is_some_and(|error| contract_error.starts_with(error)) {
+                panic!("Unexpected panic on {:?}: {}", input, message);
+            }
+            None
+        }
+    }
+}
+
+/// Text of `len` pieces drawn from `alphabet`, with about one in `odd_rate` pieces
+/// taken from `ODD_FRAGMENTS` instead
+fn text(rng: &mut Rng, alphabet: &str, len: u64, odd_rate: u64) -> String {
+    let alphabet: Vec<char> = alphabet.chars().collect();
+    (0..len)
+        .map(|_| {
+            if rng.below(odd_rate) == 0 {
+                ODD_FRAGMENTS[rng.below(ODD_FRAGMENTS.len() as u64) as usize].to_string()
+            } else {
+                alphabet[rng.below(alphabet.len() as u64) as usize].to_string()
+            }
+        })
+        .collect()
+}
+
+/// Serialized `bytes` with a few random bytes replaced, inserted, removed, or
+/// swapped for a run of digits long enough to overflow any integer
+fn mutate(rng: &mut Rng, mut bytes: Vec<u8>) -> Vec<u8> {
+    for _ in 0..rng.below(MUTATIONS + 1) {
+        let at = rng.below(bytes.len() as u64 + 1) as usize;
+        let byte = if rng.below(2) == 0 {
+            ODD_BYTES[rng.below(ODD_BYTES.len() as u64) as usize]
+        } else {
+            rng.next() as u8
+        };
+        match rng.below(4) {
+            0 if at < bytes.len() => bytes[at] = byte,
+            1 => bytes.insert(at, byte),
+            2 if at < bytes.len() => {
+                bytes.remove(at);
+            }
+            _ => {
+                bytes.splice(at..at, b"99999999999999999999999".iter().copied());
+            }
+        }
+    }
+    bytes
+}
+
+/// Argument and state values covering every shape of field the contract decodes:
+/// account IDs, strings, optional and nested values, enums, byte strings, large numbers
+fn seed_actions() -> Vec<AdminAction> {
+    vec![
+        AdminAction::SetTransferConfig { cooldown_days: u32::MAX, fee: NearToken::from_near(1) },
+        AdminAction::TransferAdmin { new_admin: "new-admin.near".parse().unwrap() },
+        AdminAction::SetPauser { account_id: None },
+        AdminAction::SetLongGrantThreshold { days: Some(365) },
+        AdminAction::SetPermission { permission: Permission::GrantLicenses, roles: vec![Role::Admin, Role::Pauser] },
+        AdminAction::SetBlockedJurisdictions { jurisdictions: vec!["CU".to_string(), "US-NY".to_string()] },
+        AdminAction::EnablePrivacyMode { salt: Base64VecU8(vec![7; 16]) },
+        AdminAction::SetFeatureRollout { feature_id: "new-checkout".to_string(), rollout_bps: 2_500 },
+    ]
+}
+
+/// Decode mutated JSON of `seeds`, checking decoding never panics and that whatever
+/// decodes survives a round trip unchanged
+fn fuzz_json<T: Serialize + DeserializeOwned + PartialEq + Debug>(seeds: &[T]) {
+    for seed in 0..CASES {
+        let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+        let value = &seeds[rng.below(seeds.len() as u64) as usize];
+        let input = mutate(&mut rng, serde_json::to_vec(value).unwrap());
+        let input_text = String::from_utf8_lossy(&input);
+
+        if let Some(Ok(decoded)) = accepted(&input_text, None, || serde_json::from_slice::<T>(&input)) {
+            let reencoded = serde_json::to_vec(&decoded).unwrap();
+            assert_eq!(serde_json::from_slice::<T>(&reencoded).unwrap(), decoded, "input {}", input_text);
+        }
+    }
+}
+
+#[test]
+fn test_commitment_parsing_is_canonical() {
+    for seed in 0..CASES {
+        let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+        // Mostly 64 characters, where the parser does its work, with neighbouring lengths
+        let len = 62 + rng.below(5);
+        let input = text(&mut rng, "0123456789abcdefABCDEF", len, 32);
+
+        if let Some(parsed) = accepted(&input, Some("Commitment must be 32 bytes of hex"), || parse_commitment(&input)) {
+            // Exactly one spelling per commitment, up to case, or the same license
+            // would be reachable under several keys in events and lookups
+            assert_eq!(to_hex(&parsed), input.to_ascii_lowercase(), "input {:?}", input);
+        }
+    }
+}
+
+#[test]
+fn test_jurisdiction_normalization_is_stable() {
+    for seed in 0..CASES {
+        let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+        let len = rng.below(12);
+        let input = text(&mut rng, "ABCDEFGHIJKLMNOPQRSTUVWXYZabcxyz0123456789-", len, 6);
+
+        if let Some(code) = accepted(&input, Some("Invalid jurisdiction code: "), || normalize_jurisdiction(&input)) {
+            assert_eq!(code, input.trim().to_ascii_uppercase(), "input {:?}", input);
+            assert_eq!(normalize_jurisdiction(&code), code, "input {:?}", input);
+        }
+    }
+}
+
+#[test]
+fn test_did_parsing_round_trips() {
+    const PREFIXES: [&str; 5] = ["did:near:", "did:near:did:near:", "did:NEAR:", "did:near", ""];
+    for seed in 0..CASES {
+        let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+        let prefix = PREFIXES[rng.below(PREFIXES.len() as u64) as usize];
+        let len = rng.below(24);
+        let input = format!("{}{}", prefix, text(&mut rng, "abcxyz0189.-_ABC@", len, 8));
+
+        if let Some(Some(account_id)) = accepted(&input, None, || parse_did_near(&input)) {
+            assert_eq!(format!("did:near:{}", account_id), input);
+        }
+    }
+}
+
+#[test]
+fn test_json_arguments_decode_without_panicking() {
+    fuzz_json(&seed_actions());
+    fuzz_json(&[vec![
+        LegacyLicense { wallet_address: "alice.near".to_string(), expiry: u64::MAX },
+        LegacyLicense { wallet_address: "0x1234567890abcdef1234567890abcdef12345678".to_string(), expiry: 0 },
+    ]]);
+    fuzz_json(&[LicenseRecord {
+        wallet_address: "alice.near".to_string(),
+        expiry: Some(u64::MAX),
+        expiry_height: None,
+        expiry_epoch: Some(1),
+        serial: Some(7),
+        active: true,
+        compliance_status: ComplianceStatus::Verified,
+        jurisdiction: Some("US-NY".to_string()),
+    }]);
+}
+
+#[test]
+fn test_borsh_state_decodes_without_panicking() {
+    let seeds = seed_actions();
+    for seed in 0..CASES {
+        let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+        let value = &seeds[rng.below(seeds.len() as u64) as usize];
+        let input = mutate(&mut rng, borsh::to_vec(value).unwrap());
+
+        if let Some(Ok(decoded)) = accepted(&input, None, || borsh::from_slice::<AdminAction>(&input)) {
+            let reencoded = borsh::to_vec(&decoded).unwrap();
+            assert_eq!(borsh::from_slice::<AdminAction>(&reencoded).unwrap(), decoded, "input {:?}", input);
+        }
+    }
+}
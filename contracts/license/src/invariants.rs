@@ -0,0 +1,164 @@
+//! Invariant tests over generated sequences of grants, transfers, burns and
+//! time passing, checked against a simple model of the expiries.
+
+use std::collections::BTreeMap;
+
+use crate::tests::{admin, setup_context};
+use crate::{ExpiryKind, LicenseContract, Network, ONE_DAY_NS};
+
+/// Number of generated sequences
+const CASES: u64 = 32;
+/// Operations per sequence
+const STEPS: usize = 40;
+/// Wallets the operations pick from
+const WALLETS: [&str; 4] = ["alice.near", "bob.near", "carol.near", "0x1234567890abcdef1234567890abcdef12345678"];
+
+/// Deterministic xorshift generator, so a failing case can be replayed from its seed
+pub(crate) struct Rng(pub(crate) u64);
+
+impl Rng {
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub(crate) fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+
+    fn wallet(&mut self) -> String {
+        WALLETS[self.below(WALLETS.len() as u64) as usize].to_string()
+    }
+}
+
+/// Run one generated sequence, checking every invariant after each operation
+fn run_case(seed: u64) {
+    let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+    let mut now = rng.below(1_000) * ONE_DAY_NS;
+    setup_context(&admin(), now);
+    let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+    // Timestamp expiry of every wallet holding a license entry
+    let mut model: BTreeMap<String, u64> = BTreeMap::new();
+    // Days each wallet had been licensed at the previous step
+    let mut licensed_days: BTreeMap<String, u64> = BTreeMap::new();
+    // Last day each held license period counts as active on
+    let mut active_through: BTreeMap<String, u64> = BTreeMap::new();
+    // Last active days of license periods that lapsed before being renewed or burned
+    let mut lapsed: Vec<u64> = Vec::new();
+
+    for step in 0..STEPS {
+        let context = format!("seed {} step {}", seed, step);
+        match rng.below(5) {
+            0 | 1 => {
+                let wallet = rng.wallet();
+                let duration_ns = match rng.below(3) {
+                    0 => {
+                        let days = rng.below(800) as u32;
+                        setup_context(&admin(), now);
+                        contract.grant_license(wallet.clone(), days, None, None);
+                        days as u64 * ONE_DAY_NS
+                    }
+                    1 => {
+                        let hours = rng.below(100) as u32;
+                        setup_context(&admin(), now);
+                        contract.grant_license_hours(wallet.clone(), hours, None, None);
+                        hours as u64 * 3_600_000_000_000
+                    }
+                    _ => {
+                        let seconds = rng.below(1_000_000);
+                        setup_context(&admin(), now);
+                        contract.grant_license_seconds(wallet.clone(), seconds, None, None);
+                        seconds * 1_000_000_000
+                    }
+                };
+                let previous = model.get(&wallet).copied();
+                if previous.is_some_and(|expiry| expiry <= now) {
+                    lapsed.extend(active_through.get(&wallet));
+                }
+                let expected = previous.filter(|&expiry| expiry > now).unwrap_or(now) + duration_ns;
+                assert!(previous.is_none_or(|previous| expected >= previous), "{}: expiry decreased", context);
+                active_through.insert(wallet.clone(), ((expected.max(1) - 1) / ONE_DAY_NS).max(now / ONE_DAY_NS));
+                model.insert(wallet, expected);
+            }
+            2 => {
+                let from = rng.wallet();
+                let to = rng.wallet();
+                let allowed = from != to
+                    && model.get(&from).is_some_and(|&expiry| expiry > now)
+                    && !model.contains_key(&to);
+                if allowed {
+                    setup_context(&from.parse().unwrap_or_else(|_| admin()), now);
+                    // EVM wallets can't sign NEAR transactions, so only NEAR accounts transfer
+                    if from.ends_with(".near") {
+                        contract.transfer_license(to.clone());
+                        let expiry = model.remove(&from).unwrap();
+                        model.insert(to.clone(), expiry);
+                        let day = active_through.remove(&from).unwrap();
+                        active_through.insert(to, day);
+                    }
+                }
+            }
+            3 => {
+                let wallet = rng.wallet();
+                if model.contains_key(&wallet) && wallet.ends_with(".near") {
+                    setup_context(&wallet.parse().unwrap(), now);
+                    contract.burn_license();
+                    let day = active_through.remove(&wallet).unwrap();
+                    if model.remove(&wallet).is_some_and(|expiry| expiry <= now) {
+                        lapsed.push(day);
+                    }
+                }
+            }
+            _ => now += rng.below(3 * ONE_DAY_NS),
+        }
+        setup_context(&admin(), now);
+
+        for wallet in WALLETS {
+            let expiry = model.get(wallet).copied();
+            assert_eq!(contract.get_expiry(wallet.to_string()), expiry, "{}: expiry of {}", context, wallet);
+            assert_eq!(
+                contract.is_licensed(wallet.to_string()),
+                expiry.is_some_and(|expiry| expiry > now),
+                "{}: license check of {}",
+                context,
+                wallet
+            );
+
+            if let Some(tenure) = contract.get_tenure(wallet.to_string()) {
+                let previous = licensed_days.insert(wallet.to_string(), tenure.licensed_days).unwrap_or(0);
+                assert!(tenure.licensed_days >= previous, "{}: tenure of {} decreased", context, wallet);
+                assert!(tenure.streak_days <= tenure.licensed_days, "{}: streak of {}", context, wallet);
+            }
+        }
+        // A license period counts as active for every day it was active on, including
+        // the day it lapsed
+        let today = now / ONE_DAY_NS;
+        assert_eq!(
+            contract.get_active_license_count(),
+            active_through.values().chain(&lapsed).filter(|&&day| day >= today).count() as u64,
+            "{}: active license count",
+            context
+        );
+    }
+
+    // The checksum only depends on the entries held, not on how they got there.
+    // Both contracts share the mocked storage, so the entries are folded in without
+    // reading back the ones `contract` already wrote
+    let mut expected = LicenseContract::new(admin(), Network::Mainnet);
+    for (wallet, &expiry) in &model {
+        expected.fold_into_checksum(ExpiryKind::Timestamp, wallet, expiry);
+    }
+    assert_eq!(contract.get_state_checksum(), expected.get_state_checksum(), "seed {}: checksum", seed);
+}
+
+#[test]
+fn test_expiry_invariants_hold_for_generated_sequences() {
+    for seed in 0..CASES {
+        // The mocked blockchain keeps storage per thread, so each case gets a fresh one
+        std::thread::spawn(move || run_case(seed))
+            .join()
+            .unwrap_or_else(|_| panic!("Invariant violated for seed {}", seed));
+    }
+}
@@ -0,0 +1,208 @@
+use near_sdk::{near, AccountId, env, require, Gas};
+
+use crate::{LicenseContract, LicenseContractExt};
+
+/// Gas batch loops keep in reserve for persisting their progress and returning
+const BATCH_GAS_RESERVE: Gas = Gas::from_tgas(15);
+
+/// Whether a batch loop should stop before its next item and hand back a
+/// continuation cursor rather than risk running out of gas mid-write
+pub(crate) fn batch_gas_exhausted() -> bool {
+    env::used_gas().saturating_add(BATCH_GAS_RESERVE) >= env::prepaid_gas()
+}
+
+/// Kind of resumable batch job. Each kind runs at most once at a time.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum JobKind {
+    /// Scan of the wallet index emitting renewal reminders
+    ExpiryReminders,
+    /// Batched import of licenses from the legacy system
+    LegacyImport,
+}
+
+impl JobKind {
+    pub const ALL: [JobKind; 2] = [JobKind::ExpiryReminders, JobKind::LegacyImport];
+
+    /// Name used in panic messages, matching the JSON representation
+    fn name(&self) -> &'static str {
+        match self {
+            JobKind::ExpiryReminders => "expiry_reminders",
+            JobKind::LegacyImport => "legacy_import",
+        }
+    }
+}
+
+/// Persisted progress of the current (or last) run of a batch job.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct JobStatus {
+    pub kind: JobKind,
+    /// Position the next batch resumes from
+    pub cursor: u64,
+    /// Items processed in this run
+    pub processed: u64,
+    /// Items acted on in this run (e.g. reminded or imported)
+    pub applied: u64,
+    /// Account driving the run; other accounts can't advance it until it finishes
+    pub run_by: AccountId,
+    pub started_at: u64,
+    pub updated_at: u64,
+    /// When the run finished, `None` while it is in progress
+    pub finished_at: Option<u64>,
+}
+
+impl JobStatus {
+    pub fn is_running(&self) -> bool {
+        self.finished_at.is_none()
+    }
+}
+
+#[near]
+impl LicenseContract {
+    /// Get the status of the current or last run of a batch job, if it ever ran.
+    pub fn get_job_status(&self, kind: JobKind) -> Option<JobStatus> {
+        self.jobs.get(&kind).cloned()
+    }
+
+    /// Get the batch jobs currently in progress.
+    pub fn get_running_jobs(&self) -> Vec<JobStatus> {
+        JobKind::ALL
+            .iter()
+            .filter_map(|kind| self.jobs.get(kind))
+            .filter(|job| job.is_running())
+            .cloned()
+            .collect()
+    }
+
+    /// Finish a running batch job, e.g. to close out an import or abandon a
+    /// reminder scan. Its status stays readable; the next batch starts a new run.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or the job isn't running
+    pub fn finish_job(&mut self, kind: JobKind) {
+        self.assert_admin();
+
+        let job = self
+            .jobs
+            .get_mut(&kind)
+            .filter(|job| job.is_running())
+            .unwrap_or_else(|| env::panic_str(&format!("Job {} is not running", kind.name())));
+        job.finished_at = Some(env::block_timestamp());
+    }
+
+    /// Load the run of `kind` the caller's next batch continues, starting a new
+    /// run at cursor 0 if none is in progress.
+    ///
+    /// # Panics
+    /// Panics if the run in progress was started by another account
+    pub(crate) fn begin_batch(&self, kind: JobKind) -> JobStatus {
+        let caller = env::predecessor_account_id();
+        let now = env::block_timestamp();
+
+        match self.jobs.get(&kind).filter(|job| job.is_running()) {
+            Some(job) => {
+                require!(
+                    job.run_by == caller,
+                    format!("Job {} is being run by {}", kind.name(), job.run_by)
+                );
+                job.clone()
+            }
+            None => JobStatus {
+                kind,
+                cursor: 0,
+                processed: 0,
+                applied: 0,
+                run_by: caller,
+                started_at: now,
+                updated_at: now,
+                finished_at: None,
+            },
+        }
+    }
+
+    /// Persist the progress of a batch started with `begin_batch`
+    pub(crate) fn end_batch(&mut self, mut job: JobStatus) -> JobStatus {
+        job.updated_at = env::block_timestamp();
+        self.jobs.insert(job.kind, job.clone());
+        job
+    }
+
+    /// Panics if a job of `kind` is in progress
+    pub(crate) fn assert_job_idle(&self, kind: JobKind) {
+        require!(
+            !self.jobs.get(&kind).is_some_and(|job| job.is_running()),
+            format!("Conflicting job {} is running", kind.name())
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user};
+    use crate::Network;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        let mut job = contract.begin_batch(JobKind::LegacyImport);
+        job.cursor = 10;
+        contract.end_batch(job);
+        contract
+    }
+
+    #[test]
+    fn test_batches_resume_until_finished() {
+        let mut contract = setup_contract();
+
+        setup_context(&admin(), 5);
+        assert_eq!(contract.begin_batch(JobKind::LegacyImport).cursor, 10);
+        assert_eq!(contract.get_running_jobs().len(), 1);
+
+        contract.finish_job(JobKind::LegacyImport);
+        assert!(contract.get_running_jobs().is_empty());
+        assert_eq!(contract.get_job_status(JobKind::LegacyImport).unwrap().finished_at, Some(5));
+
+        // The next batch starts a new run
+        let job = contract.begin_batch(JobKind::LegacyImport);
+        assert_eq!((job.cursor, job.started_at), (0, 5));
+    }
+
+    #[test]
+    fn test_batch_gas_exhausted() {
+        setup_context(&admin(), 0);
+        assert!(!batch_gas_exhausted());
+
+        testing_env!(VMContextBuilder::new().prepaid_gas(BATCH_GAS_RESERVE).build());
+        assert!(batch_gas_exhausted());
+    }
+
+    #[test]
+    #[should_panic(expected = "Job legacy_import is being run by admin.near")]
+    fn test_running_job_rejects_other_runner() {
+        let contract = setup_contract();
+
+        setup_context(&user(), 0);
+        contract.begin_batch(JobKind::LegacyImport);
+    }
+
+    #[test]
+    #[should_panic(expected = "Conflicting job legacy_import is running")]
+    fn test_assert_job_idle() {
+        let contract = setup_contract();
+
+        contract.assert_job_idle(JobKind::LegacyImport);
+    }
+
+    #[test]
+    #[should_panic(expected = "Job expiry_reminders is not running")]
+    fn test_finish_idle_job() {
+        let mut contract = setup_contract();
+
+        contract.finish_job(JobKind::ExpiryReminders);
+    }
+}
@@ -0,0 +1,186 @@
+use near_sdk::{near, env, require};
+
+use crate::events::LicenseEvent;
+use crate::{AdminAction, LicenseContract, LicenseContractExt, Permission};
+
+/// Maximum length of a jurisdiction code (e.g. "US" or the subdivision "US-NY")
+const MAX_JURISDICTION_CODE_LEN: usize = 6;
+
+/// Normalize a jurisdiction code to upper case, panicking if it isn't a valid
+/// ISO 3166 country or subdivision code shape.
+pub(crate) fn normalize_jurisdiction(code: &str) -> String {
+    let code = code.trim().to_ascii_uppercase();
+    require!(
+        (2..=MAX_JURISDICTION_CODE_LEN).contains(&code.len())
+            && code.starts_with(|c: char| c.is_ascii_alphabetic())
+            && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'),
+        format!("Invalid jurisdiction code: {}", code)
+    );
+    code
+}
+
+#[near]
+impl LicenseContract {
+    /// Replace the list of jurisdictions in which licenses may not be granted.
+    /// While the list is non-empty, every wallet must have an attested jurisdiction
+    /// outside the list before it can be licensed.
+    ///
+    /// # Arguments
+    /// * `jurisdictions` - ISO 3166 country or subdivision codes (e.g. "CU", "US-NY")
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, or a code is malformed
+    pub fn set_blocked_jurisdictions(&mut self, jurisdictions: Vec<String>) {
+        self.submit_action(AdminAction::SetBlockedJurisdictions { jurisdictions });
+    }
+
+    /// Record the jurisdiction a wallet holder has attested to.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet address the attestation applies to
+    /// * `jurisdiction` - ISO 3166 country or subdivision code, or `None` to clear it
+    ///
+    /// # Panics
+    /// Panics if caller may not attest jurisdictions or the code is malformed
+    pub fn set_wallet_jurisdiction(&mut self, wallet_address: String, jurisdiction: Option<String>) {
+        self.assert_permitted(Permission::AttestJurisdictions);
+
+        let key = self.wallet_key(&wallet_address);
+        let jurisdiction = jurisdiction.map(|code| normalize_jurisdiction(&code));
+
+        LicenseEvent::WalletJurisdictionSet {
+            wallet_address: key.clone(),
+            jurisdiction: jurisdiction.clone(),
+            attested_by: env::predecessor_account_id(),
+        }
+        .emit();
+
+        match jurisdiction {
+            Some(code) => {
+                self.jurisdictions.insert(key, code);
+            }
+            None => {
+                self.jurisdictions.remove(&key);
+            }
+        }
+    }
+
+    /// Get the list of jurisdictions in which licenses may not be granted.
+    pub fn get_blocked_jurisdictions(&self) -> Vec<String> {
+        self.blocked_jurisdictions.clone()
+    }
+
+    /// Get the attested jurisdiction of a wallet, if any.
+    pub fn get_wallet_jurisdiction(&self, wallet_address: String) -> Option<String> {
+        self.jurisdictions.get(&self.wallet_key(&wallet_address)).cloned()
+    }
+
+    /// Normalize, deduplicate and store a new blocked jurisdiction list
+    pub(crate) fn replace_blocked_jurisdictions(&mut self, jurisdictions: Vec<String>) {
+        let mut blocked: Vec<String> = jurisdictions.iter().map(|c| normalize_jurisdiction(c)).collect();
+        blocked.sort();
+        blocked.dedup();
+
+        LicenseEvent::BlockedJurisdictionsUpdated {
+            blocked_jurisdictions: blocked.clone(),
+            updated_by: env::predecessor_account_id(),
+        }
+        .emit();
+
+        self.blocked_jurisdictions = blocked;
+    }
+
+    /// Panics if jurisdiction restrictions are active and the attested jurisdiction
+    /// of the wallet stored under `key` is missing or blocked
+    pub(crate) fn assert_jurisdiction_allowed(&self, key: &str) {
+        if self.blocked_jurisdictions.is_empty() {
+            return;
+        }
+
+        let jurisdiction = self
+            .jurisdictions
+            .get(key)
+            .unwrap_or_else(|| env::panic_str("Wallet jurisdiction must be attested before being licensed"));
+
+        require!(
+            !self.blocked_jurisdictions.contains(jurisdiction),
+            format!("Licenses are not available in jurisdiction {}", jurisdiction)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user_str};
+    use crate::Network;
+    use near_sdk::test_utils::get_logs;
+    use near_sdk::AccountId;
+
+    fn officer() -> AccountId {
+        "compliance.near".parse().unwrap()
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_compliance_officer(Some(officer()));
+        contract.set_blocked_jurisdictions(vec!["cu".to_string(), "US-NY".to_string(), "CU".to_string()]);
+        contract
+    }
+
+    #[test]
+    fn test_set_blocked_jurisdictions() {
+        let contract = setup_contract();
+
+        assert_eq!(contract.get_blocked_jurisdictions(), vec!["CU".to_string(), "US-NY".to_string()]);
+        assert_eq!(
+            get_logs(),
+            vec![
+                r#"EVENT_JSON:{"standard":"hopper_license","version":"1.1.0","event":"role_changed","data":{"role":"compliance_officer","account":"compliance.near","changed_by":"admin.near"}}"#,
+                r#"EVENT_JSON:{"standard":"hopper_license","version":"1.1.0","event":"blocked_jurisdictions_updated","data":{"blocked_jurisdictions":["CU","US-NY"],"updated_by":"admin.near"}}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grant_allowed_in_attested_jurisdiction() {
+        let mut contract = setup_contract();
+
+        setup_context(&officer(), 0);
+        contract.set_wallet_jurisdiction(user_str(), Some("de".to_string()));
+        assert_eq!(contract.get_wallet_jurisdiction(user_str()), Some("DE".to_string()));
+
+        setup_context(&admin(), 0);
+        contract.grant_license(user_str(), 30, None, None);
+        assert!(contract.is_licensed(user_str()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Licenses are not available in jurisdiction US-NY")]
+    fn test_grant_rejected_in_blocked_jurisdiction() {
+        let mut contract = setup_contract();
+
+        setup_context(&officer(), 0);
+        contract.set_wallet_jurisdiction(user_str(), Some("US-NY".to_string()));
+
+        setup_context(&admin(), 0);
+        contract.grant_license(user_str(), 30, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Wallet jurisdiction must be attested before being licensed")]
+    fn test_grant_rejected_without_attestation() {
+        let mut contract = setup_contract();
+
+        contract.grant_license(user_str(), 30, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid jurisdiction code")]
+    fn test_invalid_jurisdiction_code() {
+        let mut contract = setup_contract();
+
+        contract.set_blocked_jurisdictions(vec!["not a code".to_string()]);
+    }
+}
@@ -1,5 +1,156 @@
-use near_sdk::store::LookupMap;
-use near_sdk::{near, AccountId, env, require, PanicOnDefault};
+use std::collections::HashMap;
+
+use near_sdk::store::{LookupMap, LookupSet, Vector};
+use near_sdk::{near, AccountId, env, require, NearToken, PanicOnDefault};
+
+mod activity;
+mod approvals;
+mod bulk_import;
+mod burn;
+mod checksum;
+mod commitment;
+mod compliance;
+mod cost;
+mod credentials;
+mod did;
+mod events;
+mod export;
+mod features;
+#[cfg(test)]
+mod fuzz;
+#[cfg(test)]
+mod invariants;
+mod jobs;
+mod jurisdiction;
+mod liens;
+mod market;
+mod migration;
+mod pause;
+mod permissions;
+mod pools;
+mod payouts;
+mod privacy;
+mod profiling;
+mod provenance;
+mod quorum;
+mod receipts;
+mod recovery;
+mod reminders;
+mod roles;
+mod serials;
+mod rental;
+mod shutdown;
+mod stats;
+mod storage;
+mod tenure;
+mod timelock;
+mod transfer;
+
+pub use activity::DailyActive;
+pub use bulk_import::{LegacyImportProgress, LegacyLicense};
+pub use checksum::ExpiryKind;
+pub use compliance::ComplianceStatus;
+pub use cost::GrantCostEstimate;
+pub use credentials::CredentialAnchor;
+pub use did::parse_did_near;
+pub use events::{LicenseEvent, EVENT_STANDARD, EVENT_VERSION};
+pub use export::LicenseRecord;
+pub use jobs::{JobKind, JobStatus};
+pub use liens::Lien;
+pub use market::Listing;
+pub use migration::{MigrationReport, MigrationSample};
+pub use payouts::{PayoutAmount, PayoutSchedule, RevenueShare};
+pub use permissions::{Permission, PermissionRule};
+pub use pools::{FloatingPool, FullPoolPolicy, SeatCheckout};
+pub use profiling::{GasClass, GasProfileEntry, GasStats};
+pub use provenance::{GrantSource, Provenance};
+pub use quorum::PendingGrant;
+pub use receipts::{Receipt, ReceiptKind};
+pub use recovery::{GuardianConfig, RecoveryRequest};
+pub use reminders::ReminderPreference;
+pub use rental::{Rental, RentalOffer};
+pub use roles::{Role, RoleChange};
+pub use stats::{DurationHistogram, LicenseStats, OperationMetrics};
+pub use storage::StorageDeposit;
+pub use tenure::Tenure;
+pub use timelock::{AdminAction, QueuedAction};
+pub use transfer::{TransferConfig, TransferKind, TransferRecord};
+
+use provenance::assert_valid_memo;
+use recovery::DEFAULT_RECOVERY_TIMELOCK_DAYS;
+use tenure::TenureRecord;
+
+/// Nominal length of a NEAR epoch in nanoseconds (43,200 blocks at ~1s, i.e. 12 hours).
+/// Actual epochs drift with block production, so conversions are approximate.
+pub const EPOCH_DURATION_NS: u64 = 12 * 60 * 60 * 1_000_000_000;
+
+/// Maximum length of a grant correlation ID in bytes
+const MAX_CORRELATION_ID_LEN: usize = 128;
+
+/// Panics if a grant correlation ID exceeds `MAX_CORRELATION_ID_LEN`
+fn assert_valid_correlation_id(correlation_id: &Option<String>) {
+    require!(
+        correlation_id.as_ref().is_none_or(|id| id.len() <= MAX_CORRELATION_ID_LEN),
+        "Correlation ID is too long"
+    );
+}
+
+const ONE_HOUR_NS: u64 = 60 * 60 * 1_000_000_000;
+const ONE_DAY_NS: u64 = 24 * ONE_HOUR_NS;
+
+/// NEAR network a deployment is intended for.
+/// Checked against the contract account suffix at initialization and migration,
+/// so mainnet parameters can't be applied to a testnet deployment and vice versa.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "lowercase")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    /// A sandbox or localnet, whose accounts can be named anything
+    Localnet,
+}
+
+impl Network {
+    /// Account ID suffix of named accounts on this network, if it has one
+    fn account_suffix(&self) -> Option<&'static str> {
+        match self {
+            Network::Mainnet => Some(".near"),
+            Network::Testnet => Some(".testnet"),
+            Network::Localnet => None,
+        }
+    }
+
+    /// Panics unless the current contract account belongs to this network.
+    /// Implicit accounts exist on every network under the same ID, so they can't
+    /// be checked and are accepted
+    fn assert_current_account(&self) {
+        let account_id = env::current_account_id();
+        require!(
+            account_id.get_account_type().is_implicit()
+                || self.account_suffix().is_none_or(|suffix| account_id.as_str().ends_with(suffix)),
+            format!(
+                "Network mismatch: contract account {} is not a {:?} account",
+                env::current_account_id(),
+                self
+            )
+        );
+    }
+}
+
+/// Settings applied when a deployment is initialized with `new_with_config`,
+/// e.g. defaults forwarded by the license factory. Omitted fields keep their defaults.
+#[near(serializers = [json])]
+#[serde(default)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InitConfig {
+    /// Account allowed to record wallet compliance statuses
+    pub compliance_officer: Option<AccountId>,
+    /// Whether wallets must be verified before a license can be granted to them
+    pub verification_required: bool,
+    /// Jurisdiction codes in which licenses may not be granted
+    pub blocked_jurisdictions: Vec<String>,
+}
 
 /// Old contract state for migration (AccountId keys)
 /// Only used for reading borsh-serialized state during migration
@@ -21,6 +172,182 @@ pub struct LicenseContract {
     licenses: LookupMap<String, u64>,
     /// Admin account that can grant licenses
     admin: AccountId,
+    /// Mapping of wallet addresses to their license expiry block heights
+    /// Used by integrations that verify against a specific height rather than wall-clock time
+    height_licenses: LookupMap<String, u64>,
+    /// Mapping of wallet addresses to their license expiry epoch heights
+    /// Used by validator tooling that reasons in epochs rather than wall-clock time
+    epoch_licenses: LookupMap<String, u64>,
+    /// Network this deployment was initialized for
+    network: Network,
+    /// Account allowed to record wallet compliance statuses
+    compliance_officer: Option<AccountId>,
+    /// Whether wallets must be verified before a license can be granted to them
+    verification_required: bool,
+    /// Mapping of wallet addresses to their compliance status (absent means unverified)
+    compliance: LookupMap<String, ComplianceStatus>,
+    /// Jurisdiction codes in which licenses may not be granted
+    blocked_jurisdictions: Vec<String>,
+    /// Mapping of wallet addresses to their attested jurisdiction code
+    jurisdictions: LookupMap<String, String>,
+    /// Wallet addresses in the order they were first licensed, for export
+    wallets: Vector<String>,
+    /// Mapping of wallet addresses to their position in `wallets`
+    wallet_positions: LookupMap<String, u32>,
+    /// Salt for hashing wallet addresses into storage keys; `None` stores addresses as-is
+    privacy_salt: Option<Vec<u8>>,
+    /// Whether the state was migrated from the `AccountId`-keyed layout, whose
+    /// license entries can't be enumerated
+    migrated: bool,
+    /// Mapping of license commitments (sha256 of a holder secret) to expiry timestamps
+    commitment_licenses: LookupMap<[u8; 32], u64>,
+    /// Mapping of DIDs to the wallets linked to them
+    did_wallets: LookupMap<String, Vec<String>>,
+    /// Mapping of wallets to the DID they are linked to
+    wallet_dids: LookupMap<String, String>,
+    /// Anchors of issued license credentials, indexed by status list index
+    credentials: Vector<CredentialAnchor>,
+    /// Mapping of wallets to the status list index of their latest credential
+    wallet_credentials: LookupMap<String, u32>,
+    /// Mapping of wallets to the guardians allowed to recover their license
+    recovery_guardians: LookupMap<String, GuardianConfig>,
+    /// Mapping of wallets to their pending recovery request
+    recovery_requests: LookupMap<String, RecoveryRequest>,
+    /// Delay between proposing a recovery and being able to execute it, in nanoseconds
+    recovery_timelock_ns: u64,
+    /// Cooldown and fee applied to license transfers
+    transfer_config: TransferConfig,
+    /// Mapping of wallets to the timestamp they last received a license by transfer
+    last_transfers: LookupMap<String, u64>,
+    /// Transfer fees collected and not yet withdrawn
+    collected_fees: NearToken,
+    /// Most fees that can be withdrawn in any 24 hours, if limited
+    withdrawal_limit: Option<NearToken>,
+    /// Timestamps and amounts of the fee withdrawals of the past 24 hours
+    recent_withdrawals: Vec<(u64, NearToken)>,
+    /// Recurring payouts of collected fees to partners
+    payout_schedules: Vec<PayoutSchedule>,
+    /// Beneficiaries that distributed revenue is accrued to, with their shares
+    revenue_split: Vec<RevenueShare>,
+    /// Mapping of beneficiaries to their accrued and unclaimed revenue
+    accrued_payouts: LookupMap<AccountId, NearToken>,
+    /// Mapping of wallets to the resale listing of their license
+    listings: LookupMap<String, Listing>,
+    /// Share of every resale price kept as royalty, in basis points
+    resale_royalty_bps: u16,
+    /// Mapping of wallets to the terms on which they offer their license for rent
+    rental_offers: LookupMap<String, RentalOffer>,
+    /// Mapping of lender wallets to their current or last unsettled rental
+    rentals: LookupMap<String, Rental>,
+    /// Mapping of renter wallets to the lender wallet they rent from
+    rented_from: LookupMap<String, String>,
+    /// Lending contracts approved to place liens on licenses
+    lien_holders: LookupSet<AccountId>,
+    /// Mapping of wallets to the lien holder they authorized to place a lien
+    lien_authorizations: LookupMap<String, AccountId>,
+    /// Mapping of wallets to the lien on their license
+    liens: LookupMap<String, Lien>,
+    /// Mapping of wallets to the operators approved to transfer their license, with approval IDs
+    approvals: LookupMap<String, HashMap<AccountId, u64>>,
+    /// Approval ID handed out by the next operator approval
+    next_approval_id: u64,
+    /// Receipts of every monetary operation, indexed by receipt ID
+    receipts: Vector<Receipt>,
+    /// Mapping of wallets to the transfer history of the license they hold
+    transfer_history: LookupMap<String, Vec<TransferRecord>>,
+    /// Number of timestamp licenses active as of `activity_day`
+    active_licenses: u64,
+    /// Day (since the Unix epoch) the activity counters have been rolled forward to
+    activity_day: u64,
+    /// Mapping of days to the number of active timestamp licenses whose last active day they are
+    expiry_buckets: LookupMap<u64, u64>,
+    /// Mapping of closed-out days to their number of active timestamp licenses
+    daily_active: LookupMap<u64, u64>,
+    /// Number of timestamp license grants per granted duration
+    duration_histogram: DurationHistogram,
+    /// Counts of operations performed since deployment
+    metrics: OperationMetrics,
+    /// XOR of the digests of every license entry, see `get_state_checksum`
+    state_checksum: [u8; 32],
+    /// Whether change methods record their gas usage in `gas_profile`
+    gas_profiling: bool,
+    /// Mapping of operation classes to their recorded gas usage
+    gas_profile: LookupMap<GasClass, GasStats>,
+    /// Mapping of wallets to their renewal reminder opt-in
+    reminder_preferences: LookupMap<String, ReminderPreference>,
+    /// Account allowed to emit renewal reminders besides the admin
+    reminder_keeper: Option<AccountId>,
+    /// Mapping of wallets to who last granted or moved their license, and how
+    provenance: LookupMap<String, Provenance>,
+    /// Mapping of wallets to their timestamp license coverage aggregates
+    tenures: LookupMap<String, TenureRecord>,
+    /// Mapping of app features to the share of licensed wallets they are rolled out to, in basis points
+    features: LookupMap<String, u16>,
+    /// Mapping of batch job kinds to the status of their current or last run
+    jobs: LookupMap<JobKind, JobStatus>,
+    /// Whether grants, transfers and recoveries are paused
+    paused: bool,
+    /// Whether holder transfers and resales are frozen
+    transfers_frozen: bool,
+    /// Account allowed to pause and unpause besides the admin
+    pauser: Option<AccountId>,
+    /// Every change of the admin and other privileged roles, oldest first
+    role_history: Vector<RoleChange>,
+    /// Delay between queuing and executing admin actions in nanoseconds, 0 if they apply immediately
+    action_delay_ns: u64,
+    /// Admin actions waiting out the action delay, oldest first
+    pending_actions: Vec<QueuedAction>,
+    /// ID handed out to the next queued admin action
+    next_action_id: u64,
+    /// Account allowed to veto queued admin actions during their delay window
+    guardian: Option<AccountId>,
+    /// Account allowed to shut the contract down, e.g. a multisig or DAO
+    shutdown_authority: Option<AccountId>,
+    /// Account collected fees are recovered to after a shutdown
+    treasury_recovery_account: Option<AccountId>,
+    /// Whether the contract was permanently shut down
+    shut_down: bool,
+    /// Account approving grants longer than the long grant threshold
+    grant_cosigner: Option<AccountId>,
+    /// Longest single grant in days the admin can make without the co-signer
+    long_grant_threshold_days: Option<u32>,
+    /// Long grants waiting for the co-signer's approval, oldest first
+    pending_grants: Vec<PendingGrant>,
+    /// ID handed out to the next proposed long grant
+    next_grant_id: u64,
+    /// Change of the co-signer or long grant threshold the co-signer approved, not yet applied
+    approved_quorum_change: Option<AdminAction>,
+    /// Mapping of operations to the roles allowed to perform them, where changed from the defaults
+    permissions: LookupMap<Permission, Vec<Role>>,
+    /// Number of role holders that must approve each call to an operation, where above 1
+    permission_thresholds: LookupMap<Permission, u8>,
+    /// Accounts that approved a call to an operation, keyed by the operation and the
+    /// hex sha256 of the call's arguments
+    operation_approvals: LookupMap<(Permission, String), Vec<AccountId>>,
+    /// Floating pools of concurrent seats, indexed by pool ID
+    pools: LookupMap<u64, FloatingPool>,
+    /// ID handed out to the next floating pool
+    next_pool_id: u64,
+    /// Pool IDs paired with the wallets allowed to check out their seats
+    pool_members: LookupSet<(u64, String)>,
+    /// Mapping of wallets to the pools they hold a seat in
+    seat_pools: LookupMap<String, Vec<u64>>,
+    /// Pool ID the next `reap_stale_seats` call scans first
+    reap_cursor: u64,
+    /// Mapping of wallets to the serial number of the license they hold
+    serials: LookupMap<String, u64>,
+    /// Mapping of serial numbers in use to the wallet holding the license
+    serial_wallets: LookupMap<u64, String>,
+    /// IDs of payment receipts already redeemed
+    redeemed_receipts: LookupSet<u64>,
+    /// Fulfillment account allowed to redeem receipts besides the admin
+    receipt_redeemer: Option<AccountId>,
+    /// Keeper account allowed to run payouts and distribute revenue besides the admin
+    payout_keeper: Option<AccountId>,
+    /// Serial number given to the next new license
+    next_serial: u64,
+    /// Mapping of wallets to the storage deposit recorded against their license
+    storage_deposits: LookupMap<String, StorageDeposit>,
 }
 
 #[near]
@@ -29,79 +356,264 @@ impl LicenseContract {
     ///
     /// # Arguments
     /// * `admin` - The account ID that will have permission to grant licenses
+    /// * `expected_network` - The network this deployment is intended for
+    ///
+    /// # Panics
+    /// Panics if the contract account doesn't belong to `expected_network`
     #[init]
-    pub fn new(admin: AccountId) -> Self {
-        Self {
-            licenses: LookupMap::new(b"l"),
-            admin,
+    pub fn new(admin: AccountId, expected_network: Network) -> Self {
+        expected_network.assert_current_account();
+
+        Self::init_state(admin, expected_network)
+    }
+
+    /// Initialize the contract with an admin account and initial settings.
+    ///
+    /// # Arguments
+    /// * `admin` - The account ID that will have permission to grant licenses
+    /// * `expected_network` - The network this deployment is intended for
+    /// * `config` - Compliance and jurisdiction settings to start with
+    ///
+    /// # Panics
+    /// Panics if the contract account doesn't belong to `expected_network`
+    /// or a blocked jurisdiction code is malformed
+    #[init]
+    pub fn new_with_config(admin: AccountId, expected_network: Network, config: InitConfig) -> Self {
+        let mut contract = Self::new(admin, expected_network);
+        contract.compliance_officer = config.compliance_officer;
+        contract.verification_required = config.verification_required;
+        if !config.blocked_jurisdictions.is_empty() {
+            contract.replace_blocked_jurisdictions(config.blocked_jurisdictions);
         }
+        contract
     }
 
     /// Migrate from old contract state (AccountId keys) to new state (String keys).
     /// This preserves the admin but creates a new empty licenses map.
+    /// All state added since (block-height and epoch licenses, compliance, etc.) starts out empty.
     /// Existing licenses will remain accessible if they were stored with the same prefix,
     /// since String serialization of valid AccountIds is compatible.
     ///
+    /// # Arguments
+    /// * `expected_network` - The network this deployment is intended for
+    ///
     /// # Panics
-    /// Panics if caller is not the admin
+    /// Panics if caller is not the admin, or if the contract account doesn't
+    /// belong to `expected_network`
     #[private]
     #[init(ignore_state)]
-    pub fn migrate() -> Self {
+    pub fn migrate(expected_network: Network) -> Self {
+        expected_network.assert_current_account();
+
         let old_state: OldLicenseContract = env::state_read().expect("Failed to read old state");
 
         // The old LookupMap used AccountId keys with prefix "l"
         // The new LookupMap uses String keys with the same prefix "l"
         // Since AccountId serializes to a string, existing entries are compatible
         // We just need to create the new state with the same prefix
-        Self {
-            licenses: LookupMap::new(b"l"),
-            admin: old_state.admin,
-        }
+        let mut contract = Self::init_state(old_state.admin, expected_network);
+        contract.migrated = true;
+        contract
     }
 
     /// Grant a license to a wallet for a specified duration.
     /// If the wallet already has a license, extends from the current expiry.
     /// If no existing license or expired, starts from current block timestamp.
+    /// Issues a verifiable credential covering the new expiry.
     ///
     /// # Arguments
     /// * `wallet_address` - The wallet address to grant the license to (NEAR account, EVM address, etc.)
     /// * `duration_days` - Number of days to grant the license for
+    /// * `correlation_id` - Optional order or payment ID echoed in the `license_granted` event
+    /// * `memo` - Optional justification (e.g. a support ticket) stored with the license's provenance
     ///
     /// # Panics
-    /// Panics if caller is not the admin
-    pub fn grant_license(&mut self, wallet_address: String, duration_days: u32) {
-        require!(
-            env::predecessor_account_id() == self.admin,
-            "Unauthorized: only admin can grant licenses"
-        );
+    /// Panics if caller is not the admin, the duration overflows, the remaining term would exceed
+    /// the long grant threshold, or the correlation ID or memo is too long
+    pub fn grant_license(
+        &mut self,
+        wallet_address: String,
+        duration_days: u32,
+        correlation_id: Option<String>,
+        memo: Option<String>,
+    ) {
+        // Calculate duration in nanoseconds: days * 24 * 60 * 60 * 1_000_000_000
+        let duration_ns = (duration_days as u64)
+            .checked_mul(ONE_DAY_NS)
+            .unwrap_or_else(|| env::panic_str("Expiry timestamp overflow"));
+        self.extend_license(wallet_address, duration_ns, correlation_id, memo);
+    }
 
-        let current_timestamp = env::block_timestamp();
+    /// Grant a license to a wallet for a number of hours, e.g. for 24-hour passes.
+    /// Extends from the current expiry like `grant_license`.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet address to grant the license to
+    /// * `duration_hours` - Number of hours to grant the license for
+    /// * `correlation_id` - Optional order or payment ID echoed in the `license_granted` event
+    /// * `memo` - Optional justification (e.g. a support ticket) stored with the license's provenance
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, the duration overflows, the remaining term would exceed
+    /// the long grant threshold, or the correlation ID or memo is too long
+    pub fn grant_license_hours(
+        &mut self,
+        wallet_address: String,
+        duration_hours: u32,
+        correlation_id: Option<String>,
+        memo: Option<String>,
+    ) {
+        let duration_ns = (duration_hours as u64)
+            .checked_mul(ONE_HOUR_NS)
+            .unwrap_or_else(|| env::panic_str("Expiry timestamp overflow"));
+        self.extend_license(wallet_address, duration_ns, correlation_id, memo);
+    }
 
-        // Get current expiry, use current timestamp if not set or already expired
-        let base_timestamp = self.licenses
-            .get(&wallet_address)
-            .copied()
-            .filter(|&expiry| expiry > current_timestamp)
-            .unwrap_or(current_timestamp);
+    /// Grant a license to a wallet for a number of seconds, e.g. for demo sessions.
+    /// Extends from the current expiry like `grant_license`.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet address to grant the license to
+    /// * `duration_seconds` - Number of seconds to grant the license for
+    /// * `correlation_id` - Optional order or payment ID echoed in the `license_granted` event
+    /// * `memo` - Optional justification (e.g. a support ticket) stored with the license's provenance
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, the duration overflows, the remaining term would exceed
+    /// the long grant threshold, or the correlation ID or memo is too long
+    pub fn grant_license_seconds(
+        &mut self,
+        wallet_address: String,
+        duration_seconds: u64,
+        correlation_id: Option<String>,
+        memo: Option<String>,
+    ) {
+        let duration_ns = duration_seconds
+            .checked_mul(1_000_000_000)
+            .unwrap_or_else(|| env::panic_str("Expiry timestamp overflow"));
+        self.extend_license(wallet_address, duration_ns, correlation_id, memo);
+    }
 
-        // Calculate duration in nanoseconds: days * 24 * 60 * 60 * 1_000_000_000
-        let duration_ns = duration_days as u64 * 24 * 60 * 60 * 1_000_000_000;
-        let new_expiry = base_timestamp + duration_ns;
+    /// Grant a license to a wallet for a specified number of blocks.
+    /// If the wallet already has a block-height license, extends from the current expiry height.
+    /// If no existing license or expired, starts from the current block height.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet address to grant the license to
+    /// * `duration_blocks` - Number of blocks to grant the license for
+    /// * `correlation_id` - Optional order or payment ID echoed in the `license_granted` event
+    /// * `memo` - Optional justification (e.g. a support ticket) stored with the license's provenance
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, the remaining term would exceed the long grant threshold
+    /// (at one block per second), or the correlation ID or memo is too long
+    pub fn grant_license_blocks(
+        &mut self,
+        wallet_address: String,
+        duration_blocks: u64,
+        correlation_id: Option<String>,
+        memo: Option<String>,
+    ) {
+        let key = self.wallet_key(&wallet_address);
+        self.assert_grant_allowed(&key);
+
+        let current_height = env::block_height();
+
+        let old_expiry = self.height_licenses.get(&key).copied();
+        let base_height = old_expiry
+            .filter(|&expiry| expiry > current_height)
+            .unwrap_or(current_height);
+
+        let new_expiry = base_height
+            .checked_add(duration_blocks)
+            .unwrap_or_else(|| env::panic_str("Expiry height overflow"));
 
-        self.licenses.insert(wallet_address, new_expiry);
+        self.index_wallet(&key);
+        self.finish_grant(ExpiryKind::Height, key, old_expiry, new_expiry, correlation_id, memo);
+    }
+
+    /// Grant a license to a wallet for a specified number of epochs.
+    /// If the wallet already has an epoch license, extends from the current expiry epoch.
+    /// If no existing license or expired, starts from the current epoch.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet address to grant the license to
+    /// * `duration_epochs` - Number of epochs to grant the license for
+    /// * `correlation_id` - Optional order or payment ID echoed in the `license_granted` event
+    /// * `memo` - Optional justification (e.g. a support ticket) stored with the license's provenance
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, the remaining term would exceed the long grant threshold,
+    /// or the correlation ID or memo is too long
+    pub fn grant_license_epochs(
+        &mut self,
+        wallet_address: String,
+        duration_epochs: u64,
+        correlation_id: Option<String>,
+        memo: Option<String>,
+    ) {
+        let key = self.wallet_key(&wallet_address);
+        self.assert_grant_allowed(&key);
+
+        let current_epoch = env::epoch_height();
+
+        let old_expiry = self.epoch_licenses.get(&key).copied();
+        let base_epoch = old_expiry
+            .filter(|&expiry| expiry > current_epoch)
+            .unwrap_or(current_epoch);
+
+        let new_expiry = base_epoch
+            .checked_add(duration_epochs)
+            .unwrap_or_else(|| env::panic_str("Expiry epoch overflow"));
+
+        self.index_wallet(&key);
+        self.finish_grant(ExpiryKind::Epoch, key, old_expiry, new_expiry, correlation_id, memo);
     }
 
     /// Check if a wallet has a valid (non-expired) license.
+    /// A license is valid if any of its timestamp, block-height or epoch expiries
+    /// lies in the future. Wallets linked to a DID are also licensed while the DID is,
+    /// and wallets holding a floating pool seat while they hold it.
     ///
     /// # Arguments
-    /// * `wallet_address` - The wallet address to check
+    /// * `wallet_address` - The wallet address (or DID) to check
     ///
     /// # Returns
     /// `true` if the wallet has a license that hasn't expired, `false` otherwise
     pub fn is_licensed(&self, wallet_address: String) -> bool {
-        self.licenses
-            .get(&wallet_address)
-            .map(|&expiry| expiry > env::block_timestamp())
+        let key = self.wallet_key(&wallet_address);
+        self.is_key_licensed(&key) || self.is_linked_did_licensed(&key)
+    }
+
+    /// Check if a wallet's block-height license is valid at a given height.
+    /// Only block-height licenses are considered, so the answer is reproducible
+    /// by verifiers working against archival state at that height.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet address to check
+    /// * `block_height` - The block height to check validity at
+    ///
+    /// # Returns
+    /// `true` if the wallet's license expires after `block_height`, `false` otherwise
+    pub fn is_licensed_at_height(&self, wallet_address: String, block_height: u64) -> bool {
+        self.height_licenses
+            .get(&self.wallet_key(&wallet_address))
+            .map(|&expiry| expiry > block_height)
+            .unwrap_or(false)
+    }
+
+    /// Check if a wallet's epoch license is valid in a given epoch.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet address to check
+    /// * `epoch` - The epoch height to check validity at
+    ///
+    /// # Returns
+    /// `true` if the wallet's license expires after `epoch`, `false` otherwise
+    pub fn is_licensed_at_epoch(&self, wallet_address: String, epoch: u64) -> bool {
+        self.epoch_licenses
+            .get(&self.wallet_key(&wallet_address))
+            .map(|&expiry| expiry > epoch)
             .unwrap_or(false)
     }
 
@@ -113,7 +625,325 @@ impl LicenseContract {
     /// # Returns
     /// `Some(timestamp)` if the wallet has a license entry, `None` otherwise
     pub fn get_expiry(&self, wallet_address: String) -> Option<u64> {
-        self.licenses.get(&wallet_address).copied()
+        self.licenses.get(&self.wallet_key(&wallet_address)).copied()
+    }
+
+    /// Get the raw expiry block height for a wallet.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet address to query
+    ///
+    /// # Returns
+    /// `Some(height)` if the wallet has a block-height license entry, `None` otherwise
+    pub fn get_expiry_height(&self, wallet_address: String) -> Option<u64> {
+        self.height_licenses.get(&self.wallet_key(&wallet_address)).copied()
+    }
+
+    /// Get the raw expiry epoch for a wallet.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet address to query
+    ///
+    /// # Returns
+    /// `Some(epoch)` if the wallet has an epoch license entry, `None` otherwise
+    pub fn get_expiry_epoch(&self, wallet_address: String) -> Option<u64> {
+        self.epoch_licenses.get(&self.wallet_key(&wallet_address)).copied()
+    }
+
+    /// Get the network this deployment was initialized for.
+    pub fn get_network(&self) -> Network {
+        self.network
+    }
+
+    /// Get the current epoch height.
+    pub fn get_current_epoch() -> u64 {
+        env::epoch_height()
+    }
+
+    /// Convert a number of days to the number of epochs covering them (rounded up).
+    /// Based on the nominal epoch length, see `EPOCH_DURATION_NS`.
+    pub fn days_to_epochs(days: u32) -> u64 {
+        (days as u64).saturating_mul(ONE_DAY_NS).div_ceil(EPOCH_DURATION_NS)
+    }
+
+    /// Convert a number of epochs to whole days (rounded down).
+    /// Based on the nominal epoch length, see `EPOCH_DURATION_NS`.
+    pub fn epochs_to_days(epochs: u64) -> u64 {
+        epochs.saturating_mul(EPOCH_DURATION_NS) / ONE_DAY_NS
+    }
+
+    /// Extend a wallet's timestamp license by `duration_ns`, starting from the current
+    /// expiry if it hasn't passed and from the current block timestamp otherwise
+    fn extend_license(
+        &mut self,
+        wallet_address: String,
+        duration_ns: u64,
+        correlation_id: Option<String>,
+        memo: Option<String>,
+    ) {
+        let key = self.wallet_key(&wallet_address);
+        self.assert_grant_allowed(&key);
+        self.extend_key(key, duration_ns, correlation_id, memo);
+    }
+
+    /// Extend the timestamp license stored under a wallet key by `duration_ns`,
+    /// once the grant was authorized
+    fn extend_key(&mut self, key: String, duration_ns: u64, correlation_id: Option<String>, memo: Option<String>) {
+        let current_timestamp = env::block_timestamp();
+
+        // Get current expiry, use current timestamp if not set or already expired
+        let old_expiry = self.licenses.get(&key).copied();
+        let base_timestamp = old_expiry
+            .filter(|&expiry| expiry > current_timestamp)
+            .unwrap_or(current_timestamp);
+
+        let new_expiry = base_timestamp
+            .checked_add(duration_ns)
+            .unwrap_or_else(|| env::panic_str("Expiry timestamp overflow"));
+
+        self.track_activity(old_expiry, new_expiry);
+        self.duration_histogram.record(duration_ns);
+        self.index_wallet(&key);
+        self.issue_credential(&key, new_expiry);
+        self.finish_grant(ExpiryKind::Timestamp, key, old_expiry, new_expiry, correlation_id, memo);
+    }
+
+    /// Store a granted expiry, count the grant and emit its `license_granted` event.
+    /// The term left after the grant is held to the long grant threshold, so stacked
+    /// grants can't get around it; grants approved by the co-signer aren't limited
+    fn finish_grant(
+        &mut self,
+        kind: ExpiryKind,
+        key: String,
+        previous_expiry: Option<u64>,
+        new_expiry: u64,
+        correlation_id: Option<String>,
+        memo: Option<String>,
+    ) {
+        assert_valid_correlation_id(&correlation_id);
+        assert_valid_memo(&memo);
+        if self.grant_cosigner.as_ref() != Some(&env::predecessor_account_id()) {
+            self.assert_term_within_grant_threshold(kind, new_expiry);
+        }
+
+        self.write_expiry(kind, &key, new_expiry);
+        self.assign_serial(&key);
+        self.record_provenance(&key, GrantSource::Admin, memo.clone());
+        self.metrics.grants += 1;
+
+        let serial = self.serials.get(&key).copied();
+        LicenseEvent::LicenseGranted {
+            wallet_address: key,
+            expiry_kind: kind,
+            previous_expiry,
+            new_expiry,
+            serial,
+            correlation_id,
+            memo,
+        }
+        .emit();
+        self.record_gas(GasClass::Grant);
+    }
+
+    /// Check if the wallet stored under a key is licensed, by its own license, a rental
+    /// or a floating pool seat
+    pub(crate) fn is_key_licensed(&self, key: &str) -> bool {
+        self.holds_license(key) || self.is_renting(key) || self.holds_seat(key)
+    }
+
+    /// Check if the license stored under a wallet key is valid under any expiry mode
+    /// and not lent out
+    pub(crate) fn holds_license(&self, key: &str) -> bool {
+        if self.is_lent_out(key) {
+            return false;
+        }
+
+        let by_timestamp = self.licenses
+            .get(key)
+            .map(|&expiry| expiry > env::block_timestamp())
+            .unwrap_or(false);
+        let by_height = self.height_licenses
+            .get(key)
+            .map(|&expiry| expiry > env::block_height())
+            .unwrap_or(false);
+        let by_epoch = self.epoch_licenses
+            .get(key)
+            .map(|&expiry| expiry > env::epoch_height())
+            .unwrap_or(false);
+
+        by_timestamp || by_height || by_epoch
+    }
+
+    /// Build contract state around an admin, with the licenses map under prefix "l",
+    /// the admin recorded as the first role change and every other collection empty
+    fn init_state(admin: AccountId, network: Network) -> Self {
+        let mut contract = Self {
+            licenses: LookupMap::new(b"l"),
+            admin: admin.clone(),
+            height_licenses: LookupMap::new(b"h"),
+            epoch_licenses: LookupMap::new(b"e"),
+            network,
+            compliance_officer: None,
+            verification_required: false,
+            compliance: LookupMap::new(b"c"),
+            blocked_jurisdictions: Vec::new(),
+            jurisdictions: LookupMap::new(b"j"),
+            wallets: Vector::new(b"w"),
+            wallet_positions: LookupMap::new(b"p"),
+            privacy_salt: None,
+            migrated: false,
+            commitment_licenses: LookupMap::new(b"m"),
+            did_wallets: LookupMap::new(b"d"),
+            wallet_dids: LookupMap::new(b"D"),
+            credentials: Vector::new(b"v"),
+            wallet_credentials: LookupMap::new(b"B"),
+            recovery_guardians: LookupMap::new(b"g"),
+            recovery_requests: LookupMap::new(b"r"),
+            recovery_timelock_ns: DEFAULT_RECOVERY_TIMELOCK_DAYS as u64 * ONE_DAY_NS,
+            transfer_config: TransferConfig::default(),
+            last_transfers: LookupMap::new(b"t"),
+            collected_fees: NearToken::from_yoctonear(0),
+            withdrawal_limit: None,
+            recent_withdrawals: Vec::new(),
+            payout_schedules: Vec::new(),
+            revenue_split: Vec::new(),
+            accrued_payouts: LookupMap::new(b"Q"),
+            transfer_history: LookupMap::new(b"T"),
+            active_licenses: 0,
+            activity_day: env::block_timestamp() / ONE_DAY_NS,
+            expiry_buckets: LookupMap::new(b"x"),
+            daily_active: LookupMap::new(b"a"),
+            duration_histogram: DurationHistogram::default(),
+            metrics: OperationMetrics::default(),
+            state_checksum: [0; 32],
+            gas_profiling: false,
+            gas_profile: LookupMap::new(b"G"),
+            reminder_preferences: LookupMap::new(b"n"),
+            reminder_keeper: None,
+            provenance: LookupMap::new(b"P"),
+            tenures: LookupMap::new(b"U"),
+            features: LookupMap::new(b"F"),
+            listings: LookupMap::new(b"L"),
+            resale_royalty_bps: 0,
+            rental_offers: LookupMap::new(b"O"),
+            rentals: LookupMap::new(b"E"),
+            rented_from: LookupMap::new(b"N"),
+            lien_holders: LookupSet::new(b"H"),
+            lien_authorizations: LookupMap::new(b"z"),
+            liens: LookupMap::new(b"Z"),
+            approvals: LookupMap::new(b"A"),
+            next_approval_id: 0,
+            receipts: Vector::new(b"R"),
+            jobs: LookupMap::new(b"J"),
+            paused: false,
+            transfers_frozen: false,
+            pauser: None,
+            role_history: Vector::new(b"Y"),
+            action_delay_ns: 0,
+            pending_actions: Vec::new(),
+            next_action_id: 0,
+            guardian: None,
+            shutdown_authority: None,
+            treasury_recovery_account: None,
+            shut_down: false,
+            grant_cosigner: None,
+            long_grant_threshold_days: None,
+            pending_grants: Vec::new(),
+            next_grant_id: 0,
+            approved_quorum_change: None,
+            permissions: LookupMap::new(b"K"),
+            permission_thresholds: LookupMap::new(b"C"),
+            operation_approvals: LookupMap::new(b"b"),
+            pools: LookupMap::new(b"S"),
+            next_pool_id: 0,
+            pool_members: LookupSet::new(b"M"),
+            seat_pools: LookupMap::new(b"s"),
+            reap_cursor: 0,
+            serials: LookupMap::new(b"V"),
+            serial_wallets: LookupMap::new(b"W"),
+            redeemed_receipts: LookupSet::new(b"I"),
+            receipt_redeemer: None,
+            payout_keeper: None,
+            next_serial: 1,
+            storage_deposits: LookupMap::new(b"f"),
+        };
+        contract.role_history.push(RoleChange {
+            role: Role::Admin,
+            account: Some(admin),
+            changed_by: env::predecessor_account_id(),
+            changed_at: env::block_timestamp(),
+        });
+        contract
+    }
+
+    /// Panics unless the contract is running and the caller is the admin
+    fn assert_admin(&self) {
+        self.assert_not_shut_down();
+        require!(
+            env::predecessor_account_id() == self.admin,
+            "Unauthorized: only admin can perform this action"
+        );
+    }
+
+    /// Panics unless the contract is unpaused and the caller is allowed to grant licenses
+    fn assert_can_grant(&mut self) {
+        self.assert_not_paused();
+        self.assert_permitted(Permission::GrantLicenses);
+    }
+
+    /// Panics unless the caller may grant and the wallet stored under `key` is eligible for a license
+    fn assert_grant_allowed(&mut self, key: &str) {
+        self.assert_can_grant();
+        self.assert_eligible(key);
+    }
+
+    /// Panics unless the wallet stored under `key` passes compliance and jurisdiction checks
+    pub(crate) fn assert_eligible(&self, key: &str) {
+        self.assert_compliant(key);
+        self.assert_jurisdiction_allowed(key);
+    }
+
+    /// Move every license entry (timestamp, block-height and epoch) from one wallet key
+    /// to another, which must be eligible and hold no unexpired license entries of its own.
+    /// The recipient's lapsed license is released first. The sender's credential is
+    /// revoked and the recipient issued one.
+    /// Panics while the contract is paused
+    pub(crate) fn move_license(&mut self, from_key: &str, to_key: &str) {
+        require!(
+            ExpiryKind::ALL
+                .into_iter()
+                .all(|kind| self.read_expiry(kind, to_key).is_none_or(|expiry| kind.has_passed(expiry))),
+            "Target wallet already holds a license"
+        );
+        self.assert_not_paused();
+        self.assert_eligible(to_key);
+        self.release_lapsed_license(to_key);
+
+        for kind in ExpiryKind::ALL {
+            if let Some(expiry) = self.take_expiry(kind, from_key) {
+                self.write_expiry(kind, to_key, expiry);
+            }
+        }
+        self.index_wallet(to_key);
+
+        self.revoke_wallet_credential(from_key, "License moved to another wallet");
+        if let Some(expiry) = self.licenses.get(to_key).copied() {
+            self.issue_credential(to_key, expiry);
+        }
+    }
+
+    /// Remove the expired license entries stored under `key`, along with the serial,
+    /// listing, rental offer and operator approvals of that license, so they don't
+    /// carry over to a license moving in
+    fn release_lapsed_license(&mut self, key: &str) {
+        require!(!self.liens.contains_key(key), "Target wallet's license is under a lien");
+        for kind in ExpiryKind::ALL {
+            self.take_expiry(kind, key);
+        }
+        self.retire_serial(key);
+        self.listings.remove(key);
+        self.rental_offers.remove(key);
+        self.approvals.remove(key);
     }
 }
 
@@ -123,25 +953,23 @@ mod tests {
     use near_sdk::test_utils::VMContextBuilder;
     use near_sdk::testing_env;
 
-    const ONE_DAY_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
-
-    fn admin() -> AccountId {
+    pub(crate) fn admin() -> AccountId {
         "admin.near".parse().unwrap()
     }
 
-    fn user() -> AccountId {
+    pub(crate) fn user() -> AccountId {
         "user.near".parse().unwrap()
     }
 
-    fn user_str() -> String {
+    pub(crate) fn user_str() -> String {
         "user.near".to_string()
     }
 
-    fn evm_address() -> String {
+    pub(crate) fn evm_address() -> String {
         "0x1234567890abcdef1234567890abcdef12345678".to_string()
     }
 
-    fn setup_context(predecessor: &AccountId, block_timestamp: u64) {
+    pub(crate) fn setup_context(predecessor: &AccountId, block_timestamp: u64) {
         let context = VMContextBuilder::new()
             .predecessor_account_id(predecessor.clone())
             .block_timestamp(block_timestamp)
@@ -149,10 +977,26 @@ mod tests {
         testing_env!(context);
     }
 
+    fn setup_context_at_epoch(predecessor: &AccountId, epoch_height: u64) {
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(predecessor.clone())
+            .epoch_height(epoch_height)
+            .build();
+        testing_env!(context);
+    }
+
+    fn setup_context_at_height(predecessor: &AccountId, block_height: u64) {
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(predecessor.clone())
+            .block_height(block_height)
+            .build();
+        testing_env!(context);
+    }
+
     #[test]
     fn test_new_initializes_admin() {
         setup_context(&admin(), 0);
-        let contract = LicenseContract::new(admin());
+        let contract = LicenseContract::new(admin(), Network::Mainnet);
 
         // Verify admin is set by trying to grant license (only admin can do this)
         // If admin wasn't set correctly, this would panic
@@ -162,9 +1006,9 @@ mod tests {
     #[test]
     fn test_grant_license_by_admin() {
         setup_context(&admin(), 1_000_000_000);
-        let mut contract = LicenseContract::new(admin());
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
 
-        contract.grant_license(user_str(), 30);
+        contract.grant_license(user_str(), 30, None, None);
 
         assert!(contract.is_licensed(user_str()));
 
@@ -176,10 +1020,10 @@ mod tests {
     #[test]
     fn test_grant_license_to_evm_address() {
         setup_context(&admin(), 1_000_000_000);
-        let mut contract = LicenseContract::new(admin());
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
 
         // Grant license to an EVM address
-        contract.grant_license(evm_address(), 30);
+        contract.grant_license(evm_address(), 30, None, None);
 
         assert!(contract.is_licensed(evm_address()));
 
@@ -192,21 +1036,21 @@ mod tests {
     #[should_panic(expected = "Unauthorized: only admin can grant licenses")]
     fn test_grant_license_unauthorized() {
         setup_context(&admin(), 0);
-        let mut contract = LicenseContract::new(admin());
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
 
         // Switch to non-admin context
         setup_context(&user(), 0);
-        contract.grant_license(user_str(), 30);
+        contract.grant_license(user_str(), 30, None, None);
     }
 
     #[test]
     fn test_license_expiry() {
         let initial_time = 1_000_000_000u64;
         setup_context(&admin(), initial_time);
-        let mut contract = LicenseContract::new(admin());
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
 
         // Grant 1 day license
-        contract.grant_license(user_str(), 1);
+        contract.grant_license(user_str(), 1, None, None);
         assert!(contract.is_licensed(user_str()));
 
         // Move time forward past expiry
@@ -225,17 +1069,17 @@ mod tests {
     fn test_extend_license() {
         let initial_time = 1_000_000_000u64;
         setup_context(&admin(), initial_time);
-        let mut contract = LicenseContract::new(admin());
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
 
         // Grant initial 30-day license
-        contract.grant_license(user_str(), 30);
+        contract.grant_license(user_str(), 30, None, None);
         let first_expiry = contract.get_expiry(user_str()).unwrap();
         assert_eq!(first_expiry, initial_time + 30 * ONE_DAY_NS);
 
         // Extend by another 30 days (before expiry)
         let halfway = initial_time + 15 * ONE_DAY_NS;
         setup_context(&admin(), halfway);
-        contract.grant_license(user_str(), 30);
+        contract.grant_license(user_str(), 30, None, None);
 
         // New expiry should be first_expiry + 30 days (extends from existing, not current time)
         let new_expiry = contract.get_expiry(user_str()).unwrap();
@@ -244,4 +1088,216 @@ mod tests {
         // Verify it's still licensed
         assert!(contract.is_licensed(user_str()));
     }
+
+    #[test]
+    fn test_grant_license_blocks() {
+        setup_context_at_height(&admin(), 100);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license_blocks(user_str(), 50, None, None);
+
+        assert_eq!(contract.get_expiry_height(user_str()), Some(150));
+        assert!(contract.is_licensed(user_str()));
+        assert!(contract.is_licensed_at_height(user_str(), 149));
+        assert!(!contract.is_licensed_at_height(user_str(), 150));
+
+        // Timestamp expiry is untouched by block-height grants
+        assert_eq!(contract.get_expiry(user_str()), None);
+
+        // Past the expiry height the wallet is no longer licensed
+        setup_context_at_height(&admin(), 150);
+        assert!(!contract.is_licensed(user_str()));
+    }
+
+    #[test]
+    fn test_extend_license_blocks() {
+        setup_context_at_height(&admin(), 100);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license_blocks(user_str(), 50, None, None);
+
+        // Extending before expiry builds on the existing expiry height
+        setup_context_at_height(&admin(), 120);
+        contract.grant_license_blocks(user_str(), 50, None, None);
+        assert_eq!(contract.get_expiry_height(user_str()), Some(200));
+
+        // Granting after expiry starts from the current height
+        setup_context_at_height(&admin(), 500);
+        contract.grant_license_blocks(user_str(), 10, None, None);
+        assert_eq!(contract.get_expiry_height(user_str()), Some(510));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only admin can grant licenses")]
+    fn test_grant_license_blocks_unauthorized() {
+        setup_context_at_height(&admin(), 100);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        setup_context_at_height(&user(), 100);
+        contract.grant_license_blocks(user_str(), 50, None, None);
+    }
+
+    #[test]
+    fn test_grant_license_epochs() {
+        setup_context_at_epoch(&admin(), 10);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license_epochs(user_str(), 4, None, None);
+
+        assert_eq!(contract.get_expiry_epoch(user_str()), Some(14));
+        assert!(contract.is_licensed(user_str()));
+        assert!(contract.is_licensed_at_epoch(user_str(), 13));
+        assert!(!contract.is_licensed_at_epoch(user_str(), 14));
+
+        // Extending before expiry builds on the existing expiry epoch
+        setup_context_at_epoch(&admin(), 12);
+        contract.grant_license_epochs(user_str(), 4, None, None);
+        assert_eq!(contract.get_expiry_epoch(user_str()), Some(18));
+
+        setup_context_at_epoch(&admin(), 18);
+        assert!(!contract.is_licensed(user_str()));
+    }
+
+    #[test]
+    fn test_epoch_conversion_helpers() {
+        assert_eq!(LicenseContract::days_to_epochs(0), 0);
+        assert_eq!(LicenseContract::days_to_epochs(1), 2);
+        assert_eq!(LicenseContract::days_to_epochs(30), 60);
+        assert_eq!(LicenseContract::epochs_to_days(60), 30);
+        assert_eq!(LicenseContract::epochs_to_days(3), 1);
+        // Saturates instead of overflowing
+        assert_eq!(LicenseContract::days_to_epochs(u32::MAX), u64::MAX.div_ceil(EPOCH_DURATION_NS));
+    }
+
+    #[test]
+    fn test_new_records_network() {
+        setup_context(&admin(), 0);
+        let contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        assert_eq!(contract.get_network(), Network::Mainnet);
+    }
+
+    #[test]
+    #[should_panic(expected = "Network mismatch")]
+    fn test_new_rejects_wrong_network() {
+        setup_context(&admin(), 0);
+        // The default test contract account is alice.near, a mainnet account
+        LicenseContract::new(admin(), Network::Testnet);
+    }
+
+    #[test]
+    fn test_new_accepts_implicit_and_localnet_accounts() {
+        let implicit: AccountId = "98793cd91a3f870fb126f66285808c7e094afcfc4eda8a970f6648cdf0dbd6de".parse().unwrap();
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(implicit)
+            .predecessor_account_id(admin())
+            .build());
+        LicenseContract::new(admin(), Network::Testnet);
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id("license.test".parse().unwrap())
+            .predecessor_account_id(admin())
+            .build());
+        assert_eq!(LicenseContract::new(admin(), Network::Localnet).get_network(), Network::Localnet);
+    }
+
+    #[test]
+    fn test_grant_emits_license_granted_event() {
+        setup_context(&admin(), 1_000);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license_seconds(user_str(), 1, None, None);
+
+        setup_context(&admin(), 2_000);
+        contract.grant_license_seconds(user_str(), 1, Some("in_123".to_string()), Some("SUP-42".to_string()));
+
+        let log = near_sdk::test_utils::get_logs().pop().unwrap();
+        let event: near_sdk::serde_json::Value =
+            near_sdk::serde_json::from_str(log.strip_prefix("EVENT_JSON:").unwrap()).unwrap();
+        assert_eq!(event["event"], "license_granted");
+        assert_eq!(
+            event["data"],
+            near_sdk::serde_json::json!({
+                "wallet_address": user_str(),
+                "expiry_kind": "timestamp",
+                "previous_expiry": 1_000_001_000u64,
+                "new_expiry": 2_000_001_000u64,
+                "serial": 1,
+                "correlation_id": "in_123",
+                "memo": "SUP-42",
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Correlation ID is too long")]
+    fn test_grant_rejects_long_correlation_id() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license(user_str(), 30, Some("x".repeat(129)), None);
+    }
+
+    #[test]
+    fn test_new_with_config() {
+        setup_context(&admin(), 0);
+        let officer: AccountId = "compliance.near".parse().unwrap();
+        let contract = LicenseContract::new_with_config(
+            admin(),
+            Network::Mainnet,
+            InitConfig {
+                compliance_officer: Some(officer.clone()),
+                verification_required: true,
+                blocked_jurisdictions: vec!["cu".to_string(), "CU".to_string(), "us-ny".to_string()],
+            },
+        );
+
+        assert_eq!(contract.admin, admin());
+        assert_eq!(contract.get_compliance_officer(), Some(officer));
+        assert!(contract.is_verification_required());
+        assert_eq!(contract.get_blocked_jurisdictions(), vec!["CU", "US-NY"]);
+    }
+
+    #[test]
+    fn test_grant_license_hours_and_seconds() {
+        let initial_time = 1_000_000_000u64;
+        setup_context(&admin(), initial_time);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license_hours(user_str(), 24, None, None);
+        assert_eq!(contract.get_expiry(user_str()), Some(initial_time + ONE_DAY_NS));
+
+        // Sub-day grants extend an active license like day grants do
+        contract.grant_license_seconds(user_str(), 90, None, None);
+        assert_eq!(contract.get_expiry(user_str()), Some(initial_time + ONE_DAY_NS + 90_000_000_000));
+
+        setup_context(&admin(), initial_time + ONE_DAY_NS + 90_000_000_000);
+        assert!(!contract.is_licensed(user_str()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expiry timestamp overflow")]
+    fn test_grant_license_seconds_overflow() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license_seconds(user_str(), u64::MAX, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expiry timestamp overflow")]
+    fn test_grant_license_days_overflow() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license(user_str(), u32::MAX, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expiry timestamp overflow")]
+    fn test_grant_license_hours_overflow() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license_hours(user_str(), u32::MAX, None, None);
+    }
 }
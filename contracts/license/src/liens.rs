@@ -0,0 +1,181 @@
+use near_sdk::{near, AccountId, env, require};
+
+use crate::events::LicenseEvent;
+use crate::{AdminAction, LicenseContract, LicenseContractExt};
+
+/// A lien a lending contract holds on a license, blocking its transfer until released.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lien {
+    pub holder: AccountId,
+    /// Timestamp the lien was placed in nanoseconds
+    pub placed_at: u64,
+}
+
+#[near]
+impl LicenseContract {
+    /// Approve (or revoke approval of) a lending contract as a lien holder.
+    /// Revoking approval doesn't release liens already placed.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or an action delay is set
+    pub fn set_lien_holder_approval(&mut self, account_id: AccountId, approved: bool) {
+        self.submit_action(AdminAction::SetLienHolderApproval { account_id, approved });
+    }
+
+    /// Authorize an approved lien holder to place a lien on the caller's license,
+    /// or withdraw the authorization with `None`. The authorization is used up by
+    /// `place_lien`.
+    ///
+    /// # Panics
+    /// Panics if the account isn't an approved lien holder
+    pub fn authorize_lien(&mut self, lien_holder: Option<AccountId>) {
+        self.assert_not_shut_down();
+        let key = self.wallet_key(env::predecessor_account_id().as_str());
+        match lien_holder {
+            Some(holder) => {
+                require!(self.lien_holders.contains(&holder), "Account is not an approved lien holder");
+                self.lien_authorizations.insert(key, holder);
+            }
+            None => {
+                self.lien_authorizations.remove(&key);
+            }
+        }
+    }
+
+    /// Place a lien on a license whose holder authorized the caller.
+    ///
+    /// # Panics
+    /// Panics if the caller isn't approved or authorized by the holder,
+    /// the wallet has no active license, or the license already has a lien
+    pub fn place_lien(&mut self, wallet_address: String) {
+        self.assert_not_shut_down();
+        let caller = env::predecessor_account_id();
+        require!(self.lien_holders.contains(&caller), "Account is not an approved lien holder");
+
+        let key = self.wallet_key(&wallet_address);
+        require!(
+            self.lien_authorizations.get(&key) == Some(&caller),
+            "Lien was not authorized by the license holder"
+        );
+        require!(self.holds_license(&key), "No active license to place a lien on");
+        require!(!self.liens.contains_key(&key), "License already has a lien");
+
+        self.lien_authorizations.remove(&key);
+        self.liens.insert(
+            key.clone(),
+            Lien {
+                holder: caller.clone(),
+                placed_at: env::block_timestamp(),
+            },
+        );
+
+        LicenseEvent::LienPlaced {
+            wallet_address: key,
+            lien_holder: caller,
+        }
+        .emit();
+    }
+
+    /// Release the caller's lien on a license.
+    ///
+    /// # Panics
+    /// Panics if the caller doesn't hold a lien on the license
+    pub fn release_lien(&mut self, wallet_address: String) {
+        self.assert_not_shut_down();
+        let caller = env::predecessor_account_id();
+        let key = self.wallet_key(&wallet_address);
+        require!(
+            self.liens.get(&key).is_some_and(|lien| lien.holder == caller),
+            "No lien held by caller on this license"
+        );
+
+        self.liens.remove(&key);
+
+        LicenseEvent::LienReleased {
+            wallet_address: key,
+            lien_holder: caller,
+        }
+        .emit();
+    }
+
+    /// Get the lien on a wallet's license, if any.
+    pub fn get_lien(&self, wallet_address: String) -> Option<Lien> {
+        self.liens.get(&self.wallet_key(&wallet_address)).cloned()
+    }
+
+    /// Check if an account is an approved lien holder.
+    pub fn is_approved_lien_holder(&self, account_id: AccountId) -> bool {
+        self.lien_holders.contains(&account_id)
+    }
+
+    /// Panics if the license stored under `key` has a lien
+    pub(crate) fn assert_no_lien(&self, key: &str) {
+        require!(!self.liens.contains_key(key), "License is under a lien");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+
+    fn lender() -> AccountId {
+        "lending.near".parse().unwrap()
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+        contract.set_lien_holder_approval(lender(), true);
+
+        setup_context(&user(), 0);
+        contract.authorize_lien(Some(lender()));
+        setup_context(&lender(), 0);
+        contract.place_lien(user_str());
+        contract
+    }
+
+    #[test]
+    fn test_lien_blocks_transfer_until_released() {
+        let mut contract = setup_contract();
+        assert_eq!(contract.get_lien(user_str()).unwrap().holder, lender());
+
+        contract.release_lien(user_str());
+        assert_eq!(contract.get_lien(user_str()), None);
+
+        setup_context(&user(), 0);
+        contract.transfer_license("new.near".to_string());
+        assert!(contract.is_licensed("new.near".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "License is under a lien")]
+    fn test_transfer_under_lien() {
+        let mut contract = setup_contract();
+
+        setup_context(&user(), 0);
+        contract.transfer_license("new.near".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Lien was not authorized by the license holder")]
+    fn test_place_lien_without_authorization() {
+        let mut contract = setup_contract();
+        contract.release_lien(user_str());
+
+        // The authorization was used up by the first lien
+        contract.place_lien(user_str());
+    }
+
+    #[test]
+    #[should_panic(expected = "No lien held by caller on this license")]
+    fn test_release_lien_by_other_account() {
+        let mut contract = setup_contract();
+
+        setup_context(&user(), 0);
+        contract.release_lien(user_str());
+    }
+}
@@ -0,0 +1,280 @@
+use near_sdk::{near, AccountId, env, require, NearToken, Promise};
+
+use crate::events::LicenseEvent;
+use crate::{AdminAction, GasClass, LicenseContract, LicenseContractExt, ReceiptKind, TransferKind};
+
+/// Basis points in 100%
+pub(crate) const MAX_BPS: u16 = 10_000;
+
+/// A license offered for resale by its holder.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Listing {
+    /// Account the sale proceeds are paid to
+    pub seller: AccountId,
+    pub price: NearToken,
+    /// Timestamp of the listing in nanoseconds
+    pub listed_at: u64,
+}
+
+#[near]
+impl LicenseContract {
+    /// List the caller's license for sale. Relisting replaces the price.
+    /// The listing is dropped when the license changes hands by any means.
+    ///
+    /// # Arguments
+    /// * `price` - Price in yoctoNEAR the buyer pays, royalty included
+    ///
+    /// # Panics
+    /// Panics if the caller has no active license or the price is zero
+    pub fn list_license(&mut self, price: NearToken) {
+        self.assert_not_shut_down();
+        let seller = env::predecessor_account_id();
+        let key = self.wallet_key(seller.as_str());
+        require!(self.holds_license(&key), "No active license to list");
+        require!(!price.is_zero(), "Price must be greater than zero");
+
+        self.listings.insert(
+            key.clone(),
+            Listing {
+                seller,
+                price,
+                listed_at: env::block_timestamp(),
+            },
+        );
+
+        LicenseEvent::LicenseListed { wallet_address: key, price }.emit();
+    }
+
+    /// Withdraw the caller's license from sale.
+    ///
+    /// # Panics
+    /// Panics if the caller's license isn't listed
+    pub fn delist_license(&mut self) {
+        self.assert_not_shut_down();
+        let key = self.wallet_key(env::predecessor_account_id().as_str());
+        require!(self.listings.remove(&key).is_some(), "License is not listed");
+
+        LicenseEvent::LicenseDelisted { wallet_address: key }.emit();
+    }
+
+    /// Buy a listed license. The license moves to the caller like a transfer and the
+    /// caller pays the transfer fee on top of the price; the seller is paid the price
+    /// minus the resale royalty, and the royalty and fee are added to the collected
+    /// fees. Any deposit above the price and fee is refunded.
+    ///
+    /// # Arguments
+    /// * `seller_wallet` - The wallet address holding the listed license
+    ///
+    /// # Panics
+    /// Panics if the license isn't listed, the deposit doesn't cover the price and
+    /// transfer fee, or the license can't be transferred to the caller
+    #[payable]
+    pub fn buy_license(&mut self, seller_wallet: String) {
+        self.assert_not_shut_down();
+        let buyer = env::predecessor_account_id();
+        let from_key = self.wallet_key(&seller_wallet);
+        let to_key = self.wallet_key(buyer.as_str());
+        let listing = self
+            .listings
+            .get(&from_key)
+            .cloned()
+            .unwrap_or_else(|| env::panic_str("License is not listed"));
+
+        let fee = self.transfer_fee(&from_key);
+        let cost = listing.price.saturating_add(fee);
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= cost,
+            format!("Purchase requires a deposit of {} yoctoNEAR", cost.as_yoctonear())
+        );
+        self.assert_transferable(&from_key, &to_key);
+
+        self.hand_over_license(&from_key, &to_key, TransferKind::Sale);
+        let royalty = listing
+            .price
+            .saturating_mul(self.resale_royalty_bps as u128)
+            .saturating_div(MAX_BPS as u128);
+        let proceeds = listing.price.saturating_sub(royalty);
+        self.collected_fees = self.collected_fees.saturating_add(royalty).saturating_add(fee);
+
+        LicenseEvent::LicenseSold {
+            from: from_key,
+            to: to_key,
+            price: listing.price,
+            royalty,
+        }
+        .emit();
+
+        self.issue_receipt(ReceiptKind::Sale, buyer.clone(), listing.price);
+        if !fee.is_zero() {
+            self.issue_receipt(ReceiptKind::TransferFee, buyer.clone(), fee);
+        }
+        if !proceeds.is_zero() {
+            self.issue_receipt(ReceiptKind::SaleProceeds, listing.seller.clone(), proceeds);
+            Promise::new(listing.seller).transfer(proceeds).detach();
+        }
+        let refund = deposit.saturating_sub(cost);
+        if !refund.is_zero() {
+            self.issue_receipt(ReceiptKind::Refund, buyer.clone(), refund);
+            Promise::new(buyer).transfer(refund).detach();
+        }
+        self.metrics.transfers += 1;
+        self.record_gas(GasClass::Transfer);
+    }
+
+    /// Set the share of every resale price kept as royalty.
+    ///
+    /// # Arguments
+    /// * `royalty_bps` - Royalty in basis points (1/100 of a percent)
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, or the royalty exceeds 100%
+    pub fn set_resale_royalty(&mut self, royalty_bps: u16) {
+        self.submit_action(AdminAction::SetResaleRoyalty { royalty_bps });
+    }
+
+    /// Get the listing of a wallet's license, if it is for sale.
+    pub fn get_listing(&self, wallet_address: String) -> Option<Listing> {
+        self.listings.get(&self.wallet_key(&wallet_address)).cloned()
+    }
+
+    /// Get the resale royalty in basis points.
+    pub fn get_resale_royalty(&self) -> u16 {
+        self.resale_royalty_bps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::{GrantSource, Network, ONE_DAY_NS};
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    fn buyer() -> AccountId {
+        "buyer.near".parse().unwrap()
+    }
+
+    fn setup_payment_context(predecessor: &AccountId, deposit: NearToken) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(predecessor.clone())
+            .block_timestamp(ONE_DAY_NS)
+            .attached_deposit(deposit)
+            .build());
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+        contract.set_resale_royalty(500);
+
+        setup_context(&user(), 0);
+        contract.list_license(NearToken::from_near(2));
+        contract
+    }
+
+    #[test]
+    fn test_buy_listed_license() {
+        let mut contract = setup_contract();
+        let expiry = contract.get_expiry(user_str());
+
+        setup_payment_context(&buyer(), NearToken::from_near(3));
+        contract.buy_license(user_str());
+
+        assert!(!contract.is_licensed(user_str()));
+        assert_eq!(contract.get_expiry(buyer().to_string()), expiry);
+        assert_eq!(contract.get_listing(user_str()), None);
+        assert_eq!(contract.get_collected_fees(), NearToken::from_millinear(100));
+        assert_eq!(
+            contract.get_license_provenance(buyer().to_string()).unwrap().source,
+            GrantSource::Purchase
+        );
+
+        let receipts: Vec<_> = (0..3).map(|id| contract.get_receipt(id).unwrap()).collect();
+        assert_eq!(
+            receipts.iter().map(|r| (r.kind, r.account.clone(), r.amount)).collect::<Vec<_>>(),
+            vec![
+                (ReceiptKind::Sale, buyer(), NearToken::from_near(2)),
+                (ReceiptKind::SaleProceeds, user(), NearToken::from_millinear(1900)),
+                (ReceiptKind::Refund, buyer(), NearToken::from_near(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_buy_charges_transfer_fee() {
+        let mut contract = setup_contract();
+        setup_context(&admin(), 0);
+        contract.set_transfer_config(0, NearToken::from_millinear(100));
+        contract.set_transfer_fee_rate(1_000, NearToken::from_millinear(10));
+
+        // 29 remaining days at 0.01 NEAR a day, 10% of it on top of the flat fee
+        let fee = NearToken::from_millinear(129);
+        setup_payment_context(&buyer(), NearToken::from_near(3));
+        contract.buy_license(user_str());
+
+        assert_eq!(contract.get_collected_fees(), NearToken::from_millinear(100).saturating_add(fee));
+        let receipts: Vec<_> = (0..4).map(|id| contract.get_receipt(id).unwrap()).collect();
+        assert_eq!(
+            receipts.iter().map(|r| (r.kind, r.account.clone(), r.amount)).collect::<Vec<_>>(),
+            vec![
+                (ReceiptKind::Sale, buyer(), NearToken::from_near(2)),
+                (ReceiptKind::TransferFee, buyer(), fee),
+                (ReceiptKind::SaleProceeds, user(), NearToken::from_millinear(1900)),
+                (ReceiptKind::Refund, buyer(), NearToken::from_millinear(871)),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Purchase requires a deposit of 2100000000000000000000000 yoctoNEAR")]
+    fn test_buy_without_transfer_fee() {
+        let mut contract = setup_contract();
+        setup_context(&admin(), 0);
+        contract.set_transfer_config(0, NearToken::from_millinear(100));
+
+        setup_payment_context(&buyer(), NearToken::from_near(2));
+        contract.buy_license(user_str());
+    }
+
+    #[test]
+    fn test_transfer_drops_listing() {
+        let mut contract = setup_contract();
+
+        setup_payment_context(&user(), NearToken::from_yoctonear(0));
+        contract.transfer_license(buyer().to_string());
+
+        assert_eq!(contract.get_listing(user_str()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Purchase requires a deposit of 2000000000000000000000000 yoctoNEAR")]
+    fn test_buy_with_insufficient_deposit() {
+        let mut contract = setup_contract();
+
+        setup_payment_context(&buyer(), NearToken::from_near(1));
+        contract.buy_license(user_str());
+    }
+
+    #[test]
+    #[should_panic(expected = "License is not listed")]
+    fn test_buy_delisted_license() {
+        let mut contract = setup_contract();
+        contract.delist_license();
+
+        setup_payment_context(&buyer(), NearToken::from_near(2));
+        contract.buy_license(user_str());
+    }
+
+    #[test]
+    #[should_panic(expected = "No active license to list")]
+    fn test_list_without_license() {
+        let mut contract = setup_contract();
+
+        setup_context(&buyer(), 0);
+        contract.list_license(NearToken::from_near(1));
+    }
+}
@@ -0,0 +1,155 @@
+use near_sdk::borsh::BorshDeserialize;
+use near_sdk::store::LookupMap;
+use near_sdk::{near, AccountId, env};
+
+use crate::{LicenseContract, LicenseContractExt, OldLicenseContract};
+
+/// Maximum number of sample wallets checked by `validate_migration`
+const MAX_MIGRATION_SAMPLES: usize = 100;
+
+/// Storage key of the contract state
+const STATE_KEY: &[u8] = b"STATE";
+
+/// A sampled license entry read under both the old and the new state layout.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationSample {
+    pub wallet_address: String,
+    /// Expiry read through the old `AccountId`-keyed map
+    pub old_expiry: Option<u64>,
+    /// Expiry read through the new `String`-keyed map
+    pub new_expiry: Option<u64>,
+}
+
+/// Result of a dry-run migration check.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationReport {
+    /// Admin that will be carried over, or the current admin if already migrated
+    pub admin: AccountId,
+    /// Whether the state is already in the new layout, in which case nothing is sampled
+    pub already_migrated: bool,
+    /// Number of sample wallets checked
+    pub sampled: u32,
+    /// Number of sample wallets holding a license entry under the old layout
+    pub found: u32,
+    /// Sample wallets whose old and new reads disagree
+    pub mismatched: Vec<String>,
+    pub samples: Vec<MigrationSample>,
+}
+
+#[near]
+impl LicenseContract {
+    /// Check that the current (pre-migration) state can be read under the new layout,
+    /// without writing anything. Run against a forked or sandbox state before calling `migrate`.
+    /// Lookup maps can't be enumerated, so entries are checked for the given sample wallets.
+    ///
+    /// # Arguments
+    /// * `sample_wallets` - Wallet addresses to read under both layouts (at most 100)
+    ///
+    /// # Panics
+    /// Panics if there is no state, or it is in neither the pre-migration nor the new layout
+    pub fn validate_migration(sample_wallets: Vec<String>) -> MigrationReport {
+        let state = env::storage_read(STATE_KEY)
+            .unwrap_or_else(|| env::panic_str("State is not in the pre-migration layout"));
+        if let Ok(current) = LicenseContract::try_from_slice(&state) {
+            return MigrationReport {
+                admin: current.admin,
+                already_migrated: true,
+                sampled: 0,
+                found: 0,
+                mismatched: Vec::new(),
+                samples: Vec::new(),
+            };
+        }
+        let old_state = OldLicenseContract::try_from_slice(&state)
+            .unwrap_or_else(|_| env::panic_str("State is not in the pre-migration layout"));
+        // Same prefix as `init_state`, which is what `migrate` relies on
+        let new_licenses: LookupMap<String, u64> = LookupMap::new(b"l");
+
+        let samples: Vec<MigrationSample> = sample_wallets
+            .into_iter()
+            .take(MAX_MIGRATION_SAMPLES)
+            .map(|wallet_address| {
+                let old_expiry = wallet_address
+                    .parse::<AccountId>()
+                    .ok()
+                    .and_then(|account_id| old_state.licenses.get(&account_id).copied());
+                let new_expiry = new_licenses.get(&wallet_address).copied();
+                MigrationSample { wallet_address, old_expiry, new_expiry }
+            })
+            .collect();
+
+        MigrationReport {
+            admin: old_state.admin,
+            already_migrated: false,
+            sampled: samples.len() as u32,
+            found: samples.iter().filter(|sample| sample.old_expiry.is_some()).count() as u32,
+            mismatched: samples
+                .iter()
+                .filter(|sample| sample.old_expiry != sample.new_expiry)
+                .map(|sample| sample.wallet_address.clone())
+                .collect(),
+            samples,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+
+    /// Write state in the pre-migration layout with a license for `user()`
+    fn write_old_state() {
+        let mut licenses = LookupMap::new(b"l");
+        licenses.insert(user(), 1_000);
+        licenses.flush();
+        env::state_write(&OldLicenseContract { licenses, admin: admin() });
+    }
+
+    #[test]
+    fn test_validate_migration() {
+        setup_context(&admin(), 0);
+        write_old_state();
+
+        let report = LicenseContract::validate_migration(vec![user_str(), "nobody.near".to_string()]);
+        assert_eq!(report.admin, admin());
+        assert!(!report.already_migrated);
+        assert_eq!(report.sampled, 2);
+        assert_eq!(report.found, 1);
+        assert!(report.mismatched.is_empty());
+        assert_eq!(report.samples[0].new_expiry, Some(1_000));
+    }
+
+    #[test]
+    fn test_validate_migration_on_migrated_state() {
+        setup_context(&admin(), 0);
+        write_old_state();
+        env::state_write(&LicenseContract::migrate(Network::Mainnet));
+
+        let report = LicenseContract::validate_migration(vec![user_str()]);
+        assert!(report.already_migrated);
+        assert_eq!(report.admin, admin());
+        assert_eq!(report.sampled, 0);
+        assert!(report.samples.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_keeps_licenses() {
+        setup_context(&admin(), 0);
+        write_old_state();
+
+        let contract = LicenseContract::migrate(Network::Mainnet);
+        assert_eq!(contract.get_expiry(user_str()), Some(1_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "State is not in the pre-migration layout")]
+    fn test_validate_migration_without_state() {
+        setup_context(&admin(), 0);
+
+        LicenseContract::validate_migration(vec![user_str()]);
+    }
+}
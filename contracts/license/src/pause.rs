@@ -0,0 +1,206 @@
+use near_sdk::{near, AccountId, env, require};
+
+use crate::events::LicenseEvent;
+use crate::{AdminAction, LicenseContract, LicenseContractExt, Permission};
+
+#[near]
+impl LicenseContract {
+    /// Set (or clear) the pauser account, e.g. the product registry that
+    /// broadcasts global pauses.
+    ///
+    /// # Arguments
+    /// * `account_id` - The account allowed to pause and unpause, or `None` to remove the role
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or an action delay is set
+    pub fn set_pauser(&mut self, account_id: Option<AccountId>) {
+        self.submit_action(AdminAction::SetPauser { account_id });
+    }
+
+    /// Pause the contract. While paused, no license can be granted, transferred or recovered;
+    /// license checks and views keep working.
+    ///
+    /// # Panics
+    /// Panics if caller may not pause the contract
+    pub fn pause(&mut self) {
+        self.set_paused(true);
+    }
+
+    /// Unpause the contract.
+    ///
+    /// # Panics
+    /// Panics if caller may not pause the contract
+    pub fn unpause(&mut self) {
+        self.set_paused(false);
+    }
+
+    /// Freeze license transfers, e.g. during a phishing wave. Unlike a pause,
+    /// grants, rentals, recoveries and license checks keep working; only holder
+    /// transfers and resales are rejected.
+    ///
+    /// # Panics
+    /// Panics if caller may not freeze transfers
+    pub fn freeze_transfers(&mut self) {
+        self.set_transfers_frozen(true);
+    }
+
+    /// Unfreeze license transfers.
+    ///
+    /// # Panics
+    /// Panics if caller may not freeze transfers
+    pub fn unfreeze_transfers(&mut self) {
+        self.set_transfers_frozen(false);
+    }
+
+    /// Check whether the contract is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Check whether license transfers are frozen.
+    pub fn is_transfers_frozen(&self) -> bool {
+        self.transfers_frozen
+    }
+
+    /// Get the current pauser account, if any.
+    pub fn get_pauser(&self) -> Option<AccountId> {
+        self.pauser.clone()
+    }
+
+    /// Panics if the contract is paused or shut down
+    pub(crate) fn assert_not_paused(&self) {
+        self.assert_not_shut_down();
+        require!(!self.paused, "Contract is paused");
+    }
+
+    /// Panics if license transfers are frozen
+    pub(crate) fn assert_transfers_not_frozen(&self) {
+        require!(!self.transfers_frozen, "License transfers are frozen");
+    }
+
+    /// Record a pause state change made by the admin or pauser
+    fn set_paused(&mut self, paused: bool) {
+        self.assert_permitted(Permission::Pause);
+        let caller = env::predecessor_account_id();
+
+        if self.paused != paused {
+            self.paused = paused;
+            LicenseEvent::PauseChanged { paused, changed_by: caller }.emit();
+        }
+    }
+
+    /// Record a transfer freeze change made by the admin or pauser
+    fn set_transfers_frozen(&mut self, frozen: bool) {
+        self.assert_permitted(Permission::FreezeTransfers);
+        let caller = env::predecessor_account_id();
+
+        if self.transfers_frozen != frozen {
+            self.transfers_frozen = frozen;
+            LicenseEvent::TransferFreezeChanged { frozen, changed_by: caller }.emit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+
+    fn registry() -> AccountId {
+        "registry.near".parse().unwrap()
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_pauser(Some(registry()));
+        contract
+    }
+
+    #[test]
+    fn test_pauser_can_pause_and_unpause() {
+        let mut contract = setup_contract();
+        contract.grant_license(user_str(), 30, None, None);
+
+        setup_context(&registry(), 0);
+        contract.pause();
+        assert!(contract.is_paused());
+        // License checks keep working while paused
+        assert!(contract.is_licensed(user_str()));
+
+        contract.unpause();
+        assert!(!contract.is_paused());
+
+        setup_context(&admin(), 0);
+        contract.grant_license(user_str(), 30, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn test_grant_rejected_while_paused() {
+        let mut contract = setup_contract();
+        contract.pause();
+
+        contract.grant_license(user_str(), 30, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn test_transfer_rejected_while_paused() {
+        let mut contract = setup_contract();
+        contract.grant_license(user_str(), 30, None, None);
+        contract.pause();
+
+        setup_context(&user(), 0);
+        contract.transfer_license("other.near".to_string());
+    }
+
+    #[test]
+    fn test_freeze_blocks_transfers_only() {
+        let mut contract = setup_contract();
+        contract.grant_license(user_str(), 30, None, None);
+
+        setup_context(&registry(), 0);
+        contract.freeze_transfers();
+        assert!(contract.is_transfers_frozen());
+
+        // Grants keep working while transfers are frozen
+        setup_context(&admin(), 0);
+        contract.grant_license(user_str(), 30, None, None);
+
+        contract.unfreeze_transfers();
+        setup_context(&user(), 0);
+        contract.transfer_license("other.near".to_string());
+        assert!(contract.is_licensed("other.near".to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "License transfers are frozen")]
+    fn test_transfer_rejected_while_frozen() {
+        let mut contract = setup_contract();
+        contract.grant_license(user_str(), 30, None, None);
+        contract.freeze_transfers();
+
+        setup_context(&user(), 0);
+        contract.transfer_license("other.near".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only admin or pauser can freeze transfers")]
+    fn test_freeze_unauthorized() {
+        let mut contract = setup_contract();
+
+        setup_context(&user(), 0);
+        contract.freeze_transfers();
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only admin or pauser can pause the contract")]
+    fn test_pause_unauthorized() {
+        let mut contract = setup_contract();
+
+        setup_context(&user(), 0);
+        contract.pause();
+    }
+}
@@ -0,0 +1,418 @@
+use near_sdk::{near, AccountId, env, require, NearToken, Promise};
+
+use crate::market::MAX_BPS;
+use crate::{AdminAction, LicenseContract, LicenseContractExt, Permission, ReceiptKind, ONE_DAY_NS};
+
+/// Maximum number of payout schedules
+pub(crate) const MAX_PAYOUT_SCHEDULES: usize = 10;
+
+/// Maximum number of beneficiaries in the revenue split
+const MAX_REVENUE_BENEFICIARIES: usize = 10;
+
+/// How much of the collected fees a scheduled payout sends.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PayoutAmount {
+    /// A fixed amount
+    Fixed(NearToken),
+    /// A share of the fees collected when the payout run starts, in basis points
+    ShareBps(u16),
+}
+
+/// A recurring payout of collected fees to a partner.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PayoutSchedule {
+    pub beneficiary: AccountId,
+    pub amount: PayoutAmount,
+    pub interval_days: u32,
+    /// Timestamp from which the next payout is due in nanoseconds
+    pub next_payout_at: u64,
+}
+
+/// A beneficiary's share of distributed revenue.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RevenueShare {
+    pub beneficiary: AccountId,
+    /// Share of every distribution in basis points
+    pub bps: u16,
+}
+
+/// Panics unless the shares are empty (no split), or name at most 10 distinct
+/// beneficiaries with non-zero shares summing to 10000 basis points
+pub(crate) fn assert_valid_revenue_split(shares: &[RevenueShare]) {
+    if shares.is_empty() {
+        return;
+    }
+    require!(shares.len() <= MAX_REVENUE_BENEFICIARIES, "Too many revenue beneficiaries");
+    require!(shares.iter().all(|share| share.bps > 0), "Revenue shares must be non-zero");
+    require!(
+        shares.iter().map(|share| share.bps as u32).sum::<u32>() == MAX_BPS as u32,
+        "Revenue shares must sum to 10000 basis points"
+    );
+    for (i, share) in shares.iter().enumerate() {
+        require!(
+            shares[..i].iter().all(|other| other.beneficiary != share.beneficiary),
+            "Duplicate revenue beneficiary"
+        );
+    }
+}
+
+impl PayoutAmount {
+    /// Panics if a share exceeds 100%
+    pub(crate) fn validate(&self) {
+        if let PayoutAmount::ShareBps(bps) = self {
+            require!(*bps <= MAX_BPS, "Payout share cannot exceed 10000 basis points");
+        }
+    }
+}
+
+#[near]
+impl LicenseContract {
+    /// Pay a beneficiary out of the collected fees every `interval_days`, starting
+    /// one interval from now. Replaces any existing schedule for the beneficiary.
+    ///
+    /// # Arguments
+    /// * `beneficiary` - The account receiving the payouts
+    /// * `amount` - A fixed amount or a share of the fees collected at payout time
+    /// * `interval_days` - Days between payouts
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, the interval is zero,
+    /// the share exceeds 100%, or 10 beneficiaries already have schedules
+    pub fn set_payout_schedule(&mut self, beneficiary: AccountId, amount: PayoutAmount, interval_days: u32) {
+        self.submit_action(AdminAction::SetPayoutSchedule {
+            beneficiary,
+            amount,
+            interval_days,
+        });
+    }
+
+    /// Stop the payouts to a beneficiary.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, or the beneficiary has no schedule
+    pub fn remove_payout_schedule(&mut self, beneficiary: AccountId) {
+        self.submit_action(AdminAction::RemovePayoutSchedule { beneficiary });
+    }
+
+    /// Make every payout that is due. By default the admin and the payout keeper may
+    /// run payouts, see `set_payout_keeper`. Shares are taken of the fees collected
+    /// when the run starts, so schedules earlier in the run don't shrink later ones.
+    /// A payout that the collected fees or the daily withdrawal limit can't cover
+    /// stays due until they can. Each payout is recorded as a receipt.
+    ///
+    /// # Returns
+    /// The number of payouts made
+    ///
+    /// # Panics
+    /// Panics if caller may not run payouts
+    pub fn run_payouts(&mut self) -> u32 {
+        self.assert_permitted(Permission::RunPayouts);
+        let now = env::block_timestamp();
+        let fees_at_start = self.collected_fees;
+        let mut paid = 0;
+
+        for index in 0..self.payout_schedules.len() {
+            let schedule = self.payout_schedules[index].clone();
+            if now < schedule.next_payout_at {
+                continue;
+            }
+
+            let amount = match schedule.amount {
+                PayoutAmount::Fixed(amount) => amount,
+                PayoutAmount::ShareBps(bps) => NearToken::from_yoctonear(
+                    fees_at_start.as_yoctonear() * bps as u128 / MAX_BPS as u128,
+                ),
+            };
+            let available = self
+                .get_withdrawal_allowance()
+                .map_or(self.collected_fees, |allowance| allowance.min(self.collected_fees));
+            if amount.is_zero() || amount > available {
+                continue;
+            }
+
+            self.record_withdrawal(amount);
+            self.collected_fees = self.collected_fees.saturating_sub(amount);
+            // Skip missed intervals rather than paying them all at once
+            let interval_ns = schedule.interval_days as u64 * ONE_DAY_NS;
+            let missed = (now - schedule.next_payout_at) / interval_ns;
+            self.payout_schedules[index].next_payout_at = schedule.next_payout_at + (missed + 1) * interval_ns;
+            self.issue_receipt(ReceiptKind::Payout, schedule.beneficiary.clone(), amount);
+            Promise::new(schedule.beneficiary).transfer(amount).detach();
+            paid += 1;
+        }
+        paid
+    }
+
+    /// Replace the revenue split that `distribute_revenue` accrues collected fees by.
+    /// Amounts already accrued stay claimable.
+    ///
+    /// # Arguments
+    /// * `shares` - Up to 10 beneficiaries whose shares sum to 10000 basis points,
+    ///   or none to stop distributing
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, or the shares are invalid
+    pub fn set_revenue_split(&mut self, shares: Vec<RevenueShare>) {
+        self.submit_action(AdminAction::SetRevenueSplit { shares });
+    }
+
+    /// Accrue the collected fees to the revenue split beneficiaries, who then claim
+    /// them with `claim_payout`. By default the admin and the payout keeper may
+    /// distribute revenue. At most the daily withdrawal limit is distributed, and
+    /// rounding dust stays collected.
+    ///
+    /// # Returns
+    /// The amount accrued to beneficiaries
+    ///
+    /// # Panics
+    /// Panics if caller may not run payouts or no revenue split is set
+    pub fn distribute_revenue(&mut self) -> NearToken {
+        self.assert_permitted(Permission::RunPayouts);
+        require!(!self.revenue_split.is_empty(), "No revenue split set");
+
+        let pool = self
+            .get_withdrawal_allowance()
+            .map_or(self.collected_fees, |allowance| allowance.min(self.collected_fees));
+        let mut distributed = NearToken::from_yoctonear(0);
+        for share in self.revenue_split.clone() {
+            let amount = NearToken::from_yoctonear(pool.as_yoctonear() * share.bps as u128 / MAX_BPS as u128);
+            let accrued = self.accrued_payouts.get(&share.beneficiary).copied().unwrap_or_default();
+            self.accrued_payouts.insert(share.beneficiary, accrued.saturating_add(amount));
+            distributed = distributed.saturating_add(amount);
+        }
+
+        if !distributed.is_zero() {
+            self.record_withdrawal(distributed);
+            self.collected_fees = self.collected_fees.saturating_sub(distributed);
+        }
+        distributed
+    }
+
+    /// Claim the caller's accrued revenue. Keeps working after a shutdown.
+    ///
+    /// # Panics
+    /// Panics if the caller has nothing accrued
+    pub fn claim_payout(&mut self) -> Promise {
+        let caller = env::predecessor_account_id();
+        let amount = self
+            .accrued_payouts
+            .remove(&caller)
+            .filter(|amount| !amount.is_zero())
+            .unwrap_or_else(|| env::panic_str("No payout to claim"));
+
+        self.issue_receipt(ReceiptKind::Payout, caller.clone(), amount);
+        Promise::new(caller).transfer(amount)
+    }
+
+    /// Get the revenue accrued to an account and not yet claimed.
+    pub fn get_accrued_payout(&self, account_id: AccountId) -> NearToken {
+        self.accrued_payouts.get(&account_id).copied().unwrap_or_default()
+    }
+
+    /// Get the revenue split, empty if none is set.
+    pub fn get_revenue_split(&self) -> Vec<RevenueShare> {
+        self.revenue_split.clone()
+    }
+
+    /// Set (or clear) the keeper account allowed to run payouts and distribute
+    /// revenue besides the admin.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or an action delay is set
+    pub fn set_payout_keeper(&mut self, account_id: Option<AccountId>) {
+        self.submit_action(AdminAction::SetPayoutKeeper { account_id });
+    }
+
+    /// Get the current payout keeper account, if any.
+    pub fn get_payout_keeper(&self) -> Option<AccountId> {
+        self.payout_keeper.clone()
+    }
+
+    /// Get every payout schedule.
+    pub fn get_payout_schedules(&self) -> Vec<PayoutSchedule> {
+        self.payout_schedules.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    fn partner() -> AccountId {
+        "partner.near".parse().unwrap()
+    }
+
+    /// Collect a 1 NEAR transfer fee, scheduling weekly payouts of `amount` if given
+    fn setup_contract(amount: Option<PayoutAmount>) -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+        contract.set_transfer_config(0, NearToken::from_near(1));
+        if let Some(amount) = amount {
+            contract.set_payout_schedule(partner(), amount, 7);
+        }
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(user())
+            .attached_deposit(NearToken::from_near(1))
+            .build();
+        testing_env!(context);
+        contract.transfer_license("other.near".to_string());
+        contract
+    }
+
+    #[test]
+    fn test_share_payout_runs_each_interval() {
+        let mut contract = setup_contract(Some(PayoutAmount::ShareBps(2_500)));
+
+        setup_context(&admin(), 6 * ONE_DAY_NS);
+        assert_eq!(contract.run_payouts(), 0);
+
+        setup_context(&admin(), 7 * ONE_DAY_NS);
+        assert_eq!(contract.run_payouts(), 1);
+        assert_eq!(contract.get_collected_fees(), NearToken::from_millinear(750));
+        assert_eq!(contract.run_payouts(), 0);
+
+        // Missed intervals are skipped, not paid at once
+        setup_context(&admin(), 30 * ONE_DAY_NS);
+        assert_eq!(contract.run_payouts(), 1);
+        assert_eq!(contract.get_payout_schedules()[0].next_payout_at, 35 * ONE_DAY_NS);
+
+        let receipt = contract.get_receipt(contract.get_receipt_count() - 1).unwrap();
+        assert_eq!(receipt.kind, ReceiptKind::Payout);
+        assert_eq!(receipt.account, partner());
+    }
+
+    #[test]
+    fn test_fixed_payout_waits_for_funds() {
+        let mut contract = setup_contract(Some(PayoutAmount::Fixed(NearToken::from_near(2))));
+
+        setup_context(&admin(), 7 * ONE_DAY_NS);
+        assert_eq!(contract.run_payouts(), 0);
+        assert_eq!(contract.get_collected_fees(), NearToken::from_near(1));
+        assert_eq!(contract.get_payout_schedules()[0].next_payout_at, 7 * ONE_DAY_NS);
+    }
+
+    #[test]
+    fn test_shares_taken_of_fees_at_run_start() {
+        let mut contract = setup_contract(Some(PayoutAmount::ShareBps(5_000)));
+        let studio: AccountId = "studio.near".parse().unwrap();
+        setup_context(&admin(), 0);
+        contract.set_payout_schedule(studio.clone(), PayoutAmount::ShareBps(5_000), 7);
+
+        setup_context(&admin(), 7 * ONE_DAY_NS);
+        assert_eq!(contract.run_payouts(), 2);
+        assert_eq!(contract.get_collected_fees(), NearToken::from_yoctonear(0));
+        let receipt = contract.get_receipt(contract.get_receipt_count() - 1).unwrap();
+        assert_eq!((receipt.account, receipt.amount), (studio, NearToken::from_millinear(500)));
+    }
+
+    #[test]
+    fn test_payout_keeper_runs_payouts() {
+        let mut contract = setup_contract(Some(PayoutAmount::ShareBps(2_500)));
+        let keeper: AccountId = "keeper.near".parse().unwrap();
+        setup_context(&admin(), 0);
+        contract.set_payout_keeper(Some(keeper.clone()));
+        assert_eq!(contract.get_payout_keeper(), Some(keeper.clone()));
+
+        setup_context(&keeper, 7 * ONE_DAY_NS);
+        assert_eq!(contract.run_payouts(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only admin or payout keeper can run payouts")]
+    fn test_run_payouts_unauthorized() {
+        let mut contract = setup_contract(Some(PayoutAmount::ShareBps(2_500)));
+
+        setup_context(&user(), 7 * ONE_DAY_NS);
+        contract.run_payouts();
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only admin or payout keeper can run payouts")]
+    fn test_distribute_revenue_unauthorized() {
+        let mut contract = setup_contract(None);
+        setup_context(&admin(), 0);
+        contract.set_revenue_split(vec![share("partner.near", 10_000)]);
+
+        setup_context(&user(), 0);
+        contract.distribute_revenue();
+    }
+
+    #[test]
+    fn test_remove_payout_schedule() {
+        let mut contract = setup_contract(Some(PayoutAmount::ShareBps(100)));
+
+        setup_context(&admin(), 0);
+        contract.remove_payout_schedule(partner());
+        assert!(contract.get_payout_schedules().is_empty());
+    }
+
+    fn share(beneficiary: &str, bps: u16) -> RevenueShare {
+        RevenueShare {
+            beneficiary: beneficiary.parse().unwrap(),
+            bps,
+        }
+    }
+
+    #[test]
+    fn test_revenue_split_accrues_and_claims() {
+        let mut contract = setup_contract(None);
+        setup_context(&admin(), 0);
+        contract.set_revenue_split(vec![share("partner.near", 7_000), share("studio.near", 3_000)]);
+
+        setup_context(&admin(), 0);
+        assert_eq!(contract.distribute_revenue(), NearToken::from_near(1));
+        assert_eq!(contract.get_accrued_payout(partner()), NearToken::from_millinear(700));
+        assert_eq!(contract.get_collected_fees(), NearToken::from_yoctonear(0));
+
+        setup_context(&partner(), 0);
+        let _ = contract.claim_payout();
+        assert_eq!(contract.get_accrued_payout(partner()), NearToken::from_yoctonear(0));
+        assert_eq!(
+            contract.get_accrued_payout("studio.near".parse().unwrap()),
+            NearToken::from_millinear(300)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Revenue shares must sum to 10000 basis points")]
+    fn test_revenue_split_must_sum_to_whole() {
+        let mut contract = setup_contract(None);
+
+        setup_context(&admin(), 0);
+        contract.set_revenue_split(vec![share("partner.near", 7_000), share("studio.near", 2_000)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Duplicate revenue beneficiary")]
+    fn test_revenue_split_rejects_duplicates() {
+        let mut contract = setup_contract(None);
+
+        setup_context(&admin(), 0);
+        contract.set_revenue_split(vec![share("partner.near", 5_000), share("partner.near", 5_000)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "No payout to claim")]
+    fn test_claim_without_accrual() {
+        let mut contract = setup_contract(None);
+
+        setup_context(&partner(), 0);
+        let _ = contract.claim_payout();
+    }
+
+    #[test]
+    #[should_panic(expected = "Payout share cannot exceed 10000 basis points")]
+    fn test_payout_share_validated() {
+        setup_contract(Some(PayoutAmount::ShareBps(10_001)));
+    }
+}
@@ -0,0 +1,438 @@
+use near_sdk::{near, AccountId, env, require};
+
+use crate::events::LicenseEvent;
+use crate::privacy::to_hex;
+use crate::{AdminAction, LicenseContract, LicenseContractExt, Role};
+
+/// Operation whose required roles are configurable through `set_permission`.
+/// Admin settings stay with the admin and its timelock.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Permission {
+    /// Any license grant, and proposing long grants
+    GrantLicenses,
+    /// `revoke_credential`
+    RevokeCredentials,
+    /// `pause` and `unpause`
+    Pause,
+    /// `freeze_transfers` and `unfreeze_transfers`
+    FreezeTransfers,
+    /// `emit_expiry_reminders`
+    EmitReminders,
+    /// `set_compliance_status`
+    SetComplianceStatus,
+    /// `set_wallet_jurisdiction`
+    AttestJurisdictions,
+    /// `redeem_receipt`
+    RedeemReceipts,
+    /// `run_payouts` and `distribute_revenue`
+    RunPayouts,
+}
+
+impl Permission {
+    const ALL: [Permission; 9] = [
+        Permission::GrantLicenses,
+        Permission::RevokeCredentials,
+        Permission::Pause,
+        Permission::FreezeTransfers,
+        Permission::EmitReminders,
+        Permission::SetComplianceStatus,
+        Permission::AttestJurisdictions,
+        Permission::RedeemReceipts,
+        Permission::RunPayouts,
+    ];
+
+    /// Roles allowed to perform the operation until the admin changes them
+    fn default_roles(self) -> Vec<Role> {
+        match self {
+            Permission::GrantLicenses | Permission::RevokeCredentials => vec![Role::Admin],
+            Permission::RedeemReceipts => vec![Role::Admin, Role::ReceiptRedeemer],
+            Permission::RunPayouts => vec![Role::Admin, Role::PayoutKeeper],
+            Permission::Pause | Permission::FreezeTransfers => vec![Role::Admin, Role::Pauser],
+            Permission::EmitReminders => vec![Role::Admin, Role::ReminderKeeper],
+            Permission::SetComplianceStatus | Permission::AttestJurisdictions => vec![Role::ComplianceOfficer],
+        }
+    }
+
+    /// The operation, as used in authorization errors
+    fn description(self) -> &'static str {
+        match self {
+            Permission::GrantLicenses => "grant licenses",
+            Permission::RevokeCredentials => "revoke credentials",
+            Permission::Pause => "pause the contract",
+            Permission::FreezeTransfers => "freeze transfers",
+            Permission::EmitReminders => "emit reminders",
+            Permission::SetComplianceStatus => "set compliance status",
+            Permission::AttestJurisdictions => "attest jurisdictions",
+            Permission::RedeemReceipts => "redeem receipts",
+            Permission::RunPayouts => "run payouts",
+        }
+    }
+}
+
+/// Roles allowed to perform an operation, as returned by `get_permissions`.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PermissionRule {
+    pub permission: Permission,
+    pub roles: Vec<Role>,
+    /// Number of distinct role holders, including the caller, that must approve each call
+    pub threshold: u8,
+}
+
+/// Panics if `roles` is empty or names a role twice
+pub(crate) fn assert_valid_roles(roles: &[Role]) {
+    require!(!roles.is_empty(), "A permission needs at least one role");
+    require!(
+        roles.iter().enumerate().all(|(i, role)| !roles[..i].contains(role)),
+        "Duplicate role"
+    );
+}
+
+/// Panics if `call_hash` isn't a lowercase hex-encoded sha256
+fn assert_valid_call_hash(call_hash: &str) {
+    require!(
+        call_hash.len() == 64 && call_hash.bytes().all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f')),
+        "Call hash must be a lowercase hex-encoded sha256"
+    );
+}
+
+#[near]
+impl LicenseContract {
+    /// Set the roles allowed to perform an operation, e.g. to let the pauser also
+    /// revoke credentials. Any holder of one of the roles may perform it, unless the
+    /// operation's approval threshold asks for several of them.
+    ///
+    /// # Arguments
+    /// * `permission` - The operation to configure
+    /// * `roles` - Roles allowed to perform it, at least one
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, or `roles` is empty,
+    /// contains duplicates or has fewer roles than the operation's approval threshold
+    pub fn set_permission(&mut self, permission: Permission, roles: Vec<Role>) {
+        self.submit_action(AdminAction::SetPermission { permission, roles });
+    }
+
+    /// Set the number of distinct role holders that must approve each call to an
+    /// operation (1 by default). With a threshold above 1, the other holders approve
+    /// the exact call with `approve_operation` before the last one makes it.
+    ///
+    /// # Arguments
+    /// * `permission` - The operation to configure
+    /// * `threshold` - Approvals needed per call, counting the caller's own
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, or `threshold` is
+    /// zero or exceeds the number of roles allowed to perform the operation
+    pub fn set_permission_threshold(&mut self, permission: Permission, threshold: u8) {
+        self.submit_action(AdminAction::SetPermissionThreshold { permission, threshold });
+    }
+
+    /// Approve one call to an operation that needs several approvals. The call is
+    /// identified by the sha256 of its JSON arguments, so the approval covers only
+    /// that exact call and is used up when it is made.
+    ///
+    /// # Arguments
+    /// * `permission` - The operation the call performs
+    /// * `call_hash` - Hex-encoded sha256 of the call's JSON arguments
+    ///
+    /// # Panics
+    /// Panics if the contract is shut down, the caller holds none of the roles allowed
+    /// to perform the operation, the call hash is malformed, or the caller already
+    /// approved the call
+    pub fn approve_operation(&mut self, permission: Permission, call_hash: String) {
+        self.assert_not_shut_down();
+        let caller = env::predecessor_account_id();
+        let roles = self.get_permission(permission);
+        if !self.holds_any_role(&roles, &caller) {
+            panic_unauthorized(permission, &roles);
+        }
+        assert_valid_call_hash(&call_hash);
+
+        let approvals = self.operation_approvals.entry((permission, call_hash.clone())).or_default();
+        require!(!approvals.contains(&caller), "Call already approved by this account");
+        approvals.push(caller.clone());
+
+        LicenseEvent::OperationApproved {
+            permission,
+            call_hash,
+            approved_by: caller,
+        }
+        .emit();
+    }
+
+    /// Get the roles allowed to perform an operation.
+    pub fn get_permission(&self, permission: Permission) -> Vec<Role> {
+        self.permissions
+            .get(&permission)
+            .cloned()
+            .unwrap_or_else(|| permission.default_roles())
+    }
+
+    /// Get the number of distinct role holders that must approve each call to an operation.
+    pub fn get_permission_threshold(&self, permission: Permission) -> u8 {
+        self.permission_thresholds.get(&permission).copied().unwrap_or(1)
+    }
+
+    /// Get the roles allowed to perform every configurable operation.
+    pub fn get_permissions(&self) -> Vec<PermissionRule> {
+        Permission::ALL
+            .into_iter()
+            .map(|permission| PermissionRule {
+                permission,
+                roles: self.get_permission(permission),
+                threshold: self.get_permission_threshold(permission),
+            })
+            .collect()
+    }
+
+    /// Get the accounts that approved a call to an operation that wasn't made yet.
+    pub fn get_operation_approvals(&self, permission: Permission, call_hash: String) -> Vec<AccountId> {
+        self.operation_approvals
+            .get(&(permission, call_hash))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Store the roles allowed to perform an operation and emit its event
+    pub(crate) fn apply_permission(&mut self, permission: Permission, roles: Vec<Role>) {
+        require!(
+            roles.len() >= self.get_permission_threshold(permission) as usize,
+            "Fewer roles than the operation's approval threshold"
+        );
+        if roles == permission.default_roles() {
+            self.permissions.remove(&permission);
+        } else {
+            self.permissions.insert(permission, roles.clone());
+        }
+        LicenseEvent::PermissionChanged { permission, roles }.emit();
+    }
+
+    /// Store the approval threshold of an operation and emit its event
+    pub(crate) fn apply_permission_threshold(&mut self, permission: Permission, threshold: u8) {
+        require!(
+            threshold as usize <= self.get_permission(permission).len(),
+            "Threshold exceeds the number of roles allowed to perform the operation"
+        );
+        if threshold == 1 {
+            self.permission_thresholds.remove(&permission);
+        } else {
+            self.permission_thresholds.insert(permission, threshold);
+        }
+        LicenseEvent::PermissionThresholdChanged { permission, threshold }.emit();
+    }
+
+    /// Panics unless the contract is running, the caller holds one of the roles allowed
+    /// to perform `permission`, and enough other holders approved the call. Their
+    /// approvals are used up
+    pub(crate) fn assert_permitted(&mut self, permission: Permission) {
+        self.assert_not_shut_down();
+        let caller = env::predecessor_account_id();
+        let roles = self.get_permission(permission);
+        if !self.holds_any_role(&roles, &caller) {
+            panic_unauthorized(permission, &roles);
+        }
+
+        let threshold = self.get_permission_threshold(permission) as usize;
+        if threshold > 1 {
+            let call_hash = to_hex(&env::sha256(env::input().unwrap_or_default()));
+            let approvals = self.operation_approvals.remove(&(permission, call_hash)).unwrap_or_default();
+            // Approvals only count while the approver still holds one of the roles
+            let approved = approvals
+                .iter()
+                .filter(|&approver| approver != &caller && self.holds_any_role(&roles, approver))
+                .count();
+            require!(
+                approved + 1 >= threshold,
+                format!(
+                    "Calls to {} need {} approvals, use approve_operation",
+                    permission.description(),
+                    threshold
+                )
+            );
+        }
+    }
+
+    /// Whether `account_id` holds one of `roles`
+    fn holds_any_role(&self, roles: &[Role], account_id: &AccountId) -> bool {
+        roles.iter().any(|&role| self.role_holder(role).as_ref() == Some(account_id))
+    }
+}
+
+/// Panic with the authorization error of `permission`
+fn panic_unauthorized(permission: Permission, roles: &[Role]) -> ! {
+    let holders: Vec<&str> = roles.iter().map(|role| role.description()).collect();
+    env::panic_str(&format!(
+        "Unauthorized: only {} can {}",
+        holders.join(" or "),
+        permission.description()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    fn pauser() -> AccountId {
+        "pauser.near".parse().unwrap()
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_pauser(Some(pauser()));
+        contract.grant_license(user_str(), 30, None, None);
+        contract
+    }
+
+    #[test]
+    fn test_default_permissions() {
+        let contract = setup_contract();
+
+        let permissions = contract.get_permissions();
+        assert_eq!(permissions.len(), Permission::ALL.len());
+        assert_eq!(
+            permissions[2],
+            PermissionRule {
+                permission: Permission::Pause,
+                roles: vec![Role::Admin, Role::Pauser],
+                threshold: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_permission_extended_to_another_role() {
+        let mut contract = setup_contract();
+        contract.set_permission(Permission::RevokeCredentials, vec![Role::Admin, Role::Pauser]);
+
+        setup_context(&pauser(), 0);
+        contract.revoke_credential(0, None);
+        assert!(contract.get_credential(0).unwrap().revoked);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only pauser can pause the contract")]
+    fn test_permission_restricted() {
+        let mut contract = setup_contract();
+        contract.set_permission(Permission::Pause, vec![Role::Pauser]);
+
+        contract.pause();
+    }
+
+    #[test]
+    fn test_resetting_to_defaults_clears_override() {
+        let mut contract = setup_contract();
+        contract.set_permission(Permission::Pause, vec![Role::Pauser]);
+        contract.set_permission(Permission::Pause, vec![Role::Admin, Role::Pauser]);
+
+        assert!(contract.permissions.get(&Permission::Pause).is_none());
+        contract.pause();
+        assert!(contract.is_paused());
+    }
+
+    #[test]
+    #[should_panic(expected = "A permission needs at least one role")]
+    fn test_permission_without_roles_rejected() {
+        let mut contract = setup_contract();
+
+        contract.set_permission(Permission::GrantLicenses, vec![]);
+    }
+
+    /// Set up a context calling as `predecessor` with the given JSON arguments
+    fn setup_call_context(predecessor: &AccountId, args: &str) {
+        let mut builder = VMContextBuilder::new();
+        builder.predecessor_account_id(predecessor.clone());
+        builder.context.input = args.as_bytes().to_vec().into();
+        testing_env!(builder.build());
+    }
+
+    fn call_hash(args: &str) -> String {
+        to_hex(&env::sha256(args.as_bytes()))
+    }
+
+    #[test]
+    fn test_threshold_requires_approval_of_exact_call() {
+        let mut contract = setup_contract();
+        contract.set_permission(Permission::RevokeCredentials, vec![Role::Admin, Role::Pauser]);
+        contract.set_permission_threshold(Permission::RevokeCredentials, 2);
+        assert_eq!(contract.get_permissions()[1].threshold, 2);
+
+        let args = r#"{"status_index":0,"memo":null}"#;
+        setup_context(&pauser(), 0);
+        contract.approve_operation(Permission::RevokeCredentials, call_hash(args));
+        assert_eq!(
+            contract.get_operation_approvals(Permission::RevokeCredentials, call_hash(args)),
+            vec![pauser()]
+        );
+
+        setup_call_context(&admin(), args);
+        contract.revoke_credential(0, None);
+        assert!(contract.get_credential(0).unwrap().revoked);
+        // The approval was used up
+        assert!(contract
+            .get_operation_approvals(Permission::RevokeCredentials, call_hash(args))
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Calls to revoke credentials need 2 approvals, use approve_operation")]
+    fn test_threshold_rejects_unapproved_call() {
+        let mut contract = setup_contract();
+        contract.set_permission(Permission::RevokeCredentials, vec![Role::Admin, Role::Pauser]);
+        contract.set_permission_threshold(Permission::RevokeCredentials, 2);
+
+        // The pauser approved a different call
+        setup_context(&pauser(), 0);
+        contract.approve_operation(Permission::RevokeCredentials, call_hash(r#"{"status_index":1}"#));
+
+        setup_call_context(&admin(), r#"{"status_index":0,"memo":null}"#);
+        contract.revoke_credential(0, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Calls to revoke credentials need 2 approvals, use approve_operation")]
+    fn test_caller_cannot_approve_own_call() {
+        let mut contract = setup_contract();
+        contract.set_permission(Permission::RevokeCredentials, vec![Role::Admin, Role::Pauser]);
+        contract.set_permission_threshold(Permission::RevokeCredentials, 2);
+
+        let args = r#"{"status_index":0,"memo":null}"#;
+        setup_context(&admin(), 0);
+        contract.approve_operation(Permission::RevokeCredentials, call_hash(args));
+        setup_call_context(&admin(), args);
+        contract.revoke_credential(0, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Threshold exceeds the number of roles allowed to perform the operation")]
+    fn test_threshold_above_role_count_rejected() {
+        let mut contract = setup_contract();
+
+        contract.set_permission_threshold(Permission::RevokeCredentials, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Fewer roles than the operation's approval threshold")]
+    fn test_roles_cannot_drop_below_threshold() {
+        let mut contract = setup_contract();
+        contract.set_permission_threshold(Permission::Pause, 2);
+
+        contract.set_permission(Permission::Pause, vec![Role::Pauser]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only admin can perform this action")]
+    fn test_set_permission_unauthorized() {
+        let mut contract = setup_contract();
+
+        setup_context(&user(), 0);
+        contract.set_permission(Permission::GrantLicenses, vec![Role::Pauser]);
+    }
+}
@@ -0,0 +1,788 @@
+use near_sdk::{near, AccountId, env, require};
+
+use crate::events::LicenseEvent;
+use crate::jobs::batch_gas_exhausted;
+use crate::{LicenseContract, LicenseContractExt, ONE_DAY_NS};
+
+/// Maximum number of seats in a floating pool
+const MAX_POOL_SEATS: u32 = 500;
+
+/// Minutes a seat stays fresh after it was last seen, for new pools
+const DEFAULT_SEAT_TTL_MINUTES: u32 = 60;
+
+/// Maximum number of seats reclaimed by one `reap_stale_seats` call
+const MAX_REAP_SEATS: u32 = 100;
+
+/// Default number of entries returned by `get_pool_holders`
+const DEFAULT_HOLDERS_LIMIT: u32 = 50;
+
+/// Maximum number of entries returned by `get_pool_holders`
+const MAX_HOLDERS_LIMIT: u32 = 200;
+
+/// A wallet holding a seat of a floating pool.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeatCheckout {
+    /// Wallet address (or hash, in privacy mode) of the holder
+    pub wallet_address: String,
+    /// Timestamp the seat was checked out in nanoseconds
+    pub checked_out_at: u64,
+    /// Timestamp of the holder's last checkout or heartbeat in nanoseconds
+    pub last_seen_at: u64,
+}
+
+/// What happens when a wallet checks out a seat of a floating pool whose seats
+/// are all checked out.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FullPoolPolicy {
+    /// The checkout fails
+    Reject,
+    /// The seat last seen longest ago is reclaimed for the new holder if it is stale,
+    /// i.e. had no heartbeat within the pool's seat TTL; otherwise the checkout fails.
+    /// Ties go to the seat checked out first.
+    EvictStalest,
+}
+
+/// Concurrent-use license bought by an organization. Member wallets check out
+/// seats while they use the product, and are licensed while they hold one.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FloatingPool {
+    /// Account managing the pool's members, e.g. the organization's admin
+    pub manager: AccountId,
+    /// Number of seats that can be checked out at once
+    pub seats: u32,
+    /// Number of seats a single wallet can hold at once, e.g. one per machine
+    pub max_seats_per_wallet: u32,
+    /// What happens on a checkout while every seat is checked out
+    pub full_policy: FullPoolPolicy,
+    /// Minutes without a heartbeat before a seat counts as stale and can be reclaimed
+    pub seat_ttl_minutes: u32,
+    /// Timestamp until which checked out seats license their holders, in nanoseconds
+    pub expires_at: u64,
+    /// Seats currently checked out, in checkout order
+    pub holders: Vec<SeatCheckout>,
+}
+
+#[near]
+impl LicenseContract {
+    /// Create a floating pool of concurrent seats for an organization.
+    ///
+    /// # Arguments
+    /// * `manager` - Account managing the pool's members
+    /// * `seats` - Number of seats that can be checked out at once, at most 500
+    /// * `duration_days` - Number of days the pool is valid for
+    ///
+    /// # Returns
+    /// The ID of the new pool
+    ///
+    /// # Panics
+    /// Panics if caller may not grant licenses, the seat count is out of range,
+    /// or the duration overflows or exceeds the long grant threshold
+    pub fn create_pool(&mut self, manager: AccountId, seats: u32, duration_days: u32) -> u64 {
+        self.assert_can_grant();
+        assert_valid_seats(seats);
+        let duration_ns = pool_duration_ns(duration_days);
+        self.assert_within_grant_threshold(duration_ns);
+        let expires_at = env::block_timestamp()
+            .checked_add(duration_ns)
+            .unwrap_or_else(|| env::panic_str("Expiry timestamp overflow"));
+
+        let id = self.next_pool_id;
+        self.next_pool_id += 1;
+        let pool = FloatingPool {
+            manager,
+            seats,
+            max_seats_per_wallet: 1,
+            full_policy: FullPoolPolicy::Reject,
+            seat_ttl_minutes: DEFAULT_SEAT_TTL_MINUTES,
+            expires_at,
+            holders: Vec::new(),
+        };
+        emit_pool_updated(id, &pool);
+        self.pools.insert(id, pool);
+        id
+    }
+
+    /// Extend a floating pool from its current expiry, or from now if it has expired.
+    ///
+    /// # Panics
+    /// Panics if caller may not grant licenses, the pool doesn't exist, the new
+    /// expiry overflows or the remaining term would exceed the long grant threshold
+    pub fn extend_pool(&mut self, pool_id: u64, duration_days: u32) {
+        self.assert_can_grant();
+        let duration_ns = pool_duration_ns(duration_days);
+
+        let now = env::block_timestamp();
+        let expires_at = self
+            .pool_mut(pool_id)
+            .expires_at
+            .max(now)
+            .checked_add(duration_ns)
+            .unwrap_or_else(|| env::panic_str("Expiry timestamp overflow"));
+        self.assert_within_grant_threshold(expires_at - now);
+
+        let pool = self.pool_mut(pool_id);
+        pool.expires_at = expires_at;
+        emit_pool_updated(pool_id, pool);
+    }
+
+    /// Change the number of seats of a floating pool.
+    ///
+    /// # Panics
+    /// Panics if caller may not grant licenses, the pool doesn't exist, the seat
+    /// count is out of range or below the number of seats checked out
+    pub fn set_pool_seats(&mut self, pool_id: u64, seats: u32) {
+        self.assert_can_grant();
+        assert_valid_seats(seats);
+
+        let pool = self.pool_mut(pool_id);
+        require!(seats as usize >= pool.holders.len(), "More seats are checked out than requested");
+        pool.seats = seats;
+        emit_pool_updated(pool_id, pool);
+    }
+
+    /// Set the number of seats a single member wallet can hold at once (1 by default).
+    /// Seats already checked out are kept.
+    ///
+    /// # Panics
+    /// Panics if caller is not the pool's manager, the pool doesn't exist or the limit is zero
+    pub fn set_pool_wallet_limit(&mut self, pool_id: u64, max_seats: u32) {
+        self.assert_pool_manager(pool_id);
+        require!(max_seats > 0, "Wallet limit must be at least one seat");
+        self.pool_mut(pool_id).max_seats_per_wallet = max_seats;
+    }
+
+    /// Set what happens when a member checks out a seat while every seat is taken,
+    /// and how long a seat stays fresh after it was last seen (60 minutes by default).
+    ///
+    /// # Arguments
+    /// * `pool_id` - The pool to configure
+    /// * `full_policy` - Whether to reject the checkout or evict the stalest seat
+    /// * `seat_ttl_minutes` - Minutes after it was last seen before a seat counts as stale
+    ///
+    /// # Panics
+    /// Panics if caller is not the pool's manager, the pool doesn't exist or the TTL is zero
+    pub fn set_pool_policy(&mut self, pool_id: u64, full_policy: FullPoolPolicy, seat_ttl_minutes: u32) {
+        self.assert_pool_manager(pool_id);
+        require!(seat_ttl_minutes > 0, "Seat TTL must be at least one minute");
+        let pool = self.pool_mut(pool_id);
+        pool.full_policy = full_policy;
+        pool.seat_ttl_minutes = seat_ttl_minutes;
+    }
+
+    /// Allow a wallet to check out seats of a floating pool.
+    ///
+    /// # Panics
+    /// Panics if caller is not the pool's manager or the pool doesn't exist
+    pub fn add_pool_member(&mut self, pool_id: u64, wallet_address: String) {
+        self.assert_pool_manager(pool_id);
+        let key = self.wallet_key(&wallet_address);
+        self.pool_members.insert((pool_id, key));
+    }
+
+    /// Remove a wallet from a floating pool, checking in every seat it holds.
+    ///
+    /// # Panics
+    /// Panics if caller is not the pool's manager, the pool doesn't exist or the
+    /// wallet isn't a member
+    pub fn remove_pool_member(&mut self, pool_id: u64, wallet_address: String) {
+        self.assert_pool_manager(pool_id);
+        let key = self.wallet_key(&wallet_address);
+        require!(self.pool_members.remove(&(pool_id, key.clone())), "Not a member of this pool");
+        while self.release_seat(pool_id, &key) {}
+    }
+
+    /// Check out a seat of a floating pool for the caller's wallet, licensing it
+    /// until its seats are checked in or the pool expires.
+    ///
+    /// # Panics
+    /// Panics if the contract is paused, the caller isn't an eligible member of the pool,
+    /// already holds as many of its seats as a wallet may, the pool has expired, or every
+    /// seat is checked out and none can be evicted under the pool's policy
+    pub fn checkout_seat(&mut self, pool_id: u64) {
+        self.assert_not_paused();
+        let key = self.wallet_key(env::predecessor_account_id().as_str());
+        require!(self.pool_members.contains(&(pool_id, key.clone())), "Not a member of this pool");
+        self.assert_eligible(&key);
+
+        let pool = self.pool_mut(pool_id);
+        let now = env::block_timestamp();
+        require!(pool.expires_at > now, "Pool has expired");
+        let held = pool.holders.iter().filter(|holder| holder.wallet_address == key).count();
+        require!(
+            (held as u32) < pool.max_seats_per_wallet,
+            "Wallet holds the maximum number of seats in this pool"
+        );
+        if pool.holders.len() as u32 >= pool.seats {
+            let stalest = match pool.full_policy {
+                FullPoolPolicy::Reject => None,
+                FullPoolPolicy::EvictStalest => pool.stalest_seat(now),
+            };
+            let position = stalest.unwrap_or_else(|| env::panic_str("No seats available"));
+            self.reclaim_seat(pool_id, position);
+        }
+
+        let pool = self.pool_mut(pool_id);
+        let held = pool.holders.iter().filter(|holder| holder.wallet_address == key).count();
+        pool.holders.push(SeatCheckout {
+            wallet_address: key.clone(),
+            checked_out_at: now,
+            last_seen_at: now,
+        });
+
+        if held == 0 {
+            let mut pool_ids = self.seat_pools.get(&key).cloned().unwrap_or_default();
+            pool_ids.push(pool_id);
+            self.seat_pools.insert(key.clone(), pool_ids);
+        }
+
+        LicenseEvent::SeatCheckedOut { pool_id, wallet_address: key }.emit();
+    }
+
+    /// Refresh the caller's seats in a floating pool, so they don't go stale. Clients
+    /// call this periodically while the product is in use.
+    ///
+    /// # Panics
+    /// Panics if the caller holds no seat in the pool
+    pub fn heartbeat(&mut self, pool_id: u64) {
+        self.assert_not_shut_down();
+        let key = self.wallet_key(env::predecessor_account_id().as_str());
+        let now = env::block_timestamp();
+
+        let mut refreshed = false;
+        for holder in self.pool_mut(pool_id).holders.iter_mut() {
+            if holder.wallet_address == key {
+                holder.last_seen_at = now;
+                refreshed = true;
+            }
+        }
+        require!(refreshed, "Wallet holds no seat in this pool");
+    }
+
+    /// Reclaim stale seats, i.e. seats without a heartbeat within their pool's seat TTL,
+    /// and seats of expired pools, so crashed clients don't hold seats forever. Anyone
+    /// can call this; pools are scanned round-robin from where the last call stopped.
+    ///
+    /// # Arguments
+    /// * `limit` - Maximum number of seats to reclaim (capped at 100)
+    ///
+    /// # Returns
+    /// The number of seats reclaimed
+    pub fn reap_stale_seats(&mut self, limit: u32) -> u32 {
+        self.assert_not_shut_down();
+        let limit = limit.min(MAX_REAP_SEATS);
+        let now = env::block_timestamp();
+
+        let mut reclaimed = 0;
+        let mut scanned = 0;
+        while scanned < self.next_pool_id && !batch_gas_exhausted() {
+            let pool_id = self.reap_cursor % self.next_pool_id;
+            while let Some(position) = self.pools.get(&pool_id).and_then(|pool| pool.stale_seat(now)) {
+                // Resume from this pool next time, as it may hold more stale seats
+                if reclaimed == limit {
+                    return reclaimed;
+                }
+                self.reclaim_seat(pool_id, position);
+                reclaimed += 1;
+            }
+            self.reap_cursor = pool_id + 1;
+            scanned += 1;
+        }
+        reclaimed
+    }
+
+    /// Check in the caller's longest-held seat of a floating pool, freeing it for other members.
+    ///
+    /// # Panics
+    /// Panics if the caller holds no seat in the pool
+    pub fn checkin_seat(&mut self, pool_id: u64) {
+        self.assert_not_shut_down();
+        let key = self.wallet_key(env::predecessor_account_id().as_str());
+        require!(self.release_seat(pool_id, &key), "Wallet holds no seat in this pool");
+    }
+
+    /// Get a floating pool, including its current seat holders.
+    pub fn get_pool(&self, pool_id: u64) -> Option<FloatingPool> {
+        self.pools.get(&pool_id).cloned()
+    }
+
+    /// Get the seats currently checked out of a floating pool, in checkout order.
+    ///
+    /// # Arguments
+    /// * `pool_id` - The pool to list
+    /// * `from_index` - Position to start from (default 0)
+    /// * `limit` - Maximum number of entries to return (default 50, capped at 200)
+    pub fn get_pool_holders(&self, pool_id: u64, from_index: Option<u32>, limit: Option<u32>) -> Vec<SeatCheckout> {
+        let limit = limit.unwrap_or(DEFAULT_HOLDERS_LIMIT).min(MAX_HOLDERS_LIMIT);
+        self.pools
+            .get(&pool_id)
+            .map(|pool| {
+                pool.holders
+                    .iter()
+                    .skip(from_index.unwrap_or(0) as usize)
+                    .take(limit as usize)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get the IDs of the floating pools a wallet holds seats in.
+    pub fn get_wallet_pools(&self, wallet_address: String) -> Vec<u64> {
+        self.seat_pools
+            .get(&self.wallet_key(&wallet_address))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Check whether a wallet may check out seats of a floating pool.
+    pub fn is_pool_member(&self, pool_id: u64, wallet_address: String) -> bool {
+        self.pool_members.contains(&(pool_id, self.wallet_key(&wallet_address)))
+    }
+
+    /// Check if the wallet stored under a key holds a seat of an unexpired floating pool
+    pub(crate) fn holds_seat(&self, key: &str) -> bool {
+        let now = env::block_timestamp();
+        self.seat_pools.get(key).is_some_and(|pool_ids| {
+            pool_ids
+                .iter()
+                .any(|pool_id| self.pools.get(pool_id).is_some_and(|pool| pool.expires_at > now))
+        })
+    }
+
+    /// Take back the seat at `position` in a pool's holders from its holder
+    fn reclaim_seat(&mut self, pool_id: u64, position: usize) {
+        let pool = self.pool_mut(pool_id);
+        let seat = pool.holders.remove(position);
+        if !pool.holders.iter().any(|holder| holder.wallet_address == seat.wallet_address) {
+            self.forget_seat_pool(&seat.wallet_address, pool_id);
+        }
+
+        LicenseEvent::SeatReclaimed {
+            pool_id,
+            wallet_address: seat.wallet_address,
+            reclaimed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    /// Check in the longest-held seat the wallet stored under `key` holds in a pool,
+    /// returning whether it held one
+    fn release_seat(&mut self, pool_id: u64, key: &str) -> bool {
+        let pool = self.pool_mut(pool_id);
+        let Some(position) = pool.holders.iter().position(|holder| holder.wallet_address == key) else {
+            return false;
+        };
+        pool.holders.remove(position);
+        if !pool.holders.iter().any(|holder| holder.wallet_address == key) {
+            self.forget_seat_pool(key, pool_id);
+        }
+
+        LicenseEvent::SeatCheckedIn {
+            pool_id,
+            wallet_address: key.to_string(),
+        }
+        .emit();
+        true
+    }
+
+    /// Drop a pool from the pools the wallet stored under `key` holds seats in
+    fn forget_seat_pool(&mut self, key: &str, pool_id: u64) {
+        let mut pool_ids = self.seat_pools.get(key).cloned().unwrap_or_default();
+        pool_ids.retain(|&id| id != pool_id);
+        if pool_ids.is_empty() {
+            self.seat_pools.remove(key);
+        } else {
+            self.seat_pools.insert(key.to_string(), pool_ids);
+        }
+    }
+
+    /// Get a floating pool for modification
+    fn pool_mut(&mut self, pool_id: u64) -> &mut FloatingPool {
+        self.pools
+            .get_mut(&pool_id)
+            .unwrap_or_else(|| env::panic_str("Unknown pool"))
+    }
+
+    /// Panics unless the contract is running and the caller manages the pool
+    fn assert_pool_manager(&self, pool_id: u64) {
+        self.assert_not_shut_down();
+        let pool = self.pools.get(&pool_id).unwrap_or_else(|| env::panic_str("Unknown pool"));
+        require!(
+            pool.manager == env::predecessor_account_id(),
+            "Unauthorized: only the pool manager can manage members"
+        );
+    }
+}
+
+impl FloatingPool {
+    /// Whether a seat of this pool can be reclaimed at `now`
+    fn is_stale(&self, seat: &SeatCheckout, now: u64) -> bool {
+        self.expires_at <= now || now.saturating_sub(seat.last_seen_at) >= self.seat_ttl_minutes as u64 * 60_000_000_000
+    }
+
+    /// Position of the seat last seen longest ago, if it is stale at `now`
+    fn stalest_seat(&self, now: u64) -> Option<usize> {
+        self.holders
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, holder)| holder.last_seen_at)
+            .filter(|(_, holder)| self.is_stale(holder, now))
+            .map(|(position, _)| position)
+    }
+
+    /// Position of the first stale seat at `now`, if any
+    fn stale_seat(&self, now: u64) -> Option<usize> {
+        self.holders.iter().position(|holder| self.is_stale(holder, now))
+    }
+}
+
+/// Panics if a pool seat count is out of range
+fn assert_valid_seats(seats: u32) {
+    require!(seats > 0 && seats <= MAX_POOL_SEATS, "Pool must have between 1 and 500 seats");
+}
+
+/// A pool duration in nanoseconds, panicking if it overflows
+fn pool_duration_ns(duration_days: u32) -> u64 {
+    (duration_days as u64)
+        .checked_mul(ONE_DAY_NS)
+        .unwrap_or_else(|| env::panic_str("Expiry timestamp overflow"))
+}
+
+/// Emit the `pool_updated` event for a pool's current terms
+fn emit_pool_updated(pool_id: u64, pool: &FloatingPool) {
+    LicenseEvent::PoolUpdated {
+        pool_id,
+        manager: pool.manager.clone(),
+        seats: pool.seats,
+        expires_at: pool.expires_at,
+    }
+    .emit();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+
+    const ONE_MINUTE_NS: u64 = 60_000_000_000;
+
+    fn org() -> AccountId {
+        "org.near".parse().unwrap()
+    }
+
+    fn member(i: u32) -> AccountId {
+        format!("member{}.near", i).parse().unwrap()
+    }
+
+    /// Contract with a two-seat pool valid for 30 days and three members
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        let pool_id = contract.create_pool(org(), 2, 30);
+
+        setup_context(&org(), 0);
+        for i in 0..3 {
+            contract.add_pool_member(pool_id, member(i).to_string());
+        }
+        contract
+    }
+
+    #[test]
+    fn test_seat_licenses_holder_until_checked_in() {
+        let mut contract = setup_contract();
+        assert!(!contract.is_licensed(member(0).to_string()));
+
+        setup_context(&member(0), 0);
+        contract.checkout_seat(0);
+        assert!(contract.is_licensed(member(0).to_string()));
+        assert_eq!(contract.get_pool(0).unwrap().holders.len(), 1);
+
+        contract.checkin_seat(0);
+        assert!(!contract.is_licensed(member(0).to_string()));
+        assert!(contract.get_pool(0).unwrap().holders.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "No seats available")]
+    fn test_checkout_rejected_when_pool_full() {
+        let mut contract = setup_contract();
+        for i in 0..3 {
+            setup_context(&member(i), 0);
+            contract.checkout_seat(0);
+        }
+    }
+
+    #[test]
+    fn test_checked_in_seat_can_be_reused() {
+        let mut contract = setup_contract();
+        for i in 0..2 {
+            setup_context(&member(i), 0);
+            contract.checkout_seat(0);
+        }
+
+        setup_context(&member(0), 0);
+        contract.checkin_seat(0);
+        setup_context(&member(2), 0);
+        contract.checkout_seat(0);
+        assert!(contract.is_licensed(member(2).to_string()));
+    }
+
+    #[test]
+    fn test_seat_stops_licensing_when_pool_expires() {
+        let mut contract = setup_contract();
+        setup_context(&member(0), 0);
+        contract.checkout_seat(0);
+
+        setup_context(&member(0), 30 * ONE_DAY_NS);
+        assert!(!contract.is_licensed(member(0).to_string()));
+
+        setup_context(&admin(), 30 * ONE_DAY_NS);
+        contract.extend_pool(0, 30);
+        assert!(contract.is_licensed(member(0).to_string()));
+        assert_eq!(contract.get_pool(0).unwrap().expires_at, 60 * ONE_DAY_NS);
+    }
+
+    #[test]
+    fn test_removed_member_loses_seat() {
+        let mut contract = setup_contract();
+        setup_context(&member(0), 0);
+        contract.checkout_seat(0);
+
+        setup_context(&org(), 0);
+        contract.remove_pool_member(0, member(0).to_string());
+        assert!(!contract.is_pool_member(0, member(0).to_string()));
+        assert!(!contract.is_licensed(member(0).to_string()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not a member of this pool")]
+    fn test_checkout_requires_membership() {
+        let mut contract = setup_contract();
+
+        setup_context(&user(), 0);
+        contract.checkout_seat(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Wallet holds the maximum number of seats in this pool")]
+    fn test_checkout_twice_rejected() {
+        let mut contract = setup_contract();
+        setup_context(&member(0), 0);
+        contract.checkout_seat(0);
+
+        contract.checkout_seat(0);
+    }
+
+    #[test]
+    fn test_wallet_limit_allows_several_seats() {
+        let mut contract = setup_contract();
+        setup_context(&org(), 0);
+        contract.set_pool_wallet_limit(0, 2);
+
+        setup_context(&member(0), 0);
+        contract.checkout_seat(0);
+        contract.checkout_seat(0);
+        assert_eq!(contract.get_wallet_pools(member(0).to_string()), vec![0]);
+
+        // Checking in one of two seats keeps the wallet licensed
+        contract.checkin_seat(0);
+        assert!(contract.is_licensed(member(0).to_string()));
+        contract.checkin_seat(0);
+        assert!(!contract.is_licensed(member(0).to_string()));
+        assert!(contract.get_wallet_pools(member(0).to_string()).is_empty());
+    }
+
+    #[test]
+    fn test_pool_holders_listed_in_checkout_order() {
+        let mut contract = setup_contract();
+        for i in 0..2 {
+            setup_context(&member(i), i as u64);
+            contract.checkout_seat(0);
+        }
+
+        let holders = contract.get_pool_holders(0, Some(1), None);
+        assert_eq!(
+            holders,
+            vec![SeatCheckout {
+                wallet_address: member(1).to_string(),
+                checked_out_at: 1,
+                last_seen_at: 1,
+            }]
+        );
+        assert_eq!(contract.get_pool_holders(0, None, Some(1)).len(), 1);
+        assert!(contract.get_pool_holders(7, None, None).is_empty());
+    }
+
+    #[test]
+    fn test_removed_member_loses_every_seat() {
+        let mut contract = setup_contract();
+        setup_context(&org(), 0);
+        contract.set_pool_wallet_limit(0, 2);
+        setup_context(&member(0), 0);
+        contract.checkout_seat(0);
+        contract.checkout_seat(0);
+
+        setup_context(&org(), 0);
+        contract.remove_pool_member(0, member(0).to_string());
+        assert!(contract.get_pool_holders(0, None, None).is_empty());
+        assert!(!contract.is_licensed(member(0).to_string()));
+    }
+
+    #[test]
+    fn test_full_pool_evicts_stalest_seat() {
+        let mut contract = setup_contract();
+        setup_context(&org(), 0);
+        contract.set_pool_policy(0, FullPoolPolicy::EvictStalest, 60);
+        for i in 0..2 {
+            setup_context(&member(i), i as u64 * ONE_MINUTE_NS);
+            contract.checkout_seat(0);
+        }
+
+        // Both seats went stale; the one checked out first is reclaimed
+        setup_context(&member(2), 61 * ONE_MINUTE_NS);
+        contract.checkout_seat(0);
+        assert!(!contract.is_licensed(member(0).to_string()));
+        assert!(contract.is_licensed(member(1).to_string()));
+        assert!(contract.is_licensed(member(2).to_string()));
+        assert!(contract.get_wallet_pools(member(0).to_string()).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "No seats available")]
+    fn test_full_pool_keeps_fresh_seats() {
+        let mut contract = setup_contract();
+        setup_context(&org(), 0);
+        contract.set_pool_policy(0, FullPoolPolicy::EvictStalest, 60);
+        for i in 0..2 {
+            setup_context(&member(i), 0);
+            contract.checkout_seat(0);
+        }
+
+        setup_context(&member(2), 59 * ONE_MINUTE_NS);
+        contract.checkout_seat(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "No seats available")]
+    fn test_full_pool_rejects_by_default() {
+        let mut contract = setup_contract();
+        for i in 0..2 {
+            setup_context(&member(i), 0);
+            contract.checkout_seat(0);
+        }
+
+        setup_context(&member(2), 30 * ONE_DAY_NS - 1);
+        contract.checkout_seat(0);
+    }
+
+    #[test]
+    fn test_heartbeat_keeps_seat_fresh() {
+        let mut contract = setup_contract();
+        setup_context(&org(), 0);
+        contract.set_pool_policy(0, FullPoolPolicy::EvictStalest, 60);
+        for i in 0..2 {
+            setup_context(&member(i), 0);
+            contract.checkout_seat(0);
+        }
+        setup_context(&member(0), 30 * ONE_MINUTE_NS);
+        contract.heartbeat(0);
+
+        // Only the seat without a heartbeat went stale
+        setup_context(&member(2), 61 * ONE_MINUTE_NS);
+        contract.checkout_seat(0);
+        assert!(contract.is_licensed(member(0).to_string()));
+        assert!(!contract.is_licensed(member(1).to_string()));
+    }
+
+    #[test]
+    fn test_reap_stale_seats() {
+        let mut contract = setup_contract();
+        setup_context(&admin(), 0);
+        contract.create_pool(org(), 2, 30);
+        setup_context(&org(), 0);
+        contract.add_pool_member(1, member(0).to_string());
+        for pool_id in 0..2 {
+            setup_context(&member(0), 0);
+            contract.checkout_seat(pool_id);
+        }
+        setup_context(&member(1), 0);
+        contract.checkout_seat(0);
+        setup_context(&member(1), 60 * ONE_MINUTE_NS - 1);
+        contract.heartbeat(0);
+
+        // Anyone can reap, a page at a time
+        setup_context(&user(), 60 * ONE_MINUTE_NS);
+        assert_eq!(contract.reap_stale_seats(1), 1);
+        assert_eq!(contract.get_wallet_pools(member(0).to_string()), vec![1]);
+        assert_eq!(contract.reap_stale_seats(10), 1);
+        assert!(!contract.is_licensed(member(0).to_string()));
+        assert!(contract.is_licensed(member(1).to_string()));
+        assert_eq!(contract.reap_stale_seats(10), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Wallet holds no seat in this pool")]
+    fn test_heartbeat_without_seat_rejected() {
+        let mut contract = setup_contract();
+
+        setup_context(&member(0), 0);
+        contract.heartbeat(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "More seats are checked out than requested")]
+    fn test_seats_cannot_drop_below_checkouts() {
+        let mut contract = setup_contract();
+        for i in 0..2 {
+            setup_context(&member(i), 0);
+            contract.checkout_seat(0);
+        }
+
+        setup_context(&admin(), 0);
+        contract.set_pool_seats(0, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Grants longer than 60 days need the co-signer, use propose_long_grant")]
+    fn test_pool_extension_limited_by_remaining_term() {
+        let mut contract = setup_contract();
+        setup_context(&admin(), 0);
+        contract.set_long_grant_threshold(Some(60));
+
+        contract.extend_pool(0, 30);
+        contract.extend_pool(0, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expiry timestamp overflow")]
+    fn test_pool_duration_overflow() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.create_pool(org(), 2, u32::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expiry timestamp overflow")]
+    fn test_pool_extension_overflow() {
+        let mut contract = setup_contract();
+        setup_context(&admin(), 0);
+        contract.pool_mut(0).expires_at = u64::MAX - ONE_DAY_NS;
+
+        contract.extend_pool(0, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only the pool manager can manage members")]
+    fn test_add_member_unauthorized() {
+        let mut contract = setup_contract();
+
+        setup_context(&user(), 0);
+        contract.add_pool_member(0, user_str());
+    }
+}
@@ -0,0 +1,148 @@
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::{near, env, require};
+
+use crate::{AdminAction, LicenseContract, LicenseContractExt};
+
+/// Minimum salt length in bytes for privacy mode
+pub(crate) const MIN_SALT_LEN: usize = 16;
+
+/// Lower-case hex encoding of a byte slice
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[near]
+impl LicenseContract {
+    /// Enable privacy mode: from now on wallet addresses are stored only as
+    /// `hex(sha256(salt || wallet_address))`. Clients keep querying with the plain
+    /// wallet address, which is hashed before every lookup.
+    /// This is one-way and must happen before any wallet is licensed or floating pool
+    /// created, and never on migrated state, since existing entries would become unreachable.
+    ///
+    /// # Arguments
+    /// * `salt` - Random salt of at least 16 bytes
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, privacy mode is already
+    /// enabled, the state was migrated, any wallet has already been licensed or pool
+    /// created, or the salt is too short
+    pub fn enable_privacy_mode(&mut self, salt: Base64VecU8) {
+        self.submit_action(AdminAction::EnablePrivacyMode { salt });
+    }
+
+    /// Check whether wallet addresses are stored hashed.
+    pub fn is_privacy_mode_enabled(&self) -> bool {
+        self.privacy_salt.is_some()
+    }
+
+    /// Start hashing wallet addresses with `salt`, checked against the state at the
+    /// time it applies, since a queued action may apply after wallets were licensed
+    pub(crate) fn apply_privacy_mode(&mut self, salt: Base64VecU8) {
+        require!(self.privacy_salt.is_none(), "Privacy mode is already enabled");
+        require!(
+            self.wallets.is_empty(),
+            "Privacy mode must be enabled before any wallet is licensed"
+        );
+        require!(
+            self.next_pool_id == 0,
+            "Privacy mode must be enabled before any floating pool is created"
+        );
+        require!(!self.migrated, "Privacy mode can't be enabled on migrated state");
+
+        self.privacy_salt = Some(salt.0);
+    }
+
+    /// Derive the storage key for a wallet address: the address itself, or its
+    /// salted hash in privacy mode
+    pub(crate) fn wallet_key(&self, wallet_address: &str) -> String {
+        match &self.privacy_salt {
+            Some(salt) => {
+                let mut preimage = salt.clone();
+                preimage.extend_from_slice(wallet_address.as_bytes());
+                to_hex(&env::sha256(&preimage))
+            }
+            None => wallet_address.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::{Network, OldLicenseContract};
+    use near_sdk::store::LookupMap;
+
+    fn salt() -> Base64VecU8 {
+        Base64VecU8(vec![7; 16])
+    }
+
+    #[test]
+    fn test_privacy_mode_stores_hashed_keys() {
+        setup_context(&admin(), 1_000_000_000);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.enable_privacy_mode(salt());
+        assert!(contract.is_privacy_mode_enabled());
+
+        contract.grant_license(user_str(), 30, None, None);
+
+        // Checks by preimage keep working
+        assert!(contract.is_licensed(user_str()));
+        assert!(contract.get_expiry(user_str()).is_some());
+
+        // Nothing is stored under the plain address
+        assert!(!contract.licenses.contains_key(&user_str()));
+
+        let mut preimage = vec![7; 16];
+        preimage.extend_from_slice(user_str().as_bytes());
+        let expected_key = to_hex(&env::sha256(&preimage));
+
+        let records = contract.export_licenses(None, None);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].wallet_address, expected_key);
+    }
+
+    #[test]
+    #[should_panic(expected = "Privacy mode must be enabled before any wallet is licensed")]
+    fn test_privacy_mode_rejected_after_grants() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+
+        contract.enable_privacy_mode(salt());
+    }
+
+    #[test]
+    #[should_panic(expected = "Privacy mode must be enabled before any floating pool is created")]
+    fn test_privacy_mode_rejected_after_pool_created() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        let pool_id = contract.create_pool(admin(), 5, 30);
+        contract.add_pool_member(pool_id, user_str());
+
+        contract.enable_privacy_mode(salt());
+    }
+
+    #[test]
+    #[should_panic(expected = "Privacy mode can't be enabled on migrated state")]
+    fn test_privacy_mode_rejected_after_migration() {
+        setup_context(&admin(), 0);
+        let mut licenses = LookupMap::new(b"l");
+        licenses.insert(user(), 1_000);
+        licenses.flush();
+        env::state_write(&OldLicenseContract { licenses, admin: admin() });
+        // The legacy entry lives in the licenses map without being indexed
+        let mut contract = LicenseContract::migrate(Network::Mainnet);
+
+        contract.enable_privacy_mode(salt());
+    }
+
+    #[test]
+    #[should_panic(expected = "Privacy salt must be at least 16 bytes")]
+    fn test_privacy_mode_short_salt() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.enable_privacy_mode(Base64VecU8(vec![1; 8]));
+    }
+}
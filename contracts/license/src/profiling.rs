@@ -0,0 +1,160 @@
+use near_sdk::{near, env, Gas};
+
+use crate::{AdminAction, LicenseContract, LicenseContractExt};
+
+/// Class of operation whose gas usage is profiled
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GasClass {
+    /// Any license grant
+    Grant,
+    /// `transfer_license`
+    Transfer,
+    /// `execute_recovery`
+    Recovery,
+    /// `import_licenses`
+    Import,
+    /// License checks measured through `profile_license_check`
+    LicenseCheck,
+}
+
+impl GasClass {
+    const ALL: [GasClass; 5] = [
+        GasClass::Grant,
+        GasClass::Transfer,
+        GasClass::Recovery,
+        GasClass::Import,
+        GasClass::LicenseCheck,
+    ];
+}
+
+/// Gas used by the invocations of one operation class since profiling was enabled.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GasStats {
+    pub calls: u64,
+    pub total_gas: Gas,
+    pub max_gas: Gas,
+}
+
+/// Gas statistics of an operation class, as returned by `get_gas_profile`.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GasProfileEntry {
+    pub class: GasClass,
+    pub stats: GasStats,
+}
+
+#[near]
+impl LicenseContract {
+    /// Enable or disable gas profiling. While enabled, change methods record the
+    /// gas they used (up to the point of recording, so excluding the final state
+    /// write) per operation class. Disabling keeps the recorded counters.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or an action delay is set
+    pub fn set_gas_profiling(&mut self, enabled: bool) {
+        self.submit_action(AdminAction::SetGasProfiling { enabled });
+    }
+
+    /// Clear every recorded gas counter.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin
+    pub fn reset_gas_profile(&mut self) {
+        self.assert_admin();
+        for class in GasClass::ALL {
+            self.gas_profile.remove(&class);
+        }
+    }
+
+    /// Check a license like `is_licensed` and record the gas it took. Views can't
+    /// write state, so this is how the license check hot path is profiled.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin
+    pub fn profile_license_check(&mut self, wallet_address: String) -> bool {
+        self.assert_admin();
+        let start = env::used_gas();
+        let licensed = self.is_licensed(wallet_address);
+        self.record_gas_since(GasClass::LicenseCheck, start);
+        licensed
+    }
+
+    /// Check whether gas profiling is enabled.
+    pub fn is_gas_profiling_enabled(&self) -> bool {
+        self.gas_profiling
+    }
+
+    /// Get the recorded gas statistics of every operation class that has been invoked.
+    pub fn get_gas_profile(&self) -> Vec<GasProfileEntry> {
+        GasClass::ALL
+            .into_iter()
+            .filter_map(|class| {
+                self.gas_profile
+                    .get(&class)
+                    .map(|stats| GasProfileEntry { class, stats: stats.clone() })
+            })
+            .collect()
+    }
+
+    /// Record the gas used by the current call, if profiling is enabled
+    pub(crate) fn record_gas(&mut self, class: GasClass) {
+        self.record_gas_since(class, Gas::from_gas(0));
+    }
+
+    /// Record the gas used since `start`, if profiling is enabled
+    fn record_gas_since(&mut self, class: GasClass, start: Gas) {
+        if !self.gas_profiling {
+            return;
+        }
+
+        let used = env::used_gas().saturating_sub(start);
+        let stats = self.gas_profile.entry(class).or_default();
+        stats.calls += 1;
+        stats.total_gas = stats.total_gas.saturating_add(used);
+        stats.max_gas = stats.max_gas.max(used);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+
+    #[test]
+    fn test_gas_profiling_is_opt_in() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license(user_str(), 30, None, None);
+        assert!(contract.get_gas_profile().is_empty());
+
+        contract.set_gas_profiling(true);
+        contract.grant_license(user_str(), 30, None, None);
+        contract.grant_license_blocks(user_str(), 100, None, None);
+        assert!(contract.profile_license_check(user_str()));
+
+        let profile = contract.get_gas_profile();
+        assert_eq!(profile.len(), 2);
+        assert_eq!(profile[0].class, GasClass::Grant);
+        assert_eq!(profile[0].stats.calls, 2);
+        assert!(profile[0].stats.max_gas <= profile[0].stats.total_gas);
+        assert_eq!(profile[1].class, GasClass::LicenseCheck);
+        assert_eq!(profile[1].stats.calls, 1);
+
+        contract.reset_gas_profile();
+        assert!(contract.get_gas_profile().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_set_gas_profiling_unauthorized() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        setup_context(&user(), 0);
+        contract.set_gas_profiling(true);
+    }
+}
@@ -0,0 +1,136 @@
+use near_sdk::{near, AccountId, env, require};
+
+use crate::{LicenseContract, LicenseContractExt, TransferKind};
+
+/// Maximum length of a grant or revocation memo in bytes
+const MAX_MEMO_LEN: usize = 256;
+
+/// Panics if a memo exceeds `MAX_MEMO_LEN`
+pub(crate) fn assert_valid_memo(memo: &Option<String>) {
+    require!(
+        memo.as_ref().is_none_or(|memo| memo.len() <= MAX_MEMO_LEN),
+        "Memo is too long"
+    );
+}
+
+/// How a wallet came to hold its license.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrantSource {
+    /// Granted directly by the admin
+    Admin,
+    /// Written by an import from another deployment or the legacy system
+    Migration,
+    /// Received from another wallet via `transfer_license`
+    Transfer,
+    /// Moved to the wallet by guardian recovery
+    Recovery,
+    /// Bought from the previous holder on the resale market
+    Purchase,
+}
+
+impl From<TransferKind> for GrantSource {
+    fn from(kind: TransferKind) -> Self {
+        match kind {
+            TransferKind::Transfer => GrantSource::Transfer,
+            TransferKind::Recovery => GrantSource::Recovery,
+            TransferKind::Sale => GrantSource::Purchase,
+        }
+    }
+}
+
+/// Who last granted or moved a wallet's license, and how.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Provenance {
+    /// Account whose call performed the mutation
+    pub granted_by: AccountId,
+    pub source: GrantSource,
+    /// Timestamp of the mutation in nanoseconds
+    pub granted_at: u64,
+    /// Justification supplied with the grant, e.g. a ticket number or invoice reference
+    pub memo: Option<String>,
+}
+
+#[near]
+impl LicenseContract {
+    /// Get the provenance of the last mutation of a wallet's license, e.g. to scope
+    /// the grants made by a compromised key.
+    pub fn get_license_provenance(&self, wallet_address: String) -> Option<Provenance> {
+        self.provenance.get(&self.wallet_key(&wallet_address)).cloned()
+    }
+
+    /// Record the caller as the source of the latest mutation of the license stored under `key`
+    pub(crate) fn record_provenance(&mut self, key: &str, source: GrantSource, memo: Option<String>) {
+        self.provenance.insert(
+            key.to_string(),
+            Provenance {
+                granted_by: env::predecessor_account_id(),
+                source,
+                granted_at: env::block_timestamp(),
+                memo,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::{LegacyLicense, Network};
+
+    #[test]
+    fn test_grant_records_provenance() {
+        setup_context(&admin(), 7);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        assert_eq!(contract.get_license_provenance(user_str()), None);
+
+        contract.grant_license_blocks(user_str(), 100, None, Some("SUP-42".to_string()));
+        assert_eq!(
+            contract.get_license_provenance(user_str()),
+            Some(Provenance {
+                granted_by: admin(),
+                source: GrantSource::Admin,
+                granted_at: 7,
+                memo: Some("SUP-42".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Memo is too long")]
+    fn test_grant_rejects_long_memo() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license(user_str(), 30, None, Some("x".repeat(257)));
+    }
+
+    #[test]
+    fn test_transfer_moves_provenance() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+
+        setup_context(&user(), 0);
+        contract.transfer_license("new.near".to_string());
+
+        assert_eq!(contract.get_license_provenance(user_str()), None);
+        let provenance = contract.get_license_provenance("new.near".to_string()).unwrap();
+        assert_eq!((provenance.granted_by, provenance.source), (user(), GrantSource::Transfer));
+    }
+
+    #[test]
+    fn test_import_records_migration_source() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.import_legacy_licenses(vec![LegacyLicense { wallet_address: user_str(), expiry: 1 }], None);
+        assert_eq!(
+            contract.get_license_provenance(user_str()).unwrap().source,
+            GrantSource::Migration
+        );
+    }
+}
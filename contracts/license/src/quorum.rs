@@ -0,0 +1,433 @@
+use near_sdk::{near, AccountId, env, require};
+
+use crate::checksum::ExpiryKind;
+use crate::events::LicenseEvent;
+use crate::provenance::assert_valid_memo;
+use crate::{AdminAction, LicenseContract, LicenseContractExt, EPOCH_DURATION_NS, ONE_DAY_NS};
+
+/// Maximum number of long grants waiting for the co-signer at once
+const MAX_PENDING_GRANTS: usize = 20;
+
+/// Block time used to measure block-height grants against the long grant threshold
+pub(crate) const BLOCK_TIME_NS: u64 = 1_000_000_000;
+
+/// Length of a long grant in nanoseconds
+fn long_grant_duration_ns(duration_days: u32) -> u64 {
+    (duration_days as u64)
+        .checked_mul(ONE_DAY_NS)
+        .unwrap_or_else(|| env::panic_str("Expiry timestamp overflow"))
+}
+
+/// A long grant proposed by the admin, waiting for the co-signer's approval.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingGrant {
+    pub id: u64,
+    pub wallet_address: String,
+    pub duration_days: u32,
+    pub memo: Option<String>,
+    /// Timestamp the grant was proposed in nanoseconds
+    pub proposed_at: u64,
+}
+
+#[near]
+impl LicenseContract {
+    /// Set (or clear) the grant co-signer, e.g. a multisig or DAO contract, which
+    /// approves grants longer than the long grant threshold. Once a co-signer is set,
+    /// replacing or clearing it also needs its approval, see `approve_quorum_change`.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, the account is the
+    /// admin, or a co-signer is set and hasn't approved the change
+    pub fn set_grant_cosigner(&mut self, account_id: Option<AccountId>) {
+        self.submit_action(AdminAction::SetGrantCosigner { account_id });
+    }
+
+    /// Set (or clear) the longest grant the admin can make alone. Grants that would
+    /// leave a longer term remaining, including extensions of a license that already
+    /// runs for a while, must be proposed with `propose_long_grant` and approved by the
+    /// co-signer, so a single compromised admin key can't mint near-permanent licenses.
+    ///
+    /// Once a co-signer is set, changing the threshold also needs its approval, see
+    /// `approve_quorum_change`.
+    ///
+    /// # Arguments
+    /// * `days` - Longest single grant in days, or `None` to lift the limit
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, or a co-signer is set
+    /// and hasn't approved the change
+    pub fn set_long_grant_threshold(&mut self, days: Option<u32>) {
+        self.submit_action(AdminAction::SetLongGrantThreshold { days });
+    }
+
+    /// Approve a change of the grant co-signer or long grant threshold, which the admin
+    /// can then make with `set_grant_cosigner` or `set_long_grant_threshold`. The
+    /// approval covers only that exact change and is used up when it is made; a new
+    /// approval replaces the previous one.
+    ///
+    /// # Arguments
+    /// * `action` - The `set_grant_cosigner` or `set_long_grant_threshold` action to approve
+    ///
+    /// # Panics
+    /// Panics if the contract is shut down, caller is not the co-signer, or the action
+    /// changes anything else
+    pub fn approve_quorum_change(&mut self, action: AdminAction) {
+        self.assert_not_shut_down();
+        let caller = env::predecessor_account_id();
+        require!(
+            self.grant_cosigner.as_ref() == Some(&caller),
+            "Unauthorized: only the grant co-signer can approve quorum changes"
+        );
+        require!(
+            matches!(action, AdminAction::SetGrantCosigner { .. } | AdminAction::SetLongGrantThreshold { .. }),
+            "Only co-signer and long grant threshold changes need the co-signer's approval"
+        );
+
+        self.approved_quorum_change = Some(action.clone());
+        LicenseEvent::QuorumChangeApproved { action, approved_by: caller }.emit();
+    }
+
+    /// Propose a grant longer than the long grant threshold for the co-signer to approve.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet address to grant the license to
+    /// * `duration_days` - Number of days to grant the license for
+    /// * `memo` - Optional justification stored with the license's provenance
+    ///
+    /// # Returns
+    /// The ID of the pending grant
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, the wallet isn't eligible, the duration overflows,
+    /// the memo is too long, or 20 grants are already pending
+    pub fn propose_long_grant(&mut self, wallet_address: String, duration_days: u32, memo: Option<String>) -> u64 {
+        let key = self.wallet_key(&wallet_address);
+        self.assert_grant_allowed(&key);
+        require!(
+            env::block_timestamp()
+                .checked_add(long_grant_duration_ns(duration_days))
+                .is_some(),
+            "Expiry timestamp overflow"
+        );
+        assert_valid_memo(&memo);
+        require!(self.pending_grants.len() < MAX_PENDING_GRANTS, "Too many pending grants");
+
+        let id = self.next_grant_id;
+        self.next_grant_id += 1;
+        self.pending_grants.push(PendingGrant {
+            id,
+            wallet_address: key.clone(),
+            duration_days,
+            memo,
+            proposed_at: env::block_timestamp(),
+        });
+
+        LicenseEvent::LongGrantProposed {
+            grant_id: id,
+            wallet_address: key,
+            duration_days,
+        }
+        .emit();
+        id
+    }
+
+    /// Approve a pending long grant, granting the license.
+    ///
+    /// # Panics
+    /// Panics if caller is not the co-signer, the contract is paused, no such grant
+    /// is pending or the wallet is no longer eligible
+    pub fn approve_long_grant(&mut self, grant_id: u64) {
+        self.assert_not_paused();
+        let caller = env::predecessor_account_id();
+        require!(
+            self.grant_cosigner.as_ref() == Some(&caller),
+            "Unauthorized: only the grant co-signer can approve long grants"
+        );
+
+        let position = self.pending_grant_position(grant_id);
+        let grant = self.pending_grants.remove(position);
+        self.assert_eligible(&grant.wallet_address);
+
+        LicenseEvent::LongGrantApproved { grant_id, approved_by: caller }.emit();
+        self.extend_key(grant.wallet_address, long_grant_duration_ns(grant.duration_days), None, grant.memo);
+    }
+
+    /// Withdraw a pending long grant.
+    ///
+    /// # Panics
+    /// Panics if caller is neither the admin nor the co-signer, or no such grant is pending
+    pub fn cancel_long_grant(&mut self, grant_id: u64) {
+        self.assert_not_shut_down();
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == self.admin || self.grant_cosigner.as_ref() == Some(&caller),
+            "Unauthorized: only admin or grant co-signer can cancel long grants"
+        );
+
+        let position = self.pending_grant_position(grant_id);
+        self.pending_grants.remove(position);
+        LicenseEvent::LongGrantCancelled {
+            grant_id,
+            cancelled_by: caller,
+        }
+        .emit();
+    }
+
+    /// Get the grant co-signer, if any.
+    pub fn get_grant_cosigner(&self) -> Option<AccountId> {
+        self.grant_cosigner.clone()
+    }
+
+    /// Get the longest grant the admin can make alone in days, if limited.
+    pub fn get_long_grant_threshold(&self) -> Option<u32> {
+        self.long_grant_threshold_days
+    }
+
+    /// Get the change of the co-signer or long grant threshold the co-signer approved, if
+    /// it wasn't made yet.
+    pub fn get_approved_quorum_change(&self) -> Option<AdminAction> {
+        self.approved_quorum_change.clone()
+    }
+
+    /// Get every long grant waiting for the co-signer, oldest first.
+    pub fn get_pending_grants(&self) -> Vec<PendingGrant> {
+        self.pending_grants.clone()
+    }
+
+    /// Panics if a license entry of the given kind expiring at `expiry` would leave a
+    /// longer term remaining than the admin can grant alone
+    pub(crate) fn assert_term_within_grant_threshold(&self, kind: ExpiryKind, expiry: u64) {
+        let term_ns = match kind {
+            ExpiryKind::Timestamp => expiry.saturating_sub(env::block_timestamp()),
+            ExpiryKind::Height => expiry.saturating_sub(env::block_height()).saturating_mul(BLOCK_TIME_NS),
+            ExpiryKind::Epoch => expiry.saturating_sub(env::epoch_height()).saturating_mul(EPOCH_DURATION_NS),
+        };
+        self.assert_within_grant_threshold(term_ns);
+    }
+
+    /// Panics if a remaining term of `term_ns` is longer than the admin can grant alone
+    pub(crate) fn assert_within_grant_threshold(&self, term_ns: u64) {
+        if let Some(days) = self.long_grant_threshold_days {
+            if term_ns > (days as u64).saturating_mul(ONE_DAY_NS) {
+                env::panic_str(&format!(
+                    "Grants longer than {} days need the co-signer, use propose_long_grant",
+                    days
+                ));
+            }
+        }
+    }
+
+    /// Panics if a co-signer is set and didn't approve `action`, a change of the co-signer
+    /// or long grant threshold. The approval is used up; until a co-signer is set, the
+    /// admin makes such changes alone
+    pub(crate) fn assert_quorum_change_approved(&mut self, action: &AdminAction) {
+        if self.grant_cosigner.is_some() {
+            require!(
+                self.approved_quorum_change.take().as_ref() == Some(action),
+                "Changing the grant co-signer or long grant threshold needs the co-signer's approval, use approve_quorum_change"
+            );
+        }
+    }
+
+    /// Position of a pending grant in the queue
+    fn pending_grant_position(&self, grant_id: u64) -> usize {
+        self.pending_grants
+            .iter()
+            .position(|grant| grant.id == grant_id)
+            .unwrap_or_else(|| env::panic_str("No pending grant with this ID"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+
+    fn cosigner() -> AccountId {
+        "dao.near".parse().unwrap()
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_long_grant_threshold(Some(730));
+        contract.set_grant_cosigner(Some(cosigner()));
+        contract
+    }
+
+    #[test]
+    fn test_grants_up_to_threshold_need_no_cosigner() {
+        let mut contract = setup_contract();
+
+        contract.grant_license(user_str(), 730, None, None);
+
+        // A day later the license can be topped back up to the threshold
+        setup_context(&admin(), ONE_DAY_NS);
+        contract.grant_license_hours(user_str(), 24, None, None);
+        assert_eq!(contract.get_expiry(user_str()), Some(731 * ONE_DAY_NS));
+    }
+
+    #[test]
+    #[should_panic(expected = "Grants longer than 730 days need the co-signer, use propose_long_grant")]
+    fn test_stacked_grants_rejected_past_threshold() {
+        let mut contract = setup_contract();
+
+        contract.grant_license(user_str(), 400, None, None);
+        contract.grant_license(user_str(), 400, None, None);
+    }
+
+    #[test]
+    fn test_cosigner_approves_extension_past_threshold() {
+        let mut contract = setup_contract();
+        contract.grant_license(user_str(), 730, None, None);
+        let grant_id = contract.propose_long_grant(user_str(), 30, None);
+
+        setup_context(&cosigner(), 0);
+        contract.approve_long_grant(grant_id);
+        assert_eq!(contract.get_expiry(user_str()), Some(760 * ONE_DAY_NS));
+    }
+
+    #[test]
+    #[should_panic(expected = "Grants longer than 730 days need the co-signer, use propose_long_grant")]
+    fn test_long_grant_rejected_without_cosigner() {
+        let mut contract = setup_contract();
+
+        contract.grant_license(user_str(), 731, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Grants longer than 730 days need the co-signer, use propose_long_grant")]
+    fn test_long_epoch_grant_rejected_without_cosigner() {
+        let mut contract = setup_contract();
+
+        contract.grant_license_epochs(user_str(), 2 * 731, None, None);
+    }
+
+    #[test]
+    fn test_cosigner_approves_long_grant() {
+        let mut contract = setup_contract();
+        let grant_id = contract.propose_long_grant(user_str(), 3650, Some("Lifetime deal".to_string()));
+        assert!(!contract.is_licensed(user_str()));
+        assert_eq!(contract.get_pending_grants().len(), 1);
+
+        setup_context(&cosigner(), 0);
+        contract.approve_long_grant(grant_id);
+        assert_eq!(contract.get_expiry(user_str()), Some(3650 * ONE_DAY_NS));
+        assert!(contract.get_pending_grants().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only the grant co-signer can approve long grants")]
+    fn test_admin_cannot_approve_own_grant() {
+        let mut contract = setup_contract();
+        let grant_id = contract.propose_long_grant(user_str(), 3650, None);
+
+        contract.approve_long_grant(grant_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "No pending grant with this ID")]
+    fn test_cancelled_grant_cannot_be_approved() {
+        let mut contract = setup_contract();
+        let grant_id = contract.propose_long_grant(user_str(), 3650, None);
+
+        setup_context(&cosigner(), 0);
+        contract.cancel_long_grant(grant_id);
+        contract.approve_long_grant(grant_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only admin or grant co-signer can cancel long grants")]
+    fn test_cancel_unauthorized() {
+        let mut contract = setup_contract();
+        let grant_id = contract.propose_long_grant(user_str(), 3650, None);
+
+        setup_context(&user(), 0);
+        contract.cancel_long_grant(grant_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expiry timestamp overflow")]
+    fn test_overflowing_long_grant_rejected_at_proposal() {
+        let mut contract = setup_contract();
+
+        contract.propose_long_grant(user_str(), u32::MAX, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The admin can't also be the grant co-signer")]
+    fn test_admin_cannot_be_cosigner() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.set_grant_cosigner(Some(admin()));
+    }
+
+    #[test]
+    #[should_panic(expected = "The grant co-signer can't also be the admin")]
+    fn test_cosigner_cannot_become_admin() {
+        let mut contract = setup_contract();
+
+        contract.transfer_admin(cosigner());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Changing the grant co-signer or long grant threshold needs the co-signer's approval, use approve_quorum_change"
+    )]
+    fn test_admin_cannot_clear_cosigner_alone() {
+        let mut contract = setup_contract();
+
+        contract.set_grant_cosigner(None);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Changing the grant co-signer or long grant threshold needs the co-signer's approval, use approve_quorum_change"
+    )]
+    fn test_admin_cannot_lift_threshold_alone() {
+        let mut contract = setup_contract();
+
+        contract.set_long_grant_threshold(None);
+    }
+
+    #[test]
+    fn test_cosigner_approves_quorum_change() {
+        let mut contract = setup_contract();
+        let change = AdminAction::SetLongGrantThreshold { days: Some(3650) };
+
+        setup_context(&cosigner(), 0);
+        contract.approve_quorum_change(change.clone());
+        assert_eq!(contract.get_approved_quorum_change(), Some(change));
+
+        setup_context(&admin(), 0);
+        contract.set_long_grant_threshold(Some(3650));
+        assert_eq!(contract.get_long_grant_threshold(), Some(3650));
+        assert_eq!(contract.get_approved_quorum_change(), None);
+        contract.grant_license(user_str(), 3650, None, None);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Changing the grant co-signer or long grant threshold needs the co-signer's approval, use approve_quorum_change"
+    )]
+    fn test_quorum_approval_covers_only_that_change() {
+        let mut contract = setup_contract();
+
+        setup_context(&cosigner(), 0);
+        contract.approve_quorum_change(AdminAction::SetLongGrantThreshold { days: Some(1000) });
+
+        setup_context(&admin(), 0);
+        contract.set_long_grant_threshold(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only the grant co-signer can approve quorum changes")]
+    fn test_admin_cannot_approve_quorum_change() {
+        let mut contract = setup_contract();
+
+        contract.approve_quorum_change(AdminAction::SetGrantCosigner { account_id: None });
+    }
+}
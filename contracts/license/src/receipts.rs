@@ -0,0 +1,224 @@
+use near_sdk::{near, AccountId, env, require, NearToken};
+
+use crate::events::LicenseEvent;
+use crate::{AdminAction, LicenseContract, LicenseContractExt, Permission};
+
+/// Monetary operation a receipt records.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReceiptKind {
+    /// Fee charged for a license transfer
+    TransferFee,
+    /// Deposit attached beyond a fee and returned to the payer
+    Refund,
+    /// Collected fees paid out to the admin
+    FeeWithdrawal,
+    /// Price paid by the buyer of a resold license
+    Sale,
+    /// Resale price minus royalty paid out to the seller
+    SaleProceeds,
+    /// Rent paid by a renter, held until the term ends
+    RentalPayment,
+    /// Held rent paid out to the lender
+    RentalPayout,
+    /// Collected fees paid out to a beneficiary on schedule or claimed from the revenue split
+    Payout,
+    /// Collected fees sent to the treasury recovery account after a shutdown
+    TreasuryRecovery,
+    /// Storage deposit of a burned license returned to its depositor
+    StorageRefund,
+}
+
+impl ReceiptKind {
+    /// Whether the receipt's account paid the contract, as opposed to being paid by it
+    fn is_payment(self) -> bool {
+        matches!(self, ReceiptKind::TransferFee | ReceiptKind::Sale | ReceiptKind::RentalPayment)
+    }
+}
+
+/// Record of a single monetary operation, referenced by its sequential ID.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Receipt {
+    pub id: u64,
+    pub kind: ReceiptKind,
+    /// Account that paid (for fees) or was paid (for refunds and withdrawals)
+    pub account: AccountId,
+    pub amount: NearToken,
+    /// Timestamp of the operation in nanoseconds
+    pub timestamp: u64,
+}
+
+#[near]
+impl LicenseContract {
+    /// Get a receipt by its ID.
+    pub fn get_receipt(&self, id: u64) -> Option<Receipt> {
+        u32::try_from(id).ok().and_then(|index| self.receipts.get(index)).cloned()
+    }
+
+    /// Check that a receipt records a payment made by `expected_wallet` that hasn't
+    /// been redeemed yet. Systems granting perks for purchases check this, then
+    /// `redeem_receipt`, rather than trusting a `receipt_issued` event that could be replayed.
+    ///
+    /// # Arguments
+    /// * `receipt_id` - The receipt to check
+    /// * `expected_wallet` - The account the payment should have come from
+    pub fn verify_receipt(&self, receipt_id: u64, expected_wallet: AccountId) -> bool {
+        self.get_receipt(receipt_id).is_some_and(|receipt| {
+            receipt.kind.is_payment()
+                && receipt.account == expected_wallet
+                && !self.redeemed_receipts.contains(&receipt_id)
+        })
+    }
+
+    /// Redeem a payment receipt, e.g. when fulfilling the perk it paid for, so it
+    /// can't be redeemed again. By default the admin and the receipt redeemer may
+    /// redeem receipts, see `set_receipt_redeemer`.
+    ///
+    /// # Arguments
+    /// * `receipt_id` - The receipt to redeem
+    /// * `expected_wallet` - The account the payment should have come from
+    ///
+    /// # Panics
+    /// Panics if caller may not redeem receipts, or the receipt doesn't exist, isn't a
+    /// payment, wasn't paid by `expected_wallet` or was already redeemed
+    pub fn redeem_receipt(&mut self, receipt_id: u64, expected_wallet: AccountId) {
+        self.assert_permitted(Permission::RedeemReceipts);
+        let receipt = self
+            .get_receipt(receipt_id)
+            .unwrap_or_else(|| env::panic_str("Unknown receipt"));
+        require!(receipt.kind.is_payment(), "Receipt is not a payment");
+        require!(receipt.account == expected_wallet, "Receipt was paid by another account");
+        require!(self.redeemed_receipts.insert(receipt_id), "Receipt already redeemed");
+
+        LicenseEvent::ReceiptRedeemed {
+            receipt_id,
+            redeemed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    /// Set (or clear) the fulfillment account allowed to redeem receipts besides the
+    /// admin, e.g. the backend or contract granting perks for purchases.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or an action delay is set
+    pub fn set_receipt_redeemer(&mut self, account_id: Option<AccountId>) {
+        self.submit_action(AdminAction::SetReceiptRedeemer { account_id });
+    }
+
+    /// Get the current receipt redeemer account, if any.
+    pub fn get_receipt_redeemer(&self) -> Option<AccountId> {
+        self.receipt_redeemer.clone()
+    }
+
+    /// Get the number of receipts issued, i.e. the ID the next receipt will get.
+    pub fn get_receipt_count(&self) -> u64 {
+        self.receipts.len() as u64
+    }
+
+    /// Record a monetary operation under the next receipt ID and emit its event
+    pub(crate) fn issue_receipt(&mut self, kind: ReceiptKind, account: AccountId, amount: NearToken) -> u64 {
+        let id = self.receipts.len() as u64;
+        self.receipts.push(Receipt {
+            id,
+            kind,
+            account: account.clone(),
+            amount,
+            timestamp: env::block_timestamp(),
+        });
+
+        LicenseEvent::ReceiptIssued {
+            receipt_id: id,
+            kind,
+            account,
+            amount,
+        }
+        .emit();
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    /// Contract where the user paid a transfer fee (receipt 0) and got a refund (receipt 1)
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+        contract.set_transfer_config(0, NearToken::from_millinear(100));
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(user())
+            .attached_deposit(NearToken::from_millinear(150))
+            .build();
+        testing_env!(context);
+        contract.transfer_license("other.near".to_string());
+        setup_context(&admin(), 0);
+        contract
+    }
+
+    #[test]
+    fn test_receipt_redeemed_once() {
+        let mut contract = setup_contract();
+        assert!(contract.verify_receipt(0, user()));
+
+        contract.redeem_receipt(0, user());
+        assert!(!contract.verify_receipt(0, user()));
+    }
+
+    #[test]
+    fn test_verify_receipt_checks_payer_and_kind() {
+        let contract = setup_contract();
+
+        assert!(!contract.verify_receipt(0, admin()));
+        // Refunds were paid by the contract, not to it
+        assert!(!contract.verify_receipt(1, user()));
+        assert!(!contract.verify_receipt(2, user()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Receipt already redeemed")]
+    fn test_replayed_receipt_rejected() {
+        let mut contract = setup_contract();
+        contract.redeem_receipt(0, user());
+
+        contract.redeem_receipt(0, user());
+    }
+
+    #[test]
+    #[should_panic(expected = "Receipt was paid by another account")]
+    fn test_redeem_for_wrong_wallet_rejected() {
+        let mut contract = setup_contract();
+
+        contract.redeem_receipt(0, admin());
+    }
+
+    #[test]
+    fn test_receipt_redeemer_redeems() {
+        let mut contract = setup_contract();
+        let redeemer: AccountId = "fulfillment.near".parse().unwrap();
+        contract.set_receipt_redeemer(Some(redeemer.clone()));
+        assert_eq!(contract.get_receipt_redeemer(), Some(redeemer.clone()));
+
+        setup_context(&redeemer, 0);
+        contract.redeem_receipt(0, user());
+        assert!(!contract.verify_receipt(0, user()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only admin or receipt redeemer can redeem receipts")]
+    fn test_redeem_unauthorized() {
+        let mut contract = setup_contract();
+
+        setup_context(&user(), 0);
+        contract.redeem_receipt(0, user());
+    }
+}
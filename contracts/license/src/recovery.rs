@@ -0,0 +1,493 @@
+use near_sdk::{near, AccountId, env, require};
+
+use crate::events::LicenseEvent;
+use crate::transfer::TransferKind;
+use crate::{AdminAction, GasClass, LicenseContract, LicenseContractExt};
+
+/// Default delay between proposing and executing a recovery
+pub(crate) const DEFAULT_RECOVERY_TIMELOCK_DAYS: u32 = 7;
+/// Maximum number of guardians per wallet
+const MAX_GUARDIANS: usize = 7;
+/// Minimum number of guardian approvals a recovery needs
+const MIN_GUARDIAN_THRESHOLD: u8 = 2;
+
+/// Guardians registered by a licensee for social recovery.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GuardianConfig {
+    pub guardians: Vec<AccountId>,
+    /// Number of guardian approvals required to recover the license
+    pub threshold: u8,
+}
+
+/// A pending request to re-point a license to a new wallet.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecoveryRequest {
+    /// Wallet address (or hash, in privacy mode) the license will move to
+    pub new_wallet: String,
+    /// Guardians who have approved the request so far
+    pub approvals: Vec<AccountId>,
+    /// Timestamp the request was proposed at, in nanoseconds
+    pub proposed_at: u64,
+}
+
+#[near]
+impl LicenseContract {
+    /// Register the guardians who may jointly recover the caller's license.
+    /// Only NEAR accounts can register guardians, since the caller is the wallet.
+    /// Replacing guardians cancels any pending recovery.
+    /// At least two guardians must approve a recovery, so no single guardian can
+    /// take the license.
+    ///
+    /// # Arguments
+    /// * `guardians` - Guardian accounts (2 to 7, excluding the caller)
+    /// * `threshold` - Number of guardian approvals required to recover, at least 2
+    ///
+    /// # Panics
+    /// Panics if the guardian list or threshold is invalid
+    pub fn set_recovery_guardians(&mut self, guardians: Vec<AccountId>, threshold: u8) {
+        self.assert_not_shut_down();
+        let caller = env::predecessor_account_id();
+        let mut guardians = guardians;
+        guardians.sort();
+        guardians.dedup();
+        require!(
+            guardians.len() >= MIN_GUARDIAN_THRESHOLD as usize && guardians.len() <= MAX_GUARDIANS,
+            "Between 2 and 7 guardians are required"
+        );
+        require!(!guardians.contains(&caller), "A wallet can't be its own guardian");
+        require!(
+            threshold >= MIN_GUARDIAN_THRESHOLD && threshold as usize <= guardians.len(),
+            "Threshold must be between 2 and the number of guardians"
+        );
+
+        let key = self.wallet_key(caller.as_str());
+        self.recovery_requests.remove(&key);
+        self.recovery_guardians.insert(key, GuardianConfig { guardians, threshold });
+    }
+
+    /// Remove the caller's guardians, cancelling any pending recovery.
+    pub fn clear_recovery_guardians(&mut self) {
+        self.assert_not_shut_down();
+        let key = self.wallet_key(env::predecessor_account_id().as_str());
+        self.recovery_requests.remove(&key);
+        self.recovery_guardians.remove(&key);
+    }
+
+    /// Propose moving a wallet's license to a new wallet. Counts as the proposer's approval.
+    /// A pending request gets one timelock period to reach the approval threshold; only
+    /// after that may another proposal replace it, so a single guardian can't reset the
+    /// others' approvals.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The lost wallet
+    /// * `new_wallet` - The wallet the license should move to
+    ///
+    /// # Panics
+    /// Panics if caller is not a guardian of the wallet, the new wallet is one of its
+    /// guardians, or a request is pending that reached the threshold or is still within
+    /// its timelock period
+    pub fn propose_recovery(&mut self, wallet_address: String, new_wallet: String) {
+        self.assert_not_shut_down();
+        let key = self.wallet_key(&wallet_address);
+        let guardian = self.assert_guardian(&key);
+        let new_wallet = self.wallet_key(&new_wallet);
+        require!(new_wallet != key, "New wallet must differ from the recovered wallet");
+        require!(
+            !self.recovery_guardians[&key]
+                .guardians
+                .iter()
+                .any(|guardian| self.wallet_key(guardian.as_str()) == new_wallet),
+            "A guardian can't recover a license to its own wallet"
+        );
+        if let Some(pending) = self.recovery_requests.get(&key) {
+            let threshold = self.recovery_guardians.get(&key).map_or(0, |config| config.threshold);
+            require!(
+                pending.approvals.len() < threshold as usize
+                    && env::block_timestamp() >= pending.proposed_at + self.recovery_timelock_ns,
+                "A recovery request is already pending"
+            );
+        }
+
+        LicenseEvent::RecoveryProposed {
+            wallet_address: key.clone(),
+            new_wallet: new_wallet.clone(),
+            guardian: guardian.clone(),
+        }
+        .emit();
+
+        self.recovery_requests.insert(
+            key,
+            RecoveryRequest {
+                new_wallet,
+                approvals: vec![guardian],
+                proposed_at: env::block_timestamp(),
+            },
+        );
+    }
+
+    /// Approve a pending recovery. The new wallet must match the pending request,
+    /// so an approval can't be redirected by a competing proposal.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The lost wallet
+    /// * `new_wallet` - The wallet the license should move to
+    ///
+    /// # Panics
+    /// Panics if caller is not a guardian, there is no matching request, or caller already approved
+    pub fn approve_recovery(&mut self, wallet_address: String, new_wallet: String) {
+        self.assert_not_shut_down();
+        let key = self.wallet_key(&wallet_address);
+        let guardian = self.assert_guardian(&key);
+        let new_wallet = self.wallet_key(&new_wallet);
+
+        let request = self
+            .recovery_requests
+            .get_mut(&key)
+            .filter(|request| request.new_wallet == new_wallet)
+            .unwrap_or_else(|| env::panic_str("No matching recovery request"));
+        require!(!request.approvals.contains(&guardian), "Guardian already approved");
+        request.approvals.push(guardian.clone());
+
+        LicenseEvent::RecoveryApproved {
+            wallet_address: key,
+            new_wallet,
+            guardian,
+        }
+        .emit();
+    }
+
+    /// Execute an approved recovery once the timelock has passed, moving the license
+    /// and the guardian configuration to the new wallet. A running rental moves along,
+    /// so the license stays lent out until its term ends.
+    ///
+    /// Recovery is held to the transfer freeze and cooldown like any transfer, and starts
+    /// the new wallet's cooldown. It's exempt from the transfer fee: the license goes back
+    /// to the holder who lost their wallet rather than to someone else, and the holder
+    /// can't pay from a wallet they no longer control.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The lost wallet
+    ///
+    /// # Panics
+    /// Panics if caller is not a guardian, the request lacks approvals, the timelock or
+    /// transfer cooldown hasn't passed, or transfers are frozen
+    pub fn execute_recovery(&mut self, wallet_address: String) {
+        self.assert_not_shut_down();
+        self.assert_transfers_not_frozen();
+        let key = self.wallet_key(&wallet_address);
+        self.assert_guardian(&key);
+
+        let config = self.recovery_guardians.get(&key).cloned().unwrap();
+        let request = self
+            .recovery_requests
+            .get(&key)
+            .cloned()
+            .unwrap_or_else(|| env::panic_str("No pending recovery request"));
+        require!(
+            request.approvals.len() >= config.threshold as usize,
+            "Recovery has not reached the approval threshold"
+        );
+        require!(
+            env::block_timestamp() >= request.proposed_at + self.recovery_timelock_ns,
+            "Recovery timelock has not passed"
+        );
+        self.assert_cooldown_passed(&key);
+
+        self.hand_over_license(&key, &request.new_wallet, TransferKind::Recovery);
+        self.recovery_guardians.insert(request.new_wallet.clone(), config);
+
+        LicenseEvent::RecoveryExecuted {
+            wallet_address: key,
+            new_wallet: request.new_wallet,
+        }
+        .emit();
+        self.metrics.recoveries += 1;
+        self.record_gas(GasClass::Recovery);
+    }
+
+    /// Cancel a pending recovery of the caller's own license, e.g. when guardians
+    /// act against a wallet that isn't actually lost.
+    ///
+    /// # Panics
+    /// Panics if there is no pending recovery for the caller
+    pub fn cancel_recovery(&mut self) {
+        self.assert_not_shut_down();
+        let key = self.wallet_key(env::predecessor_account_id().as_str());
+        require!(
+            self.recovery_requests.remove(&key).is_some(),
+            "No pending recovery request"
+        );
+
+        LicenseEvent::RecoveryCancelled { wallet_address: key }.emit();
+    }
+
+    /// Set the delay between proposing and executing a recovery.
+    ///
+    /// # Arguments
+    /// * `days` - Timelock length in days
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or an action delay is set
+    pub fn set_recovery_timelock(&mut self, days: u32) {
+        self.submit_action(AdminAction::SetRecoveryTimelock { days });
+    }
+
+    /// Get the guardian configuration of a wallet, if any.
+    pub fn get_recovery_guardians(&self, wallet_address: String) -> Option<GuardianConfig> {
+        self.recovery_guardians.get(&self.wallet_key(&wallet_address)).cloned()
+    }
+
+    /// Get the pending recovery request of a wallet, if any.
+    pub fn get_recovery_request(&self, wallet_address: String) -> Option<RecoveryRequest> {
+        self.recovery_requests.get(&self.wallet_key(&wallet_address)).cloned()
+    }
+
+    /// Get the recovery timelock in nanoseconds.
+    pub fn get_recovery_timelock(&self) -> u64 {
+        self.recovery_timelock_ns
+    }
+
+    /// Panics unless the caller is a guardian of the wallet stored under `key`
+    fn assert_guardian(&self, key: &str) -> AccountId {
+        let caller = env::predecessor_account_id();
+        require!(
+            self.recovery_guardians
+                .get(key)
+                .is_some_and(|config| config.guardians.contains(&caller)),
+            "Unauthorized: only a registered guardian can recover this license"
+        );
+        caller
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::{Network, ONE_DAY_NS};
+    use near_sdk::NearToken;
+
+    fn guardian(i: u8) -> AccountId {
+        format!("guardian{}.near", i).parse().unwrap()
+    }
+
+    fn new_wallet() -> String {
+        "new-user.near".to_string()
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+
+        setup_context(&user(), 0);
+        contract.set_recovery_guardians(vec![guardian(1), guardian(2), guardian(3)], 2);
+        contract
+    }
+
+    #[test]
+    fn test_recovery_moves_license_after_timelock() {
+        let mut contract = setup_contract();
+        let expiry = contract.get_expiry(user_str()).unwrap();
+
+        setup_context(&guardian(1), 0);
+        contract.propose_recovery(user_str(), new_wallet());
+        setup_context(&guardian(2), ONE_DAY_NS);
+        contract.approve_recovery(user_str(), new_wallet());
+
+        setup_context(&guardian(3), 7 * ONE_DAY_NS);
+        contract.execute_recovery(user_str());
+
+        assert_eq!(contract.get_expiry(user_str()), None);
+        assert_eq!(contract.get_expiry(new_wallet()), Some(expiry));
+        assert!(contract.get_recovery_request(user_str()).is_none());
+        assert_eq!(contract.get_recovery_guardians(new_wallet()).unwrap().threshold, 2);
+        assert_eq!(contract.get_transfer_history(new_wallet())[0].kind, TransferKind::Recovery);
+    }
+
+    #[test]
+    fn test_recovery_starts_transfer_cooldown() {
+        let mut contract = setup_contract();
+        setup_context(&admin(), 0);
+        contract.set_transfer_config(30, NearToken::from_near(1));
+
+        setup_context(&guardian(1), 0);
+        contract.propose_recovery(user_str(), new_wallet());
+        setup_context(&guardian(2), 7 * ONE_DAY_NS);
+        contract.approve_recovery(user_str(), new_wallet());
+        contract.execute_recovery(user_str());
+
+        // No fee is taken, and the new wallet's cooldown starts
+        assert_eq!(contract.get_collected_fees(), NearToken::from_yoctonear(0));
+        assert_eq!(contract.get_last_transfer(new_wallet()), Some(7 * ONE_DAY_NS));
+        assert_eq!(contract.get_last_transfer(user_str()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "License transfer cooldown has not passed")]
+    fn test_recovery_respects_transfer_cooldown() {
+        let mut contract = setup_contract();
+        setup_context(&admin(), 0);
+        contract.set_transfer_config(30, NearToken::from_yoctonear(0));
+        contract.last_transfers.insert(user_str(), 0);
+
+        setup_context(&guardian(1), 0);
+        contract.propose_recovery(user_str(), new_wallet());
+        setup_context(&guardian(2), 7 * ONE_DAY_NS);
+        contract.approve_recovery(user_str(), new_wallet());
+        contract.execute_recovery(user_str());
+    }
+
+    #[test]
+    #[should_panic(expected = "License transfers are frozen")]
+    fn test_recovery_respects_transfer_freeze() {
+        let mut contract = setup_contract();
+
+        setup_context(&guardian(1), 0);
+        contract.propose_recovery(user_str(), new_wallet());
+        setup_context(&guardian(2), 0);
+        contract.approve_recovery(user_str(), new_wallet());
+        setup_context(&admin(), 7 * ONE_DAY_NS);
+        contract.freeze_transfers();
+
+        setup_context(&guardian(1), 7 * ONE_DAY_NS);
+        contract.execute_recovery(user_str());
+    }
+
+    #[test]
+    #[should_panic(expected = "Between 2 and 7 guardians are required")]
+    fn test_single_guardian_rejected() {
+        setup_context(&user(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_recovery_guardians(vec![guardian(1)], 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Threshold must be between 2 and the number of guardians")]
+    fn test_single_approval_threshold_rejected() {
+        setup_context(&user(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_recovery_guardians(vec![guardian(1), guardian(2)], 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "A guardian can't recover a license to its own wallet")]
+    fn test_guardian_cannot_recover_to_itself() {
+        let mut contract = setup_contract();
+
+        setup_context(&guardian(1), 0);
+        contract.propose_recovery(user_str(), guardian(2).to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "Recovery timelock has not passed")]
+    fn test_recovery_respects_timelock() {
+        let mut contract = setup_contract();
+
+        setup_context(&guardian(1), 0);
+        contract.propose_recovery(user_str(), new_wallet());
+        setup_context(&guardian(2), 0);
+        contract.approve_recovery(user_str(), new_wallet());
+
+        contract.execute_recovery(user_str());
+    }
+
+    #[test]
+    #[should_panic(expected = "Recovery has not reached the approval threshold")]
+    fn test_recovery_requires_threshold() {
+        let mut contract = setup_contract();
+
+        setup_context(&guardian(1), 0);
+        contract.propose_recovery(user_str(), new_wallet());
+
+        setup_context(&guardian(1), 7 * ONE_DAY_NS);
+        contract.execute_recovery(user_str());
+    }
+
+    #[test]
+    #[should_panic(expected = "No matching recovery request")]
+    fn test_approval_must_match_new_wallet() {
+        let mut contract = setup_contract();
+
+        setup_context(&guardian(1), 0);
+        contract.propose_recovery(user_str(), new_wallet());
+        setup_context(&guardian(2), 0);
+        contract.approve_recovery(user_str(), "attacker.near".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "A recovery request is already pending")]
+    fn test_pending_recovery_cannot_be_replaced() {
+        let mut contract = setup_contract();
+
+        setup_context(&guardian(1), 0);
+        contract.propose_recovery(user_str(), new_wallet());
+        setup_context(&guardian(2), 0);
+        contract.approve_recovery(user_str(), new_wallet());
+
+        setup_context(&guardian(3), 8 * ONE_DAY_NS);
+        contract.propose_recovery(user_str(), "attacker.near".to_string());
+    }
+
+    #[test]
+    fn test_stale_recovery_can_be_replaced() {
+        let mut contract = setup_contract();
+
+        setup_context(&guardian(1), 0);
+        contract.propose_recovery(user_str(), "attacker.near".to_string());
+
+        setup_context(&guardian(2), 7 * ONE_DAY_NS);
+        contract.propose_recovery(user_str(), new_wallet());
+        let request = contract.get_recovery_request(user_str()).unwrap();
+        assert_eq!(request.new_wallet, new_wallet());
+        assert_eq!(request.approvals, vec![guardian(2)]);
+    }
+
+    #[test]
+    fn test_recovery_moves_running_rental() {
+        let mut contract = setup_contract();
+        let renter: AccountId = "renter.near".parse().unwrap();
+        contract.offer_rental(NearToken::from_yoctonear(0), 10);
+        setup_context(&renter, 0);
+        contract.rent_license(user_str(), 10);
+
+        setup_context(&guardian(1), 0);
+        contract.propose_recovery(user_str(), new_wallet());
+        setup_context(&guardian(2), 0);
+        contract.approve_recovery(user_str(), new_wallet());
+        setup_context(&guardian(1), 7 * ONE_DAY_NS);
+        contract.execute_recovery(user_str());
+
+        // Only the renter is licensed until the term ends
+        assert!(contract.is_licensed(renter.to_string()));
+        assert!(!contract.is_licensed(new_wallet()));
+        assert_eq!(contract.get_rental(renter.to_string()), contract.get_rental(new_wallet()));
+
+        setup_context(&guardian(1), 10 * ONE_DAY_NS);
+        assert!(!contract.is_licensed(renter.to_string()));
+        assert!(contract.is_licensed(new_wallet()));
+    }
+
+    #[test]
+    fn test_owner_can_cancel_recovery() {
+        let mut contract = setup_contract();
+
+        setup_context(&guardian(1), 0);
+        contract.propose_recovery(user_str(), new_wallet());
+
+        setup_context(&user(), 0);
+        contract.cancel_recovery();
+        assert!(contract.get_recovery_request(user_str()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only a registered guardian can recover this license")]
+    fn test_non_guardian_cannot_propose() {
+        let mut contract = setup_contract();
+
+        setup_context(&admin(), 0);
+        contract.propose_recovery(user_str(), new_wallet());
+    }
+}
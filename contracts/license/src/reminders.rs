@@ -0,0 +1,218 @@
+use near_sdk::{near, AccountId, env, require};
+
+use crate::events::LicenseEvent;
+use crate::jobs::batch_gas_exhausted;
+use crate::{AdminAction, JobKind, JobStatus, LicenseContract, LicenseContractExt, Permission, ONE_DAY_NS};
+
+/// Maximum number of indexed wallets scanned per `emit_expiry_reminders` call
+const MAX_REMINDER_SCAN: u32 = 200;
+
+/// A wallet's opt-in to renewal reminders.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReminderPreference {
+    /// Hex-encoded sha256 identifying where to route reminders (e.g. a hashed email
+    /// or push token reference); only the notifier can resolve it
+    pub preference_hash: String,
+    /// Expiry the last reminder was emitted for, so each expiry is reminded once
+    pub last_reminded_expiry: Option<u64>,
+}
+
+#[near]
+impl LicenseContract {
+    /// Opt the caller's wallet in to (or out of) renewal reminders.
+    ///
+    /// # Arguments
+    /// * `preference_hash` - Hex-encoded sha256 routing reference, or `None` to opt out
+    ///
+    /// # Panics
+    /// Panics if the hash isn't 64 hex characters
+    pub fn set_reminder_preference(&mut self, preference_hash: Option<String>) {
+        self.assert_not_shut_down();
+        let key = self.wallet_key(env::predecessor_account_id().as_str());
+
+        match preference_hash {
+            Some(hash) => {
+                require!(
+                    hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()),
+                    "Preference hash must be 64 hex characters"
+                );
+                self.reminder_preferences.insert(
+                    key,
+                    ReminderPreference {
+                        preference_hash: hash.to_ascii_lowercase(),
+                        last_reminded_expiry: None,
+                    },
+                );
+            }
+            None => {
+                self.reminder_preferences.remove(&key);
+            }
+        }
+    }
+
+    /// Set (or clear) the keeper account allowed to emit reminders besides the admin.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or an action delay is set
+    pub fn set_reminder_keeper(&mut self, account_id: Option<AccountId>) {
+        self.submit_action(AdminAction::SetReminderKeeper { account_id });
+    }
+
+    /// Emit a `license_expiring_soon` event for every opted-in wallet in the next page of
+    /// the wallet index whose timestamp license expires within `within_days`. Each expiry is
+    /// reminded at most once; extending the license re-arms the reminder.
+    ///
+    /// Runs as the `expiry_reminders` job: each call resumes where the previous one
+    /// stopped, and the run finishes once the end of the index is reached. A page
+    /// stops early if gas runs low.
+    ///
+    /// # Arguments
+    /// * `limit` - Number of wallets to scan (capped at 200)
+    /// * `within_days` - Reminder window before expiry
+    ///
+    /// # Returns
+    /// The job status after this page
+    ///
+    /// # Panics
+    /// Panics if caller may not emit reminders, or another account is running the scan
+    pub fn emit_expiry_reminders(&mut self, limit: u32, within_days: u32) -> JobStatus {
+        self.assert_permitted(Permission::EmitReminders);
+
+        let mut job = self.begin_batch(JobKind::ExpiryReminders);
+        let now = env::block_timestamp();
+        let deadline = now.saturating_add(within_days as u64 * ONE_DAY_NS);
+        let start = job.cursor as u32;
+        let page_end = start
+            .saturating_add(limit.min(MAX_REMINDER_SCAN))
+            .min(self.wallets.len());
+
+        let mut end = start;
+        while end < page_end && !batch_gas_exhausted() {
+            let key = self.wallets.get(end).unwrap().clone();
+            end += 1;
+            let Some(expiry) = self.licenses.get(&key).copied() else {
+                continue;
+            };
+            if expiry <= now || expiry > deadline {
+                continue;
+            }
+            let Some(preference) = self.reminder_preferences.get_mut(&key) else {
+                continue;
+            };
+            if preference.last_reminded_expiry == Some(expiry) {
+                continue;
+            }
+
+            preference.last_reminded_expiry = Some(expiry);
+            LicenseEvent::LicenseExpiringSoon {
+                wallet_address: key,
+                expiry,
+                preference_hash: preference.preference_hash.clone(),
+            }
+            .emit();
+            job.applied += 1;
+        }
+
+        job.cursor = end as u64;
+        job.processed += (end - start) as u64;
+        if end == self.wallets.len() {
+            job.finished_at = Some(now);
+        }
+        self.end_batch(job)
+    }
+
+    /// Get a wallet's reminder preference, if it opted in.
+    pub fn get_reminder_preference(&self, wallet_address: String) -> Option<ReminderPreference> {
+        self.reminder_preferences.get(&self.wallet_key(&wallet_address)).cloned()
+    }
+
+    /// Get the current reminder keeper account, if any.
+    pub fn get_reminder_keeper(&self) -> Option<AccountId> {
+        self.reminder_keeper.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+    use near_sdk::test_utils::get_logs;
+
+    fn hash() -> String {
+        "ab".repeat(32)
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 10, None, None);
+        contract.grant_license("other.near".to_string(), 10, None, None);
+
+        setup_context(&user(), 0);
+        contract.set_reminder_preference(Some(hash()));
+        contract
+    }
+
+    #[test]
+    fn test_reminders_for_opted_in_wallets() {
+        let mut contract = setup_contract();
+
+        // Outside the window nothing is emitted
+        setup_context(&admin(), 0);
+        assert_eq!(contract.emit_expiry_reminders(10, 7).applied, 0);
+
+        setup_context(&admin(), 5 * ONE_DAY_NS);
+        let job = contract.emit_expiry_reminders(10, 7);
+        assert_eq!((job.applied, job.processed), (1, 2));
+        assert!(!job.is_running());
+        assert!(get_logs()[0].contains(r#""event":"license_expiring_soon""#));
+        assert!(get_logs()[0].contains(&hash()));
+
+        // Each expiry is reminded once, until the license is extended
+        assert_eq!(contract.emit_expiry_reminders(10, 7).applied, 0);
+        contract.grant_license(user_str(), 1, None, None);
+        assert_eq!(contract.emit_expiry_reminders(10, 7).applied, 1);
+    }
+
+    #[test]
+    fn test_reminder_scan_paginates() {
+        let mut contract = setup_contract();
+
+        setup_context(&admin(), 5 * ONE_DAY_NS);
+        let job = contract.emit_expiry_reminders(1, 7);
+        assert_eq!((job.cursor, job.applied), (1, 1));
+        assert!(job.is_running());
+
+        let job = contract.emit_expiry_reminders(1, 7);
+        assert_eq!((job.cursor, job.applied), (2, 1));
+        assert!(!job.is_running());
+    }
+
+    #[test]
+    fn test_opt_out() {
+        let mut contract = setup_contract();
+        contract.set_reminder_preference(None);
+        assert_eq!(contract.get_reminder_preference(user_str()), None);
+
+        setup_context(&admin(), 5 * ONE_DAY_NS);
+        assert_eq!(contract.emit_expiry_reminders(10, 7).applied, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only admin or reminder keeper can emit reminders")]
+    fn test_emit_reminders_unauthorized() {
+        let mut contract = setup_contract();
+
+        contract.emit_expiry_reminders(10, 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "Preference hash must be 64 hex characters")]
+    fn test_invalid_preference_hash() {
+        let mut contract = setup_contract();
+
+        contract.set_reminder_preference(Some("alice@example.com".to_string()));
+    }
+}
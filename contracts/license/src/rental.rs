@@ -0,0 +1,310 @@
+use near_sdk::{near, AccountId, env, require, NearToken, Promise};
+
+use crate::events::LicenseEvent;
+use crate::{LicenseContract, LicenseContractExt, ReceiptKind, ONE_DAY_NS};
+
+/// Terms on which a holder offers to lend their license.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RentalOffer {
+    /// Account the rental payments are paid to
+    pub lender: AccountId,
+    pub price_per_day: NearToken,
+    /// Longest rental term accepted, in days
+    pub max_days: u32,
+}
+
+/// A license lent out for a fixed term. The lender's license is suspended and the
+/// renter's wallet licensed until `ends_at`, after which the license returns on its own.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rental {
+    pub lender: AccountId,
+    /// Wallet address (or hash, in privacy mode) of the renter
+    pub renter: String,
+    pub starts_at: u64,
+    pub ends_at: u64,
+    /// Payment held by the contract until the term ends
+    pub payment: NearToken,
+}
+
+#[near]
+impl LicenseContract {
+    /// Offer the caller's license for rent, replacing any previous offer.
+    /// The offer is dropped when the license changes hands.
+    ///
+    /// # Arguments
+    /// * `price_per_day` - Rent per day of the term
+    /// * `max_days` - Longest rental term accepted
+    ///
+    /// # Panics
+    /// Panics if the caller has no active license or `max_days` is zero
+    pub fn offer_rental(&mut self, price_per_day: NearToken, max_days: u32) {
+        self.assert_not_shut_down();
+        let lender = env::predecessor_account_id();
+        let key = self.wallet_key(lender.as_str());
+        require!(self.holds_license(&key), "No active license to lend");
+        require!(max_days > 0, "Rental term must be at least one day");
+
+        self.rental_offers.insert(
+            key,
+            RentalOffer {
+                lender,
+                price_per_day,
+                max_days,
+            },
+        );
+    }
+
+    /// Withdraw the caller's rental offer. Rentals already running are unaffected.
+    ///
+    /// # Panics
+    /// Panics if the caller has no rental offer
+    pub fn withdraw_rental_offer(&mut self) {
+        self.assert_not_shut_down();
+        let key = self.wallet_key(env::predecessor_account_id().as_str());
+        require!(self.rental_offers.remove(&key).is_some(), "No rental offer");
+    }
+
+    /// Rent a license on offer for a number of days. The payment is held by the
+    /// contract until the term ends; any deposit above it is refunded.
+    ///
+    /// # Arguments
+    /// * `lender_wallet` - The wallet address holding the offered license
+    /// * `days` - Length of the rental term
+    ///
+    /// # Panics
+    /// Panics if there's no offer, the term is out of bounds or outlasts the license,
+    /// the license is already lent out, the caller already has a license, or the
+    /// deposit doesn't cover the rent
+    #[payable]
+    pub fn rent_license(&mut self, lender_wallet: String, days: u32) {
+        self.assert_not_paused();
+        let renter = env::predecessor_account_id();
+        let lender_key = self.wallet_key(&lender_wallet);
+        let renter_key = self.wallet_key(renter.as_str());
+        let offer = self
+            .rental_offers
+            .get(&lender_key)
+            .cloned()
+            .unwrap_or_else(|| env::panic_str("License is not offered for rent"));
+
+        require!(
+            (1..=offer.max_days).contains(&days),
+            format!("Rental term must be between 1 and {} days", offer.max_days)
+        );
+        require!(self.holds_license(&lender_key), "License is already lent out");
+        require!(!self.is_key_licensed(&renter_key), "Wallet already has a license");
+        self.assert_eligible(&renter_key);
+
+        let now = env::block_timestamp();
+        let ends_at = now + days as u64 * ONE_DAY_NS;
+        require!(
+            self.licenses.get(&lender_key).is_some_and(|&expiry| expiry >= ends_at),
+            "Rental term outlasts the license"
+        );
+
+        let payment = offer.price_per_day.saturating_mul(days as u128);
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= payment,
+            format!("Rental requires a deposit of {} yoctoNEAR", payment.as_yoctonear())
+        );
+
+        // The previous term has ended, so its payment is due to the lender
+        self.settle_rental(&lender_key);
+        self.rentals.insert(
+            lender_key.clone(),
+            Rental {
+                lender: offer.lender,
+                renter: renter_key.clone(),
+                starts_at: now,
+                ends_at,
+                payment,
+            },
+        );
+        self.rented_from.insert(renter_key.clone(), lender_key.clone());
+
+        LicenseEvent::LicenseRented {
+            lender: lender_key,
+            renter: renter_key,
+            ends_at,
+            payment,
+        }
+        .emit();
+
+        self.issue_receipt(ReceiptKind::RentalPayment, renter.clone(), payment);
+        let refund = deposit.saturating_sub(payment);
+        if !refund.is_zero() {
+            self.issue_receipt(ReceiptKind::Refund, renter.clone(), refund);
+            Promise::new(renter).transfer(refund).detach();
+        }
+    }
+
+    /// Pay out the held payment of the caller's rental once its term has ended.
+    ///
+    /// # Panics
+    /// Panics if the caller's license has no rental or its term hasn't ended
+    pub fn claim_rental_payment(&mut self) {
+        let key = self.wallet_key(env::predecessor_account_id().as_str());
+        let rental = self
+            .rentals
+            .get(&key)
+            .unwrap_or_else(|| env::panic_str("No rental to claim"));
+        require!(env::block_timestamp() >= rental.ends_at, "Rental term has not ended");
+
+        self.settle_rental(&key);
+    }
+
+    /// Get the rental offer of a wallet's license, if any.
+    pub fn get_rental_offer(&self, wallet_address: String) -> Option<RentalOffer> {
+        self.rental_offers.get(&self.wallet_key(&wallet_address)).cloned()
+    }
+
+    /// Get the current or last unsettled rental of a wallet, as lender or renter.
+    pub fn get_rental(&self, wallet_address: String) -> Option<Rental> {
+        let key = self.wallet_key(&wallet_address);
+        let lender_key = self.rented_from.get(&key).unwrap_or(&key);
+        self.rentals
+            .get(lender_key)
+            .filter(|rental| rental.renter == key || lender_key == &key)
+            .cloned()
+    }
+
+    /// Whether the license stored under `key` is lent out right now
+    pub(crate) fn is_lent_out(&self, key: &str) -> bool {
+        self.rentals
+            .get(key)
+            .is_some_and(|rental| rental.ends_at > env::block_timestamp())
+    }
+
+    /// Whether the wallet stored under `key` is renting a license right now
+    pub(crate) fn is_renting(&self, key: &str) -> bool {
+        self.rented_from.get(key).is_some_and(|lender_key| {
+            self.rentals
+                .get(lender_key)
+                .is_some_and(|rental| rental.renter == key && rental.ends_at > env::block_timestamp())
+        })
+    }
+
+    /// Pay out and remove the ended rental of the license stored under `key`, if any
+    pub(crate) fn settle_rental(&mut self, key: &str) {
+        let Some(rental) = self.rentals.remove(key) else {
+            return;
+        };
+        if self.rented_from.get(&rental.renter).is_some_and(|lender_key| lender_key == key) {
+            self.rented_from.remove(&rental.renter);
+        }
+
+        LicenseEvent::RentalSettled {
+            lender: key.to_string(),
+            renter: rental.renter,
+            payment: rental.payment,
+        }
+        .emit();
+
+        if !rental.payment.is_zero() {
+            self.issue_receipt(ReceiptKind::RentalPayout, rental.lender.clone(), rental.payment);
+            Promise::new(rental.lender).transfer(rental.payment).detach();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    fn renter() -> AccountId {
+        "renter.near".parse().unwrap()
+    }
+
+    fn setup_payment_context(predecessor: &AccountId, block_timestamp: u64, deposit: NearToken) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(predecessor.clone())
+            .block_timestamp(block_timestamp)
+            .attached_deposit(deposit)
+            .build());
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+
+        setup_context(&user(), 0);
+        contract.offer_rental(NearToken::from_millinear(100), 7);
+        contract
+    }
+
+    #[test]
+    fn test_rental_lends_and_returns_license() {
+        let mut contract = setup_contract();
+
+        setup_payment_context(&renter(), 0, NearToken::from_near(1));
+        contract.rent_license(user_str(), 5);
+
+        assert!(contract.is_licensed(renter().to_string()));
+        assert!(!contract.is_licensed(user_str()));
+        assert_eq!(contract.get_rental(renter().to_string()).unwrap().payment, NearToken::from_millinear(500));
+        assert_eq!(contract.get_receipt(1).unwrap().amount, NearToken::from_millinear(500));
+
+        // The license returns once the term ends
+        setup_context(&user(), 5 * ONE_DAY_NS);
+        assert!(!contract.is_licensed(renter().to_string()));
+        assert!(contract.is_licensed(user_str()));
+
+        contract.claim_rental_payment();
+        assert_eq!(contract.get_rental(user_str()), None);
+        let payout = contract.get_receipt(2).unwrap();
+        assert_eq!(
+            (payout.kind, payout.account, payout.amount),
+            (ReceiptKind::RentalPayout, user(), NearToken::from_millinear(500))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "No active license to transfer")]
+    fn test_lent_license_cannot_be_transferred() {
+        let mut contract = setup_contract();
+        setup_payment_context(&renter(), 0, NearToken::from_near(1));
+        contract.rent_license(user_str(), 5);
+
+        setup_payment_context(&user(), ONE_DAY_NS, NearToken::from_yoctonear(0));
+        contract.transfer_license("other.near".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "License is already lent out")]
+    fn test_overlapping_rental_rejected() {
+        let mut contract = setup_contract();
+        setup_payment_context(&renter(), 0, NearToken::from_near(1));
+        contract.rent_license(user_str(), 5);
+
+        setup_payment_context(&"other.near".parse().unwrap(), ONE_DAY_NS, NearToken::from_near(1));
+        contract.rent_license(user_str(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Rental term outlasts the license")]
+    fn test_rental_cannot_outlast_license() {
+        let mut contract = setup_contract();
+
+        setup_payment_context(&renter(), 25 * ONE_DAY_NS, NearToken::from_near(1));
+        contract.rent_license(user_str(), 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "Rental term has not ended")]
+    fn test_claim_before_term_ends() {
+        let mut contract = setup_contract();
+        setup_payment_context(&renter(), 0, NearToken::from_near(1));
+        contract.rent_license(user_str(), 5);
+
+        setup_context(&user(), ONE_DAY_NS);
+        contract.claim_rental_payment();
+    }
+}
@@ -0,0 +1,190 @@
+use near_sdk::{near, AccountId, env};
+
+use crate::events::LicenseEvent;
+use crate::{AdminAction, LicenseContract, LicenseContractExt};
+
+/// Default number of entries returned by `get_admin_history`
+const DEFAULT_HISTORY_LIMIT: u32 = 50;
+
+/// Maximum number of entries returned by `get_admin_history`
+const MAX_HISTORY_LIMIT: u32 = 200;
+
+/// Privileged role on the license contract.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Admin,
+    ComplianceOfficer,
+    Pauser,
+    ReminderKeeper,
+    Guardian,
+    ShutdownAuthority,
+    GrantCosigner,
+    ReceiptRedeemer,
+    PayoutKeeper,
+}
+
+impl Role {
+    /// The role, as used in authorization errors
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::ComplianceOfficer => "compliance officer",
+            Role::Pauser => "pauser",
+            Role::ReminderKeeper => "reminder keeper",
+            Role::Guardian => "guardian",
+            Role::ShutdownAuthority => "shutdown authority",
+            Role::GrantCosigner => "grant co-signer",
+            Role::ReceiptRedeemer => "receipt redeemer",
+            Role::PayoutKeeper => "payout keeper",
+        }
+    }
+}
+
+/// A single change of the account holding a role.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoleChange {
+    pub role: Role,
+    /// New holder of the role, `None` if the role was removed
+    pub account: Option<AccountId>,
+    pub changed_by: AccountId,
+    /// Timestamp of the change in nanoseconds
+    pub changed_at: u64,
+}
+
+#[near]
+impl LicenseContract {
+    /// Hand the admin role to another account, e.g. to rotate the admin key.
+    /// The current admin loses every admin permission immediately.
+    ///
+    /// # Arguments
+    /// * `new_admin` - The account that will become admin
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, or `new_admin` already is the admin
+    pub fn transfer_admin(&mut self, new_admin: AccountId) {
+        self.submit_action(AdminAction::TransferAdmin { new_admin });
+    }
+
+    /// Get the current admin account.
+    pub fn get_admin(&self) -> AccountId {
+        self.admin.clone()
+    }
+
+    /// Get every change of the admin and other privileged roles, oldest first,
+    /// starting with the admin set at initialization.
+    ///
+    /// # Arguments
+    /// * `from_index` - Position to start from (default 0)
+    /// * `limit` - Maximum number of entries to return (default 50, capped at 200)
+    pub fn get_admin_history(&self, from_index: Option<u32>, limit: Option<u32>) -> Vec<RoleChange> {
+        let start = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT).min(MAX_HISTORY_LIMIT);
+
+        (start..self.role_history.len().min(start.saturating_add(limit)))
+            .filter_map(|index| self.role_history.get(index))
+            .cloned()
+            .collect()
+    }
+
+    /// Get the account currently holding a role, if any
+    pub(crate) fn role_holder(&self, role: Role) -> Option<AccountId> {
+        match role {
+            Role::Admin => Some(self.admin.clone()),
+            Role::ComplianceOfficer => self.compliance_officer.clone(),
+            Role::Pauser => self.pauser.clone(),
+            Role::ReminderKeeper => self.reminder_keeper.clone(),
+            Role::Guardian => self.guardian.clone(),
+            Role::ShutdownAuthority => self.shutdown_authority.clone(),
+            Role::GrantCosigner => self.grant_cosigner.clone(),
+            Role::ReceiptRedeemer => self.receipt_redeemer.clone(),
+            Role::PayoutKeeper => self.payout_keeper.clone(),
+        }
+    }
+
+    /// Append a role change made by the caller to the role history and emit its event
+    pub(crate) fn record_role_change(&mut self, role: Role, account: Option<AccountId>) {
+        let changed_by = env::predecessor_account_id();
+        self.role_history.push(RoleChange {
+            role,
+            account: account.clone(),
+            changed_by: changed_by.clone(),
+            changed_at: env::block_timestamp(),
+        });
+
+        LicenseEvent::RoleChanged { role, account, changed_by }.emit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+    use near_sdk::test_utils::get_logs;
+
+    fn new_admin() -> AccountId {
+        "new-admin.near".parse().unwrap()
+    }
+
+    #[test]
+    fn test_admin_rotation_recorded() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_pauser(Some(user()));
+
+        setup_context(&admin(), 5);
+        contract.transfer_admin(new_admin());
+        assert!(get_logs()[0].contains(r#""event":"role_changed""#));
+        assert_eq!(contract.get_admin(), new_admin());
+
+        setup_context(&new_admin(), 6);
+        contract.set_pauser(None);
+        contract.grant_license(user_str(), 30, None, None);
+
+        let history = contract.get_admin_history(None, None);
+        assert_eq!(history.len(), 4);
+        assert_eq!(
+            history[0],
+            RoleChange {
+                role: Role::Admin,
+                account: Some(admin()),
+                changed_by: admin(),
+                changed_at: 0,
+            }
+        );
+        assert_eq!(
+            history[2],
+            RoleChange {
+                role: Role::Admin,
+                account: Some(new_admin()),
+                changed_by: admin(),
+                changed_at: 5,
+            }
+        );
+        assert_eq!(history[3].account, None);
+        assert_eq!(contract.get_admin_history(Some(3), Some(10)), vec![history[3].clone()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only admin can perform this action")]
+    fn test_previous_admin_loses_access() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.transfer_admin(new_admin());
+
+        contract.set_pauser(Some(user()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only admin can perform this action")]
+    fn test_transfer_admin_unauthorized() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        setup_context(&user(), 0);
+        contract.transfer_admin(user());
+    }
+}
@@ -0,0 +1,143 @@
+use near_sdk::{near, require};
+
+use crate::events::LicenseEvent;
+use crate::{LicenseContract, LicenseContractExt, LicenseRecord};
+
+#[near]
+impl LicenseContract {
+    /// Get the serial number of a wallet's license, a stable identifier for support
+    /// tickets, invoices and watermarked builds. Serials are assigned at a license's
+    /// first grant, follow it through transfers and recoveries, and are retired when
+    /// it is burned.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet address to query
+    ///
+    /// # Returns
+    /// `Some(serial)` if the wallet holds a license entry, `None` otherwise
+    pub fn get_serial(&self, wallet_address: String) -> Option<u64> {
+        self.serials.get(&self.wallet_key(&wallet_address)).copied()
+    }
+
+    /// Resolve a serial number to the wallet holding the license and its record,
+    /// e.g. to trace a leaked serial to its holder.
+    ///
+    /// # Arguments
+    /// * `serial` - The serial number to look up
+    ///
+    /// # Returns
+    /// `Some(record)` if a license holds the serial, `None` if it was never
+    /// assigned or the license was burned
+    pub fn get_license_by_serial(&self, serial: u64) -> Option<LicenseRecord> {
+        self.serial_wallets.get(&serial).map(|key| self.license_record(key))
+    }
+
+    /// Give the license stored under `key` the next serial number, unless it has one
+    pub(crate) fn assign_serial(&mut self, key: &str) {
+        if !self.serials.contains_key(key) {
+            let serial = self.next_serial;
+            self.restore_serial(key, serial);
+        }
+    }
+
+    /// Store a serial number for the license under `key`, e.g. one exported by
+    /// another deployment, and emit its event
+    pub(crate) fn restore_serial(&mut self, key: &str, serial: u64) {
+        require!(
+            !self.serial_wallets.contains_key(&serial),
+            format!("Serial {} is already in use", serial)
+        );
+        self.serials.insert(key.to_string(), serial);
+        self.serial_wallets.insert(serial, key.to_string());
+        self.next_serial = self.next_serial.max(serial + 1);
+        LicenseEvent::SerialAssigned {
+            serial,
+            wallet_address: key.to_string(),
+        }
+        .emit();
+    }
+
+    /// Move the serial number of a license changing wallets
+    pub(crate) fn move_serial(&mut self, from_key: &str, to_key: &str) {
+        if let Some(serial) = self.serials.remove(from_key) {
+            self.serials.insert(to_key.to_string(), serial);
+            self.serial_wallets.insert(serial, to_key.to_string());
+        }
+    }
+
+    /// Retire the serial number of the license under `key`, e.g. when it is burned
+    pub(crate) fn retire_serial(&mut self, key: &str) {
+        if let Some(serial) = self.serials.remove(key) {
+            self.serial_wallets.remove(&serial);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::{LicenseContract, Network, ONE_DAY_NS};
+
+    #[test]
+    fn test_serial_assigned_at_first_grant() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        assert_eq!(contract.get_serial(user_str()), None);
+
+        contract.grant_license(user_str(), 30, None, None);
+        contract.grant_license_epochs("other.near".to_string(), 2, None, None);
+        // Extensions keep the serial
+        contract.grant_license(user_str(), 30, None, None);
+        assert_eq!(contract.get_serial(user_str()), Some(1));
+        assert_eq!(contract.get_serial("other.near".to_string()), Some(2));
+    }
+
+    #[test]
+    fn test_license_by_serial() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+
+        let record = contract.get_license_by_serial(1).unwrap();
+        assert_eq!(record.wallet_address, user_str());
+        assert_eq!(record.serial, Some(1));
+        assert_eq!(record.expiry, Some(30 * ONE_DAY_NS));
+        assert!(record.active);
+        assert_eq!(contract.get_license_by_serial(2), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Serial 1 is already in use")]
+    fn test_import_rejects_serial_in_use() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+        let mut record = contract.get_license_by_serial(1).unwrap();
+        record.wallet_address = "other.near".to_string();
+
+        contract.import_licenses(vec![record]);
+    }
+
+    #[test]
+    fn test_serial_follows_transfer_and_retires_on_burn() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+
+        setup_context(&user(), 0);
+        contract.transfer_license("new.near".to_string());
+        assert_eq!(contract.get_serial(user_str()), None);
+        assert_eq!(contract.get_serial("new.near".to_string()), Some(1));
+        assert_eq!(contract.get_license_by_serial(1).unwrap().wallet_address, "new.near");
+
+        setup_context(&"new.near".parse().unwrap(), 0);
+        contract.burn_license();
+        assert_eq!(contract.get_serial("new.near".to_string()), None);
+        assert_eq!(contract.get_license_by_serial(1), None);
+
+        // A new license gets a new serial
+        setup_context(&admin(), 0);
+        contract.grant_license("new.near".to_string(), 30, None, None);
+        assert_eq!(contract.get_serial("new.near".to_string()), Some(2));
+    }
+}
@@ -0,0 +1,181 @@
+use near_sdk::{near, AccountId, env, require, Promise};
+
+use crate::events::LicenseEvent;
+use crate::{AdminAction, LicenseContract, LicenseContractExt, ReceiptKind};
+
+#[near]
+impl LicenseContract {
+    /// Set (or clear) the shutdown authority, e.g. a multisig or DAO contract,
+    /// the only account that can shut the contract down.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or an action delay is set
+    pub fn set_shutdown_authority(&mut self, account_id: Option<AccountId>) {
+        self.submit_action(AdminAction::SetShutdownAuthority { account_id });
+    }
+
+    /// Register (or clear) the account collected fees are recovered to after a shutdown.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or an action delay is set
+    pub fn set_treasury_recovery_account(&mut self, account_id: Option<AccountId>) {
+        self.submit_action(AdminAction::SetTreasuryRecoveryAccount { account_id });
+    }
+
+    /// Permanently shut the contract down. Every change method stops working except
+    /// `claim_rental_payment`, `claim_payout` and `recover_treasury`; license checks and views keep
+    /// working against the last state. Pending admin actions are dropped.
+    ///
+    /// # Panics
+    /// Panics if caller is not the shutdown authority, no treasury recovery account
+    /// is registered, or the contract is already shut down
+    pub fn shutdown(&mut self) {
+        self.assert_not_shut_down();
+        let caller = env::predecessor_account_id();
+        require!(
+            self.shutdown_authority.as_ref() == Some(&caller),
+            "Unauthorized: only the shutdown authority can shut down the contract"
+        );
+        let recovery_account = self
+            .treasury_recovery_account
+            .clone()
+            .unwrap_or_else(|| env::panic_str("No treasury recovery account registered"));
+
+        self.shut_down = true;
+        self.pending_actions.clear();
+
+        LicenseEvent::ContractShutDown {
+            shut_down_by: caller,
+            recovery_account,
+        }
+        .emit();
+    }
+
+    /// Send every collected fee to the treasury recovery account. Anyone can call
+    /// this once the contract is shut down, since the destination is fixed.
+    ///
+    /// # Panics
+    /// Panics if the contract isn't shut down or there are no fees to recover
+    pub fn recover_treasury(&mut self) -> Promise {
+        require!(self.shut_down, "Contract is not shut down");
+        let amount = self.collected_fees;
+        require!(!amount.is_zero(), "No fees to recover");
+        // Registered before the shutdown could happen, and frozen by it
+        let recovery_account = self.treasury_recovery_account.clone().unwrap();
+
+        self.collected_fees = self.collected_fees.saturating_sub(amount);
+        self.issue_receipt(ReceiptKind::TreasuryRecovery, recovery_account.clone(), amount);
+        Promise::new(recovery_account).transfer(amount)
+    }
+
+    /// Check whether the contract is shut down.
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down
+    }
+
+    /// Get the shutdown authority, if any.
+    pub fn get_shutdown_authority(&self) -> Option<AccountId> {
+        self.shutdown_authority.clone()
+    }
+
+    /// Get the account collected fees are recovered to after a shutdown, if any.
+    pub fn get_treasury_recovery_account(&self) -> Option<AccountId> {
+        self.treasury_recovery_account.clone()
+    }
+
+    /// Panics if the contract is shut down
+    pub(crate) fn assert_not_shut_down(&self) {
+        require!(!self.shut_down, "Contract is shut down");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{testing_env, NearToken};
+
+    fn dao() -> AccountId {
+        "dao.near".parse().unwrap()
+    }
+
+    fn treasury() -> AccountId {
+        "treasury.near".parse().unwrap()
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+        contract.set_transfer_config(0, NearToken::from_millinear(100));
+        contract.set_shutdown_authority(Some(dao()));
+        contract.set_treasury_recovery_account(Some(treasury()));
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(user())
+            .attached_deposit(NearToken::from_millinear(100))
+            .build();
+        testing_env!(context);
+        contract.transfer_license("other.near".to_string());
+
+        setup_context(&dao(), 0);
+        contract.shutdown();
+        contract
+    }
+
+    #[test]
+    fn test_shutdown_recovers_treasury() {
+        let mut contract = setup_contract();
+        assert!(contract.is_shut_down());
+        // License checks keep working after a shutdown
+        assert!(contract.is_licensed("other.near".to_string()));
+
+        setup_context(&user(), 0);
+        let _ = contract.recover_treasury();
+        assert_eq!(contract.get_collected_fees(), NearToken::from_yoctonear(0));
+        let receipt = contract.get_receipt(contract.get_receipt_count() - 1).unwrap();
+        assert_eq!(receipt.kind, ReceiptKind::TreasuryRecovery);
+        assert_eq!(receipt.account, treasury());
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is shut down")]
+    fn test_admin_halted_after_shutdown() {
+        let mut contract = setup_contract();
+
+        setup_context(&admin(), 0);
+        contract.grant_license(user_str(), 30, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is shut down")]
+    fn test_holder_halted_after_shutdown() {
+        let mut contract = setup_contract();
+
+        setup_context(&"other.near".parse().unwrap(), 0);
+        contract.list_license(NearToken::from_near(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only the shutdown authority can shut down the contract")]
+    fn test_shutdown_unauthorized() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_shutdown_authority(Some(dao()));
+
+        contract.shutdown();
+    }
+
+    #[test]
+    #[should_panic(expected = "No treasury recovery account registered")]
+    fn test_shutdown_requires_recovery_account() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_shutdown_authority(Some(dao()));
+
+        setup_context(&dao(), 0);
+        contract.shutdown();
+    }
+}
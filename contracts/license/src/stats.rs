@@ -0,0 +1,144 @@
+use near_sdk::near;
+
+use crate::{LicenseContract, LicenseContractExt, ONE_DAY_NS};
+
+/// Number of timestamp license grants per granted duration.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DurationHistogram {
+    /// Grants of up to 7 days
+    pub up_to_7_days: u64,
+    /// Grants of more than 7 and up to 30 days
+    pub up_to_30_days: u64,
+    /// Grants of more than 30 and up to 90 days
+    pub up_to_90_days: u64,
+    /// Grants of more than 90 and up to 365 days
+    pub up_to_365_days: u64,
+    /// Grants of more than 365 days
+    pub lifetime: u64,
+}
+
+impl DurationHistogram {
+    /// Count a grant of `duration_ns` in its bucket
+    pub(crate) fn record(&mut self, duration_ns: u64) {
+        let bucket = match duration_ns.div_ceil(ONE_DAY_NS) {
+            0..=7 => &mut self.up_to_7_days,
+            8..=30 => &mut self.up_to_30_days,
+            31..=90 => &mut self.up_to_90_days,
+            91..=365 => &mut self.up_to_365_days,
+            _ => &mut self.lifetime,
+        };
+        *bucket += 1;
+    }
+}
+
+/// Counts of operations performed since deployment.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OperationMetrics {
+    /// License grants of any kind, including extensions and commitment grants
+    pub grants: u64,
+    /// Credentials revoked
+    pub revocations: u64,
+    /// Licenses transferred by their holders
+    pub transfers: u64,
+    /// Licenses moved by guardian recovery
+    pub recoveries: u64,
+    /// License records imported from another deployment
+    pub imported: u64,
+    /// Licenses destroyed by their holders
+    pub burns: u64,
+}
+
+/// Aggregate license statistics.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LicenseStats {
+    /// Number of wallets that have ever held a license
+    pub indexed_wallets: u32,
+    /// Number of currently active timestamp licenses
+    pub active_licenses: u64,
+    /// Number of credential status list entries; extensions supersede a credential in place
+    pub credentials_issued: u32,
+    /// Distribution of granted durations
+    pub duration_histogram: DurationHistogram,
+}
+
+#[near]
+impl LicenseContract {
+    /// Get counts of operations performed since deployment, for dashboards to poll.
+    pub fn get_metrics(&self) -> OperationMetrics {
+        self.metrics.clone()
+    }
+
+    /// Get aggregate license statistics.
+    pub fn get_stats(&self) -> LicenseStats {
+        LicenseStats {
+            indexed_wallets: self.get_indexed_wallet_count(),
+            active_licenses: self.get_active_license_count(),
+            credentials_issued: self.get_credential_count(),
+            duration_histogram: self.duration_histogram.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+
+    #[test]
+    fn test_duration_histogram() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license_hours(user_str(), 1, None, None);
+        contract.grant_license(user_str(), 7, None, None);
+        contract.grant_license(user_str(), 8, None, None);
+        contract.grant_license(user_str(), 30, None, None);
+        contract.grant_license(user_str(), 365, None, None);
+        contract.grant_license(user_str(), 3650, None, None);
+
+        let stats = contract.get_stats();
+        assert_eq!(
+            stats.duration_histogram,
+            DurationHistogram {
+                up_to_7_days: 2,
+                up_to_30_days: 2,
+                up_to_90_days: 0,
+                up_to_365_days: 1,
+                lifetime: 1,
+            }
+        );
+        assert_eq!(stats.indexed_wallets, 1);
+        assert_eq!(stats.active_licenses, 1);
+        assert_eq!(stats.credentials_issued, 1);
+    }
+
+    #[test]
+    fn test_operation_metrics() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+
+        contract.grant_license(user_str(), 30, None, None);
+        contract.grant_license_epochs(user_str(), 2, None, None);
+        contract.revoke_credential(0, None);
+        contract.revoke_credential(0, None);
+
+        setup_context(&user(), 0);
+        contract.transfer_license("other.near".to_string());
+
+        assert_eq!(
+            contract.get_metrics(),
+            OperationMetrics {
+                grants: 2,
+                revocations: 1,
+                transfers: 1,
+                recoveries: 0,
+                imported: 0,
+                burns: 0,
+            }
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use near_sdk::{near, AccountId, env, require, NearToken, Promise};
+
+use crate::checksum::ExpiryKind;
+use crate::{LicenseContract, LicenseContractExt, ReceiptKind};
+
+/// Storage deposit recorded against a license, refunded when the license is burned.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StorageDeposit {
+    /// Account that made the deposit and gets it back
+    pub depositor: AccountId,
+    pub amount: NearToken,
+}
+
+#[near]
+impl LicenseContract {
+    /// Deposit storage for a wallet's license, in the manner of NEP-145 storage
+    /// management: the attached deposit is recorded against the license, moves with
+    /// it between wallets, and is refunded to the depositor when the license is burned.
+    /// Only the account that made a license's first deposit can top it up.
+    ///
+    /// # Arguments
+    /// * `wallet_address` - The wallet whose license the deposit covers
+    ///
+    /// # Panics
+    /// Panics if the contract is shut down, no deposit is attached, the wallet has no
+    /// license entry, or another account already deposited for the license
+    #[payable]
+    pub fn deposit_license_storage(&mut self, wallet_address: String) {
+        self.assert_not_shut_down();
+        let depositor = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        require!(!amount.is_zero(), "Attach a deposit to cover the license's storage");
+        let key = self.wallet_key(&wallet_address);
+        require!(
+            ExpiryKind::ALL.into_iter().any(|kind| self.read_expiry(kind, &key).is_some()),
+            "No license to deposit storage for"
+        );
+
+        let deposit = self.storage_deposits.entry(key).or_insert(StorageDeposit {
+            depositor: depositor.clone(),
+            amount: NearToken::from_yoctonear(0),
+        });
+        require!(
+            deposit.depositor == depositor,
+            "Storage for this license was deposited by another account"
+        );
+        deposit.amount = deposit.amount.saturating_add(amount);
+    }
+
+    /// Get the storage deposit recorded against a wallet's license, if any.
+    pub fn get_license_storage_deposit(&self, wallet_address: String) -> Option<StorageDeposit> {
+        self.storage_deposits.get(&self.wallet_key(&wallet_address)).cloned()
+    }
+
+    /// Refund the storage deposit recorded against the license stored under `key`,
+    /// if any, to its depositor
+    pub(crate) fn refund_storage_deposit(&mut self, key: &str) {
+        if let Some(StorageDeposit { depositor, amount }) = self.storage_deposits.remove(key) {
+            self.issue_receipt(ReceiptKind::StorageRefund, depositor.clone(), amount);
+            Promise::new(depositor).transfer(amount).detach();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    fn sponsor() -> AccountId {
+        "sponsor.near".parse().unwrap()
+    }
+
+    fn setup_deposit_context(account: &AccountId, deposit: NearToken) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account.clone())
+            .attached_deposit(deposit)
+            .build());
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+        contract
+    }
+
+    #[test]
+    fn test_deposit_license_storage() {
+        let mut contract = setup_contract();
+
+        setup_deposit_context(&sponsor(), NearToken::from_millinear(10));
+        contract.deposit_license_storage(user_str());
+        contract.deposit_license_storage(user_str());
+
+        assert_eq!(
+            contract.get_license_storage_deposit(user_str()),
+            Some(StorageDeposit {
+                depositor: sponsor(),
+                amount: NearToken::from_millinear(20),
+            })
+        );
+    }
+
+    #[test]
+    fn test_deposit_moves_with_license() {
+        let mut contract = setup_contract();
+        setup_deposit_context(&sponsor(), NearToken::from_millinear(10));
+        contract.deposit_license_storage(user_str());
+
+        setup_context(&user(), 0);
+        contract.transfer_license("new-user.near".to_string());
+
+        assert_eq!(contract.get_license_storage_deposit(user_str()), None);
+        assert_eq!(
+            contract.get_license_storage_deposit("new-user.near".to_string()).unwrap().depositor,
+            sponsor()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Storage for this license was deposited by another account")]
+    fn test_deposit_by_another_account() {
+        let mut contract = setup_contract();
+        setup_deposit_context(&sponsor(), NearToken::from_millinear(10));
+        contract.deposit_license_storage(user_str());
+
+        setup_deposit_context(&user(), NearToken::from_millinear(10));
+        contract.deposit_license_storage(user_str());
+    }
+
+    #[test]
+    #[should_panic(expected = "No license to deposit storage for")]
+    fn test_deposit_without_license() {
+        let mut contract = setup_contract();
+
+        setup_deposit_context(&sponsor(), NearToken::from_millinear(10));
+        contract.deposit_license_storage("other.near".to_string());
+    }
+}
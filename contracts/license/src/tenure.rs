@@ -0,0 +1,139 @@
+use near_sdk::{near, env};
+
+use crate::{LicenseContract, LicenseContractExt, ONE_DAY_NS};
+
+/// Per-wallet aggregates of timestamp license coverage, updated on every write.
+#[near(serializers = [borsh])]
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TenureRecord {
+    /// Timestamp the wallet first received a timestamp license in nanoseconds
+    first_granted_at: u64,
+    /// Time covered by coverage periods that have ended, in nanoseconds
+    past_ns: u64,
+    /// Start of the current or last coverage period in nanoseconds
+    period_start: u64,
+    /// End of the current or last coverage period in nanoseconds
+    period_end: u64,
+}
+
+/// How long a wallet has held a timestamp license.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tenure {
+    /// Timestamp the wallet first received a timestamp license in nanoseconds
+    pub first_granted_at: u64,
+    /// Whole days the wallet has been licensed so far, across every coverage period
+    pub licensed_days: u64,
+    /// Whole days of the wallet's current uninterrupted coverage, 0 if unlicensed
+    pub streak_days: u64,
+}
+
+#[near]
+impl LicenseContract {
+    /// Get how long a wallet has held a timestamp license, as of now. Coverage
+    /// follows the wallet: a transfer ends the sender's coverage period and starts
+    /// one for the recipient.
+    pub fn get_tenure(&self, wallet_address: String) -> Option<Tenure> {
+        let now = env::block_timestamp();
+        self.tenures.get(&self.wallet_key(&wallet_address)).map(|record| {
+            let current_ns = record.period_end.min(now).saturating_sub(record.period_start);
+            Tenure {
+                first_granted_at: record.first_granted_at,
+                licensed_days: (record.past_ns + current_ns) / ONE_DAY_NS,
+                streak_days: if record.period_end > now { current_ns / ONE_DAY_NS } else { 0 },
+            }
+        })
+    }
+
+    /// Record that the timestamp license stored under `key` now runs until `expiry`,
+    /// extending the current coverage period or starting a new one after a lapse
+    pub(crate) fn cover_tenure(&mut self, key: &str, expiry: u64) {
+        let now = env::block_timestamp();
+        let record = match self.tenures.get(key).cloned() {
+            Some(mut record) if record.period_end > now => {
+                record.period_end = expiry.max(record.period_start);
+                record
+            }
+            Some(mut record) => {
+                record.past_ns += record.period_end - record.period_start;
+                record.period_start = now;
+                record.period_end = expiry.max(now);
+                record
+            }
+            None => TenureRecord {
+                first_granted_at: now,
+                past_ns: 0,
+                period_start: now,
+                period_end: expiry.max(now),
+            },
+        };
+        self.tenures.insert(key.to_string(), record);
+    }
+
+    /// Record that the timestamp license stored under `key` left the wallet,
+    /// ending its current coverage period now
+    pub(crate) fn end_tenure(&mut self, key: &str) {
+        let now = env::block_timestamp();
+        if let Some(record) = self.tenures.get_mut(key) {
+            record.period_end = record.period_end.min(now).max(record.period_start);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+
+    #[test]
+    fn test_tenure_tracks_streaks_and_lapses() {
+        setup_context(&admin(), ONE_DAY_NS);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        assert_eq!(contract.get_tenure(user_str()), None);
+        contract.grant_license(user_str(), 10, None, None);
+
+        setup_context(&admin(), 6 * ONE_DAY_NS);
+        contract.grant_license(user_str(), 10, None, None);
+        assert_eq!(
+            contract.get_tenure(user_str()),
+            Some(Tenure {
+                first_granted_at: ONE_DAY_NS,
+                licensed_days: 5,
+                streak_days: 5,
+            })
+        );
+
+        // Coverage lapses on day 21, then a new streak starts on day 30
+        setup_context(&admin(), 30 * ONE_DAY_NS);
+        assert_eq!(contract.get_tenure(user_str()).unwrap().streak_days, 0);
+        contract.grant_license(user_str(), 10, None, None);
+
+        setup_context(&admin(), 33 * ONE_DAY_NS);
+        assert_eq!(
+            contract.get_tenure(user_str()),
+            Some(Tenure {
+                first_granted_at: ONE_DAY_NS,
+                licensed_days: 23,
+                streak_days: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn test_tenure_follows_wallet_on_transfer() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+
+        setup_context(&user(), 4 * ONE_DAY_NS);
+        contract.transfer_license("other.near".to_string());
+
+        setup_context(&user(), 10 * ONE_DAY_NS);
+        let sender = contract.get_tenure(user_str()).unwrap();
+        assert_eq!((sender.licensed_days, sender.streak_days), (4, 0));
+        let recipient = contract.get_tenure("other.near".to_string()).unwrap();
+        assert_eq!(recipient.first_granted_at, 4 * ONE_DAY_NS);
+        assert_eq!((recipient.licensed_days, recipient.streak_days), (6, 6));
+    }
+}
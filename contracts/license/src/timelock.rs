@@ -0,0 +1,553 @@
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::{near, AccountId, env, require, NearToken};
+
+use crate::events::LicenseEvent;
+use crate::features::assert_valid_feature_id;
+use crate::jurisdiction::normalize_jurisdiction;
+use crate::market::MAX_BPS;
+use crate::payouts::{assert_valid_revenue_split, MAX_PAYOUT_SCHEDULES};
+use crate::permissions::assert_valid_roles;
+use crate::privacy::MIN_SALT_LEN;
+use crate::provenance::assert_valid_memo;
+use crate::{
+    LicenseContract, LicenseContractExt, PayoutAmount, PayoutSchedule, Permission, RevenueShare, Role, ONE_DAY_NS,
+};
+
+/// Maximum number of admin actions waiting in the queue at once
+const MAX_PENDING_ACTIONS: usize = 20;
+
+/// Maximum delay between queuing and executing an admin action, in days
+const MAX_ACTION_DELAY_DAYS: u32 = 30;
+
+/// Economically significant admin action, which must go through the queue
+/// while an action delay is set.
+#[near(serializers = [borsh, json])]
+#[serde(rename_all = "snake_case")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum AdminAction {
+    SetTransferConfig { cooldown_days: u32, fee: NearToken },
+    SetTransferFeeRate { fee_bps: u16, daily_value: NearToken },
+    SetResaleRoyalty { royalty_bps: u16 },
+    SetRecoveryTimelock { days: u32 },
+    SetActionDelay { days: u32 },
+    TransferAdmin { new_admin: AccountId },
+    SetPauser { account_id: Option<AccountId> },
+    SetComplianceOfficer { account_id: Option<AccountId> },
+    SetReminderKeeper { account_id: Option<AccountId> },
+    SetGuardian { account_id: Option<AccountId> },
+    SetShutdownAuthority { account_id: Option<AccountId> },
+    SetTreasuryRecoveryAccount { account_id: Option<AccountId> },
+    SetLienHolderApproval { account_id: AccountId, approved: bool },
+    SetWithdrawalLimit { limit: Option<NearToken> },
+    SetPayoutSchedule { beneficiary: AccountId, amount: PayoutAmount, interval_days: u32 },
+    RemovePayoutSchedule { beneficiary: AccountId },
+    SetRevenueSplit { shares: Vec<RevenueShare> },
+    SetGrantCosigner { account_id: Option<AccountId> },
+    SetLongGrantThreshold { days: Option<u32> },
+    SetPermission { permission: Permission, roles: Vec<Role> },
+    SetPermissionThreshold { permission: Permission, threshold: u8 },
+    SetReceiptRedeemer { account_id: Option<AccountId> },
+    SetVerificationRequired { required: bool },
+    SetBlockedJurisdictions { jurisdictions: Vec<String> },
+    EnablePrivacyMode { salt: Base64VecU8 },
+    SetFeature { feature_id: String, enabled: bool },
+    SetFeatureRollout { feature_id: String, rollout_bps: u16 },
+    SetGasProfiling { enabled: bool },
+    SetPayoutKeeper { account_id: Option<AccountId> },
+}
+
+impl AdminAction {
+    /// Panics if the action's arguments are out of range
+    fn validate(&self) {
+        match self {
+            AdminAction::SetResaleRoyalty { royalty_bps } => {
+                require!(*royalty_bps <= MAX_BPS, "Royalty cannot exceed 10000 basis points");
+            }
+            AdminAction::SetTransferFeeRate { fee_bps, .. } => {
+                require!(*fee_bps <= MAX_BPS, "Transfer fee rate cannot exceed 10000 basis points");
+            }
+            AdminAction::SetActionDelay { days } => {
+                require!(*days <= MAX_ACTION_DELAY_DAYS, "Action delay cannot exceed 30 days");
+            }
+            AdminAction::SetPayoutSchedule { amount, interval_days, .. } => {
+                require!(*interval_days > 0, "Payout interval must be at least one day");
+                amount.validate();
+            }
+            AdminAction::SetRevenueSplit { shares } => assert_valid_revenue_split(shares),
+            AdminAction::SetPermission { roles, .. } => assert_valid_roles(roles),
+            AdminAction::SetPermissionThreshold { threshold, .. } => {
+                require!(*threshold >= 1, "A permission needs at least one approval")
+            }
+            AdminAction::SetBlockedJurisdictions { jurisdictions } => {
+                for code in jurisdictions {
+                    normalize_jurisdiction(code);
+                }
+            }
+            AdminAction::EnablePrivacyMode { salt } => {
+                require!(salt.0.len() >= MIN_SALT_LEN, "Privacy salt must be at least 16 bytes");
+            }
+            AdminAction::SetFeature { feature_id, .. } => assert_valid_feature_id(feature_id),
+            AdminAction::SetFeatureRollout { feature_id, rollout_bps } => {
+                assert_valid_feature_id(feature_id);
+                require!(*rollout_bps <= MAX_BPS, "Rollout cannot exceed 10000 basis points");
+            }
+            _ => {}
+        }
+    }
+}
+
+/// An admin action waiting out the action delay.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueuedAction {
+    pub id: u64,
+    pub action: AdminAction,
+    /// Timestamp the action was queued in nanoseconds
+    pub queued_at: u64,
+    /// Timestamp from which the action can be executed in nanoseconds
+    pub executable_at: u64,
+}
+
+#[near]
+impl LicenseContract {
+    /// Queue an admin action to be executed once the action delay has passed,
+    /// giving users advance notice of the change.
+    ///
+    /// # Returns
+    /// The ID of the queued action
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, the action's arguments are out of range,
+    /// or 20 actions are already pending
+    pub fn queue_action(&mut self, action: AdminAction) -> u64 {
+        self.assert_admin();
+        action.validate();
+        require!(self.pending_actions.len() < MAX_PENDING_ACTIONS, "Too many pending actions");
+
+        let id = self.next_action_id;
+        self.next_action_id += 1;
+        let queued_at = env::block_timestamp();
+        let executable_at = queued_at + self.action_delay_ns;
+        self.pending_actions.push(QueuedAction {
+            id,
+            action: action.clone(),
+            queued_at,
+            executable_at,
+        });
+
+        LicenseEvent::ActionQueued {
+            action_id: id,
+            action,
+            executable_at,
+        }
+        .emit();
+        id
+    }
+
+    /// Execute a queued admin action whose delay has passed.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, no such action is pending,
+    /// or the action is still timelocked
+    pub fn execute_action(&mut self, action_id: u64) {
+        self.assert_admin();
+        let position = self.pending_action_position(action_id);
+        require!(
+            env::block_timestamp() >= self.pending_actions[position].executable_at,
+            "Action is still timelocked"
+        );
+
+        let queued = self.pending_actions.remove(position);
+        self.apply_action(queued.action);
+        LicenseEvent::ActionExecuted { action_id }.emit();
+    }
+
+    /// Withdraw a queued admin action.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or no such action is pending
+    pub fn cancel_action(&mut self, action_id: u64) {
+        self.assert_admin();
+        let position = self.pending_action_position(action_id);
+        self.pending_actions.remove(position);
+        LicenseEvent::ActionCancelled {
+            action_id,
+            cancelled_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    /// Veto a queued admin action during its delay window, as a second line of
+    /// defense against a compromised admin key.
+    ///
+    /// # Arguments
+    /// * `action_id` - The queued action to veto
+    /// * `reason` - Optional public reason, at most 256 bytes
+    ///
+    /// # Panics
+    /// Panics if caller is not the guardian, no such action is pending,
+    /// its delay window has passed, or the reason is too long
+    pub fn veto_action(&mut self, action_id: u64, reason: Option<String>) {
+        self.assert_not_shut_down();
+        let caller = env::predecessor_account_id();
+        require!(
+            self.guardian.as_ref() == Some(&caller),
+            "Unauthorized: only the guardian can veto actions"
+        );
+        assert_valid_memo(&reason);
+
+        let position = self.pending_action_position(action_id);
+        require!(
+            env::block_timestamp() < self.pending_actions[position].executable_at,
+            "Delay window has passed"
+        );
+        self.pending_actions.remove(position);
+        LicenseEvent::ActionVetoed {
+            action_id,
+            vetoed_by: caller,
+            reason,
+        }
+        .emit();
+    }
+
+    /// Set the delay between queuing and executing admin actions. Once set, every
+    /// `AdminAction`, including changing the delay, must go through `queue_action`.
+    ///
+    /// # Arguments
+    /// * `days` - Delay in days, at most 30; 0 lets admin actions apply immediately
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, a delay is already set, or `days` exceeds 30
+    pub fn set_action_delay(&mut self, days: u32) {
+        self.submit_action(AdminAction::SetActionDelay { days });
+    }
+
+    /// Set (or clear) the guardian account, which can veto queued admin actions.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or a delay is set
+    pub fn set_guardian(&mut self, account_id: Option<AccountId>) {
+        self.submit_action(AdminAction::SetGuardian { account_id });
+    }
+
+    /// Get the delay between queuing and executing admin actions in nanoseconds.
+    pub fn get_action_delay(&self) -> u64 {
+        self.action_delay_ns
+    }
+
+    /// Get the guardian account, if any.
+    pub fn get_guardian(&self) -> Option<AccountId> {
+        self.guardian.clone()
+    }
+
+    /// Get the queued admin actions, oldest first.
+    pub fn get_pending_actions(&self) -> Vec<QueuedAction> {
+        self.pending_actions.clone()
+    }
+
+    /// Apply an admin action right away on behalf of the admin, as long as no action delay is set
+    pub(crate) fn submit_action(&mut self, action: AdminAction) {
+        self.assert_admin();
+        require!(self.action_delay_ns == 0, "Admin actions are timelocked, use queue_action");
+        action.validate();
+        self.apply_action(action);
+    }
+
+    /// Position of a pending action in the queue
+    fn pending_action_position(&self, action_id: u64) -> usize {
+        self.pending_actions
+            .iter()
+            .position(|queued| queued.id == action_id)
+            .unwrap_or_else(|| env::panic_str("No pending action with this ID"))
+    }
+
+    /// Carry out an admin action that was authorized and validated
+    fn apply_action(&mut self, action: AdminAction) {
+        if matches!(action, AdminAction::SetGrantCosigner { .. } | AdminAction::SetLongGrantThreshold { .. }) {
+            self.assert_quorum_change_approved(&action);
+        }
+        match action {
+            AdminAction::SetTransferConfig { cooldown_days, fee } => {
+                self.transfer_config.cooldown_days = cooldown_days;
+                self.transfer_config.fee = fee;
+            }
+            AdminAction::SetTransferFeeRate { fee_bps, daily_value } => {
+                self.transfer_config.fee_bps = fee_bps;
+                self.transfer_config.daily_value = daily_value;
+            }
+            AdminAction::SetResaleRoyalty { royalty_bps } => self.resale_royalty_bps = royalty_bps,
+            AdminAction::SetRecoveryTimelock { days } => self.recovery_timelock_ns = days as u64 * ONE_DAY_NS,
+            AdminAction::SetActionDelay { days } => self.action_delay_ns = days as u64 * ONE_DAY_NS,
+            AdminAction::TransferAdmin { new_admin } => {
+                require!(new_admin != self.admin, "Account is already the admin");
+                require!(
+                    self.grant_cosigner.as_ref() != Some(&new_admin),
+                    "The grant co-signer can't also be the admin"
+                );
+                self.admin = new_admin.clone();
+                self.record_role_change(Role::Admin, Some(new_admin));
+            }
+            AdminAction::SetPauser { account_id } => {
+                self.pauser = account_id.clone();
+                self.record_role_change(Role::Pauser, account_id);
+            }
+            AdminAction::SetComplianceOfficer { account_id } => {
+                self.compliance_officer = account_id.clone();
+                self.record_role_change(Role::ComplianceOfficer, account_id);
+            }
+            AdminAction::SetReminderKeeper { account_id } => {
+                self.reminder_keeper = account_id.clone();
+                self.record_role_change(Role::ReminderKeeper, account_id);
+            }
+            AdminAction::SetGuardian { account_id } => {
+                self.guardian = account_id.clone();
+                self.record_role_change(Role::Guardian, account_id);
+            }
+            AdminAction::SetShutdownAuthority { account_id } => {
+                self.shutdown_authority = account_id.clone();
+                self.record_role_change(Role::ShutdownAuthority, account_id);
+            }
+            AdminAction::SetGrantCosigner { account_id } => {
+                require!(
+                    account_id.as_ref() != Some(&self.admin),
+                    "The admin can't also be the grant co-signer"
+                );
+                self.grant_cosigner = account_id.clone();
+                self.record_role_change(Role::GrantCosigner, account_id);
+            }
+            AdminAction::SetLongGrantThreshold { days } => self.long_grant_threshold_days = days,
+            AdminAction::SetPermission { permission, roles } => self.apply_permission(permission, roles),
+            AdminAction::SetPermissionThreshold { permission, threshold } => {
+                self.apply_permission_threshold(permission, threshold)
+            }
+            AdminAction::SetReceiptRedeemer { account_id } => {
+                self.receipt_redeemer = account_id.clone();
+                self.record_role_change(Role::ReceiptRedeemer, account_id);
+            }
+            AdminAction::SetTreasuryRecoveryAccount { account_id } => self.treasury_recovery_account = account_id,
+            AdminAction::SetWithdrawalLimit { limit } => self.withdrawal_limit = limit,
+            AdminAction::SetPayoutSchedule { beneficiary, amount, interval_days } => {
+                let schedule = PayoutSchedule {
+                    beneficiary,
+                    amount,
+                    interval_days,
+                    next_payout_at: env::block_timestamp() + interval_days as u64 * ONE_DAY_NS,
+                };
+                match self.payout_schedules.iter().position(|existing| existing.beneficiary == schedule.beneficiary) {
+                    Some(position) => self.payout_schedules[position] = schedule,
+                    None => {
+                        require!(
+                            self.payout_schedules.len() < MAX_PAYOUT_SCHEDULES,
+                            "Too many payout schedules"
+                        );
+                        self.payout_schedules.push(schedule);
+                    }
+                }
+            }
+            AdminAction::SetRevenueSplit { shares } => self.revenue_split = shares,
+            AdminAction::RemovePayoutSchedule { beneficiary } => {
+                let count = self.payout_schedules.len();
+                self.payout_schedules.retain(|schedule| schedule.beneficiary != beneficiary);
+                require!(self.payout_schedules.len() < count, "No payout schedule for this beneficiary");
+            }
+            AdminAction::SetLienHolderApproval { account_id, approved } => {
+                if approved {
+                    self.lien_holders.insert(account_id);
+                } else {
+                    self.lien_holders.remove(&account_id);
+                }
+            }
+            AdminAction::SetVerificationRequired { required } => self.verification_required = required,
+            AdminAction::SetBlockedJurisdictions { jurisdictions } => {
+                self.replace_blocked_jurisdictions(jurisdictions)
+            }
+            AdminAction::EnablePrivacyMode { salt } => self.apply_privacy_mode(salt),
+            AdminAction::SetFeature { feature_id, enabled } => {
+                let rollout_bps = if enabled { MAX_BPS } else { 0 };
+                let event = LicenseEvent::FeatureUpdated { feature_id: feature_id.clone(), enabled };
+                self.apply_feature_rollout(feature_id, rollout_bps, event);
+            }
+            AdminAction::SetFeatureRollout { feature_id, rollout_bps } => {
+                let event = LicenseEvent::FeatureRolloutUpdated { feature_id: feature_id.clone(), rollout_bps };
+                self.apply_feature_rollout(feature_id, rollout_bps, event);
+            }
+            AdminAction::SetGasProfiling { enabled } => self.gas_profiling = enabled,
+            AdminAction::SetPayoutKeeper { account_id } => {
+                self.payout_keeper = account_id.clone();
+                self.record_role_change(Role::PayoutKeeper, account_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user};
+    use crate::{Network, TransferConfig};
+
+    fn guardian() -> AccountId {
+        "guardian.near".parse().unwrap()
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_guardian(Some(guardian()));
+        contract.set_action_delay(2);
+        contract
+    }
+
+    #[test]
+    fn test_queued_action_executes_after_delay() {
+        let mut contract = setup_contract();
+        let fee = NearToken::from_millinear(100);
+        let id = contract.queue_action(AdminAction::SetTransferConfig { cooldown_days: 7, fee });
+
+        let pending = contract.get_pending_actions();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].executable_at, 2 * ONE_DAY_NS);
+        assert_eq!(contract.get_transfer_config().fee, NearToken::from_yoctonear(0));
+
+        setup_context(&admin(), 2 * ONE_DAY_NS);
+        contract.execute_action(id);
+        assert_eq!(
+            contract.get_transfer_config(),
+            TransferConfig { cooldown_days: 7, fee, ..TransferConfig::default() }
+        );
+        assert!(contract.get_pending_actions().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Action is still timelocked")]
+    fn test_execute_before_delay() {
+        let mut contract = setup_contract();
+        let id = contract.queue_action(AdminAction::SetResaleRoyalty { royalty_bps: 500 });
+
+        setup_context(&admin(), ONE_DAY_NS);
+        contract.execute_action(id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Admin actions are timelocked, use queue_action")]
+    fn test_direct_change_rejected_while_timelocked() {
+        let mut contract = setup_contract();
+
+        contract.set_pauser(Some(user()));
+    }
+
+    #[test]
+    #[should_panic(expected = "No pending action with this ID")]
+    fn test_guardian_vetoes_action() {
+        let mut contract = setup_contract();
+        let id = contract.queue_action(AdminAction::TransferAdmin { new_admin: user() });
+
+        setup_context(&guardian(), ONE_DAY_NS);
+        contract.veto_action(id, Some("Unannounced admin change".to_string()));
+        assert!(contract.get_pending_actions().is_empty());
+
+        setup_context(&admin(), 2 * ONE_DAY_NS);
+        contract.execute_action(id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Delay window has passed")]
+    fn test_veto_after_delay_window() {
+        let mut contract = setup_contract();
+        let id = contract.queue_action(AdminAction::SetActionDelay { days: 0 });
+
+        setup_context(&guardian(), 2 * ONE_DAY_NS);
+        contract.veto_action(id, None);
+    }
+
+    #[test]
+    fn test_admin_cancels_action() {
+        let mut contract = setup_contract();
+        let id = contract.queue_action(AdminAction::SetRecoveryTimelock { days: 1 });
+
+        contract.cancel_action(id);
+        assert!(contract.get_pending_actions().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only admin can perform this action")]
+    fn test_guardian_cannot_cancel() {
+        let mut contract = setup_contract();
+        let id = contract.queue_action(AdminAction::SetRecoveryTimelock { days: 1 });
+
+        setup_context(&guardian(), 0);
+        contract.cancel_action(id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only the guardian can veto actions")]
+    fn test_veto_unauthorized() {
+        let mut contract = setup_contract();
+        let id = contract.queue_action(AdminAction::SetRecoveryTimelock { days: 1 });
+
+        setup_context(&user(), 0);
+        contract.veto_action(id, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Royalty cannot exceed 10000 basis points")]
+    fn test_queue_validates_action() {
+        let mut contract = setup_contract();
+
+        contract.queue_action(AdminAction::SetResaleRoyalty { royalty_bps: 10_001 });
+    }
+
+    #[test]
+    fn test_compliance_settings_go_through_queue() {
+        let mut contract = setup_contract();
+        let ids = [
+            contract.queue_action(AdminAction::SetVerificationRequired { required: true }),
+            contract.queue_action(AdminAction::SetBlockedJurisdictions { jurisdictions: vec!["cu".to_string()] }),
+        ];
+        assert!(!contract.is_verification_required());
+
+        setup_context(&admin(), 2 * ONE_DAY_NS);
+        ids.into_iter().for_each(|id| contract.execute_action(id));
+        assert!(contract.is_verification_required());
+        assert_eq!(contract.get_blocked_jurisdictions(), vec!["CU".to_string()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Admin actions are timelocked, use queue_action")]
+    fn test_direct_jurisdiction_change_rejected_while_timelocked() {
+        let mut contract = setup_contract();
+
+        contract.set_blocked_jurisdictions(Vec::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid jurisdiction code: U$")]
+    fn test_queue_validates_jurisdictions() {
+        let mut contract = setup_contract();
+
+        contract.queue_action(AdminAction::SetBlockedJurisdictions { jurisdictions: vec!["U$".to_string()] });
+    }
+
+    #[test]
+    #[should_panic(expected = "Privacy mode must be enabled before any wallet is licensed")]
+    fn test_queued_privacy_mode_rechecked_on_execution() {
+        let mut contract = setup_contract();
+        let id = contract.queue_action(AdminAction::EnablePrivacyMode { salt: Base64VecU8(vec![7; 16]) });
+        contract.grant_license(user().to_string(), 30, None, None);
+
+        setup_context(&admin(), 2 * ONE_DAY_NS);
+        contract.execute_action(id);
+    }
+
+    #[test]
+    fn test_immediate_when_no_delay() {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.set_recovery_timelock(3);
+        assert_eq!(contract.get_recovery_timelock(), 3 * ONE_DAY_NS);
+
+        let id = contract.queue_action(AdminAction::SetLienHolderApproval { account_id: user(), approved: true });
+        contract.execute_action(id);
+        assert!(contract.is_approved_lien_holder(user()));
+    }
+}
@@ -0,0 +1,601 @@
+use near_sdk::{near, AccountId, env, require, NearToken, Promise};
+
+use crate::events::LicenseEvent;
+use crate::market::MAX_BPS;
+use crate::{AdminAction, GasClass, LicenseContract, LicenseContractExt, ReceiptKind, ONE_DAY_NS};
+
+/// Rules governing license transfers.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransferConfig {
+    /// Minimum time a license must stay with a wallet before it can be transferred again, in days
+    pub cooldown_days: u32,
+    /// Flat fee attached to every transfer
+    pub fee: NearToken,
+    /// Share of the license's remaining value charged on top of the flat fee, in basis points
+    pub fee_bps: u16,
+    /// Value of one day of license, pricing the remaining term for `fee_bps`
+    pub daily_value: NearToken,
+}
+
+/// Window over which fee withdrawals count against the daily limit
+const WITHDRAWAL_WINDOW_NS: u64 = ONE_DAY_NS;
+
+/// Maximum number of entries kept in a license's transfer history
+const MAX_TRANSFER_HISTORY: usize = 20;
+
+/// How a license came to change wallets.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferKind {
+    /// The holder transferred the license
+    Transfer,
+    /// Guardians recovered the license to a new wallet
+    Recovery,
+    /// The license was bought from its holder on the resale market
+    Sale,
+}
+
+/// A single change of wallet in a license's history.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransferRecord {
+    pub from: String,
+    pub to: String,
+    pub kind: TransferKind,
+    /// Timestamp of the transfer in nanoseconds
+    pub timestamp: u64,
+    /// Time left on the license's timestamp expiry at transfer, in nanoseconds
+    pub remaining_ns: u64,
+}
+
+impl Default for TransferConfig {
+    fn default() -> Self {
+        Self {
+            cooldown_days: 0,
+            fee: NearToken::from_yoctonear(0),
+            fee_bps: 0,
+            daily_value: NearToken::from_yoctonear(0),
+        }
+    }
+}
+
+#[near]
+impl LicenseContract {
+    /// Transfer the caller's license to another wallet.
+    /// Every license entry (timestamp, block-height and epoch) moves to the new wallet,
+    /// and the caller's recovery guardians are cleared. Any deposit above the fee is refunded.
+    ///
+    /// # Arguments
+    /// * `new_wallet` - The wallet address to transfer the license to
+    ///
+    /// # Panics
+    /// Panics if the caller has no active license, the cooldown hasn't passed,
+    /// the attached deposit doesn't cover the fee, or the new wallet can't hold the license
+    /// (it's ineligible, or holds an unexpired license)
+    #[payable]
+    pub fn transfer_license(&mut self, new_wallet: String) {
+        self.assert_not_shut_down();
+        let caller = env::predecessor_account_id();
+        let from_key = self.wallet_key(caller.as_str());
+        let to_key = self.wallet_key(&new_wallet);
+        self.transfer_for_fee(caller, from_key, to_key);
+    }
+
+    /// Set the cooldown and flat fee applied to license transfers.
+    ///
+    /// # Arguments
+    /// * `cooldown_days` - Minimum days between transfers of the same license
+    /// * `fee` - Flat fee in yoctoNEAR attached to every transfer
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or an action delay is set
+    pub fn set_transfer_config(&mut self, cooldown_days: u32, fee: NearToken) {
+        self.submit_action(AdminAction::SetTransferConfig { cooldown_days, fee });
+    }
+
+    /// Set the share of a license's remaining value charged on every transfer, on top
+    /// of the flat fee, so long licenses can't be passed around for the flat fee alone.
+    /// The remaining value is the time left on the timestamp license at `daily_value`
+    /// per day.
+    ///
+    /// # Arguments
+    /// * `fee_bps` - Share of the remaining value in basis points (1/100 of a percent)
+    /// * `daily_value` - Value of one day of license
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, an action delay is set, or `fee_bps` exceeds 10000
+    pub fn set_transfer_fee_rate(&mut self, fee_bps: u16, daily_value: NearToken) {
+        self.submit_action(AdminAction::SetTransferFeeRate { fee_bps, daily_value });
+    }
+
+    /// Get the fee a wallet's license transfer costs right now: the flat fee plus the
+    /// configured share of its remaining value.
+    pub fn get_transfer_fee(&self, wallet_address: String) -> NearToken {
+        self.transfer_fee(&self.wallet_key(&wallet_address))
+    }
+
+    /// Withdraw collected transfer fees to the admin account.
+    ///
+    /// # Arguments
+    /// * `amount` - Amount to withdraw, at most the collected fees
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin, `amount` exceeds the collected fees,
+    /// or it would take the past 24 hours' withdrawals above the daily limit
+    pub fn withdraw_fees(&mut self, amount: NearToken) -> Promise {
+        self.assert_admin();
+        require!(amount <= self.collected_fees, "Amount exceeds collected fees");
+        self.record_withdrawal(amount);
+
+        self.collected_fees = self.collected_fees.saturating_sub(amount);
+        self.issue_receipt(ReceiptKind::FeeWithdrawal, self.admin.clone(), amount);
+        Promise::new(self.admin.clone()).transfer(amount)
+    }
+
+    /// Set (or clear) the most fees that can be withdrawn in any 24 hours,
+    /// limiting what a compromised admin key can drain.
+    ///
+    /// # Panics
+    /// Panics if caller is not the admin or an action delay is set
+    pub fn set_withdrawal_limit(&mut self, limit: Option<NearToken>) {
+        self.submit_action(AdminAction::SetWithdrawalLimit { limit });
+    }
+
+    /// Get the most fees that can be withdrawn in any 24 hours, if limited.
+    pub fn get_withdrawal_limit(&self) -> Option<NearToken> {
+        self.withdrawal_limit
+    }
+
+    /// Get how much can still be withdrawn right now under the daily limit, if limited.
+    pub fn get_withdrawal_allowance(&self) -> Option<NearToken> {
+        self.withdrawal_limit
+            .map(|limit| limit.saturating_sub(self.withdrawn_in_window(env::block_timestamp())))
+    }
+
+    /// Get the rules governing license transfers.
+    pub fn get_transfer_config(&self) -> TransferConfig {
+        self.transfer_config.clone()
+    }
+
+    /// Get the timestamp a wallet last received a license by transfer, if any.
+    pub fn get_last_transfer(&self, wallet_address: String) -> Option<u64> {
+        self.last_transfers.get(&self.wallet_key(&wallet_address)).copied()
+    }
+
+    /// Get the transfer fees collected and not yet withdrawn.
+    pub fn get_collected_fees(&self) -> NearToken {
+        self.collected_fees
+    }
+
+    /// Get the transfer history of the license currently held by a wallet, oldest first.
+    /// Only the most recent 20 transfers are kept.
+    pub fn get_transfer_history(&self, wallet_address: String) -> Vec<TransferRecord> {
+        self.transfer_history
+            .get(&self.wallet_key(&wallet_address))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Transfer the license stored under `from_key` to `to_key`, charging the transfer fee
+    /// to `payer` out of the attached deposit and refunding the rest
+    pub(crate) fn transfer_for_fee(&mut self, payer: AccountId, from_key: String, to_key: String) {
+        self.assert_transferable(&from_key, &to_key);
+
+        let fee = self.transfer_fee(&from_key);
+        let deposit = env::attached_deposit();
+        require!(deposit >= fee, format!("Transfer requires a fee of {} yoctoNEAR", fee.as_yoctonear()));
+
+        self.hand_over_license(&from_key, &to_key, TransferKind::Transfer);
+        self.collected_fees = self.collected_fees.saturating_add(fee);
+
+        LicenseEvent::LicenseTransferred {
+            serial: self.serials.get(&to_key).copied(),
+            from: from_key,
+            to: to_key,
+            fee,
+        }
+        .emit();
+
+        if !fee.is_zero() {
+            self.issue_receipt(ReceiptKind::TransferFee, payer.clone(), fee);
+        }
+        let refund = deposit.saturating_sub(fee);
+        if !refund.is_zero() {
+            self.issue_receipt(ReceiptKind::Refund, payer.clone(), refund);
+            Promise::new(payer).transfer(refund).detach();
+        }
+        self.metrics.transfers += 1;
+        self.record_gas(GasClass::Transfer);
+    }
+
+    /// Fee for transferring the license stored under `key`: the flat fee plus
+    /// `fee_bps` of the remaining term's value
+    pub(crate) fn transfer_fee(&self, key: &str) -> NearToken {
+        let config = &self.transfer_config;
+        let remaining_ns = self
+            .licenses
+            .get(key)
+            .map_or(0, |&expiry| expiry.saturating_sub(env::block_timestamp()));
+        let remaining_value = config
+            .daily_value
+            .as_yoctonear()
+            .saturating_mul(remaining_ns as u128)
+            / ONE_DAY_NS as u128;
+        let rate_fee = remaining_value.saturating_mul(config.fee_bps as u128) / MAX_BPS as u128;
+        config.fee.saturating_add(NearToken::from_yoctonear(rate_fee))
+    }
+
+    /// Count a withdrawal against the rolling 24-hour limit, dropping withdrawals
+    /// that fell out of the window
+    pub(crate) fn record_withdrawal(&mut self, amount: NearToken) {
+        let now = env::block_timestamp();
+        self.recent_withdrawals
+            .retain(|&(timestamp, _)| timestamp + WITHDRAWAL_WINDOW_NS > now);
+
+        if let Some(limit) = self.withdrawal_limit {
+            require!(
+                self.withdrawn_in_window(now).saturating_add(amount) <= limit,
+                format!("Withdrawal exceeds the daily limit of {} yoctoNEAR", limit.as_yoctonear())
+            );
+        }
+        self.recent_withdrawals.push((now, amount));
+    }
+
+    /// Total withdrawn in the 24 hours before `now`
+    fn withdrawn_in_window(&self, now: u64) -> NearToken {
+        self.recent_withdrawals
+            .iter()
+            .filter(|&&(timestamp, _)| timestamp + WITHDRAWAL_WINDOW_NS > now)
+            .fold(NearToken::from_yoctonear(0), |total, &(_, amount)| total.saturating_add(amount))
+    }
+
+    /// Panics unless transfers aren't frozen and the license stored under `from_key`
+    /// is active, free of liens, out of its transfer cooldown, and going to a different wallet
+    pub(crate) fn assert_transferable(&self, from_key: &str, to_key: &str) {
+        self.assert_transfers_not_frozen();
+        require!(from_key != to_key, "Cannot transfer a license to the same wallet");
+        require!(self.holds_license(from_key), "No active license to transfer");
+        self.assert_no_lien(from_key);
+        self.assert_cooldown_passed(from_key);
+    }
+
+    /// Panics if the license stored under `key` was received by transfer less than
+    /// the configured cooldown ago
+    pub(crate) fn assert_cooldown_passed(&self, key: &str) {
+        let cooldown_ns = (self.transfer_config.cooldown_days as u64).saturating_mul(ONE_DAY_NS);
+        if let Some(&last_transfer) = self.last_transfers.get(key) {
+            require!(
+                env::block_timestamp() >= last_transfer.saturating_add(cooldown_ns),
+                "License transfer cooldown has not passed"
+            );
+        }
+    }
+
+    /// Move a license between holders, clearing the previous holder's recovery setup
+    /// and starting the new holder's transfer cooldown
+    pub(crate) fn hand_over_license(&mut self, from_key: &str, to_key: &str, kind: TransferKind) {
+        self.move_license(from_key, to_key);
+        self.record_transfer(from_key, to_key, kind);
+        self.recovery_guardians.remove(from_key);
+        self.recovery_requests.remove(from_key);
+        self.last_transfers.remove(from_key);
+        self.last_transfers.insert(to_key.to_string(), env::block_timestamp());
+    }
+
+    /// Append a transfer to the history of a license that just moved from `from_key`
+    /// to `to_key`, carrying the history along with the license
+    pub(crate) fn record_transfer(&mut self, from_key: &str, to_key: &str, kind: TransferKind) {
+        let now = env::block_timestamp();
+        let remaining_ns = self
+            .licenses
+            .get(to_key)
+            .map(|&expiry| expiry.saturating_sub(now))
+            .unwrap_or(0);
+
+        let mut history = self.transfer_history.remove(from_key).unwrap_or_default();
+        if history.len() >= MAX_TRANSFER_HISTORY {
+            history.remove(0);
+        }
+        history.push(TransferRecord {
+            from: from_key.to_string(),
+            to: to_key.to_string(),
+            kind,
+            timestamp: now,
+            remaining_ns,
+        });
+        self.transfer_history.insert(to_key.to_string(), history);
+
+        self.provenance.remove(from_key);
+        self.record_provenance(to_key, kind.into(), None);
+        self.move_serial(from_key, to_key);
+        // The recipient's lapsed license was released, so its own deposit goes back
+        self.refund_storage_deposit(to_key);
+        if let Some(deposit) = self.storage_deposits.remove(from_key) {
+            self.storage_deposits.insert(to_key.to_string(), deposit);
+        }
+        self.listings.remove(from_key);
+        self.rental_offers.remove(from_key);
+        self.lien_authorizations.remove(from_key);
+        self.approvals.remove(from_key);
+        // Only recovery moves a license under a lien or lent out, and the lien and
+        // rental move with it; the rent stays due to the lender account on record
+        if let Some(lien) = self.liens.remove(from_key) {
+            self.liens.insert(to_key.to_string(), lien);
+        }
+        if let Some(rental) = self.rentals.remove(from_key) {
+            self.settle_rental(to_key);
+            if self.rented_from.get(&rental.renter).is_some_and(|lender_key| lender_key == from_key) {
+                self.rented_from.insert(rental.renter.clone(), to_key.to_string());
+            }
+            self.rentals.insert(to_key.to_string(), rental);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{admin, setup_context, user, user_str};
+    use crate::Network;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{testing_env, AccountId};
+
+    fn recipient() -> AccountId {
+        "recipient.near".parse().unwrap()
+    }
+
+    fn setup_transfer_context(predecessor: &AccountId, block_timestamp: u64, deposit: NearToken) {
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(predecessor.clone())
+            .block_timestamp(block_timestamp)
+            .attached_deposit(deposit)
+            .build();
+        testing_env!(context);
+    }
+
+    fn setup_contract() -> LicenseContract {
+        setup_context(&admin(), 0);
+        let mut contract = LicenseContract::new(admin(), Network::Mainnet);
+        contract.grant_license(user_str(), 30, None, None);
+        contract.set_transfer_config(7, NearToken::from_millinear(100));
+        contract
+    }
+
+    #[test]
+    fn test_transfer_license() {
+        let mut contract = setup_contract();
+        let expiry = contract.get_expiry(user_str());
+
+        setup_transfer_context(&user(), ONE_DAY_NS, NearToken::from_millinear(150));
+        contract.transfer_license(recipient().to_string());
+
+        assert!(!contract.is_licensed(user_str()));
+        assert!(contract.is_licensed(recipient().to_string()));
+        assert_eq!(contract.get_expiry(recipient().to_string()), expiry);
+        assert_eq!(contract.get_last_transfer(recipient().to_string()), Some(ONE_DAY_NS));
+        assert_eq!(contract.get_collected_fees(), NearToken::from_millinear(100));
+    }
+
+    #[test]
+    fn test_transfer_and_withdrawal_issue_receipts() {
+        let mut contract = setup_contract();
+
+        setup_transfer_context(&user(), ONE_DAY_NS, NearToken::from_millinear(150));
+        contract.transfer_license(recipient().to_string());
+        setup_context(&admin(), 2 * ONE_DAY_NS);
+        let _ = contract.withdraw_fees(NearToken::from_millinear(100));
+
+        assert_eq!(contract.get_receipt_count(), 3);
+        let receipts: Vec<_> = (0..3).map(|id| contract.get_receipt(id).unwrap()).collect();
+        assert_eq!(
+            receipts.iter().map(|r| (r.id, r.kind, r.amount)).collect::<Vec<_>>(),
+            vec![
+                (0, ReceiptKind::TransferFee, NearToken::from_millinear(100)),
+                (1, ReceiptKind::Refund, NearToken::from_millinear(50)),
+                (2, ReceiptKind::FeeWithdrawal, NearToken::from_millinear(100)),
+            ]
+        );
+        assert_eq!(receipts[1].account, user());
+        assert_eq!(receipts[2].account, admin());
+        assert_eq!(receipts[2].timestamp, 2 * ONE_DAY_NS);
+        assert_eq!(contract.get_receipt(3), None);
+    }
+
+    #[test]
+    fn test_transfer_history_follows_license() {
+        let mut contract = setup_contract();
+        contract.set_transfer_config(0, NearToken::from_yoctonear(0));
+
+        setup_transfer_context(&user(), ONE_DAY_NS, NearToken::from_yoctonear(0));
+        contract.transfer_license(recipient().to_string());
+        setup_transfer_context(&recipient(), 2 * ONE_DAY_NS, NearToken::from_yoctonear(0));
+        contract.transfer_license(user_str());
+
+        assert!(contract.get_transfer_history(recipient().to_string()).is_empty());
+        let history = contract.get_transfer_history(user_str());
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].from, user_str());
+        assert_eq!(history[0].to, recipient().to_string());
+        assert_eq!(history[0].kind, TransferKind::Transfer);
+        assert_eq!(history[0].remaining_ns, 29 * ONE_DAY_NS);
+        assert_eq!(history[1].timestamp, 2 * ONE_DAY_NS);
+        assert_eq!(history[1].remaining_ns, 28 * ONE_DAY_NS);
+    }
+
+    #[test]
+    fn test_transfer_history_is_bounded() {
+        let mut contract = setup_contract();
+        contract.set_transfer_config(0, NearToken::from_yoctonear(0));
+
+        let mut holder = user();
+        for i in 0..(MAX_TRANSFER_HISTORY + 5) {
+            let next: AccountId = format!("holder{}.near", i).parse().unwrap();
+            setup_transfer_context(&holder, 0, NearToken::from_yoctonear(0));
+            contract.transfer_license(next.to_string());
+            holder = next;
+        }
+
+        let history = contract.get_transfer_history(holder.to_string());
+        assert_eq!(history.len(), MAX_TRANSFER_HISTORY);
+        assert_eq!(history.last().unwrap().to, holder.to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "License transfer cooldown has not passed")]
+    fn test_transfer_cooldown() {
+        let mut contract = setup_contract();
+
+        setup_transfer_context(&user(), 0, NearToken::from_millinear(100));
+        contract.transfer_license(recipient().to_string());
+
+        // The new holder has to wait out the cooldown before passing it on
+        setup_transfer_context(&recipient(), 6 * ONE_DAY_NS, NearToken::from_millinear(100));
+        contract.transfer_license(user_str());
+    }
+
+    #[test]
+    fn test_transfer_after_cooldown() {
+        let mut contract = setup_contract();
+
+        setup_transfer_context(&user(), 0, NearToken::from_millinear(100));
+        contract.transfer_license(recipient().to_string());
+
+        setup_transfer_context(&recipient(), 7 * ONE_DAY_NS, NearToken::from_millinear(100));
+        contract.transfer_license(user_str());
+        assert!(contract.is_licensed(user_str()));
+    }
+
+    #[test]
+    #[should_panic(expected = "License transfer cooldown has not passed")]
+    fn test_longest_cooldown_does_not_overflow() {
+        let mut contract = setup_contract();
+
+        setup_transfer_context(&user(), 0, NearToken::from_millinear(100));
+        contract.transfer_license(recipient().to_string());
+        setup_context(&admin(), 0);
+        contract.set_transfer_config(u32::MAX, NearToken::from_millinear(100));
+
+        setup_transfer_context(&recipient(), ONE_DAY_NS, NearToken::from_millinear(100));
+        contract.transfer_license(user_str());
+    }
+
+    #[test]
+    #[should_panic(expected = "Transfer requires a fee of 100000000000000000000000 yoctoNEAR")]
+    fn test_transfer_requires_fee() {
+        let mut contract = setup_contract();
+
+        setup_transfer_context(&user(), 0, NearToken::from_millinear(50));
+        contract.transfer_license(recipient().to_string());
+    }
+
+    #[test]
+    fn test_transfer_fee_includes_share_of_remaining_value() {
+        let mut contract = setup_contract();
+        // 10% of 0.01 NEAR per remaining day, on top of the 0.1 NEAR flat fee
+        contract.set_transfer_fee_rate(1_000, NearToken::from_millinear(10));
+
+        setup_context(&user(), 10 * ONE_DAY_NS);
+        assert_eq!(contract.get_transfer_fee(user_str()), NearToken::from_millinear(120));
+
+        setup_transfer_context(&user(), 10 * ONE_DAY_NS, NearToken::from_millinear(150));
+        contract.transfer_license(recipient().to_string());
+        assert_eq!(contract.get_collected_fees(), NearToken::from_millinear(120));
+    }
+
+    #[test]
+    #[should_panic(expected = "Transfer requires a fee of 120000000000000000000000 yoctoNEAR")]
+    fn test_transfer_requires_rate_fee() {
+        let mut contract = setup_contract();
+        contract.set_transfer_fee_rate(1_000, NearToken::from_millinear(10));
+
+        setup_transfer_context(&user(), 10 * ONE_DAY_NS, NearToken::from_millinear(100));
+        contract.transfer_license(recipient().to_string());
+    }
+
+    #[test]
+    fn test_transfer_to_wallet_with_lapsed_license() {
+        let mut contract = setup_contract();
+        contract.grant_license(recipient().to_string(), 1, None, None);
+        contract.grant_license_blocks(recipient().to_string(), 1, None, None);
+        let expiry = contract.get_expiry(user_str()).unwrap();
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(user())
+            .block_timestamp(2 * ONE_DAY_NS)
+            .block_height(10)
+            .attached_deposit(NearToken::from_millinear(100))
+            .build());
+        contract.transfer_license(recipient().to_string());
+
+        assert_eq!(contract.get_expiry(recipient().to_string()), Some(expiry));
+        assert_eq!(contract.get_expiry_height(recipient().to_string()), None);
+        // The lapsed license's serial is retired and the incoming one kept
+        assert_eq!(contract.get_serial(recipient().to_string()), Some(1));
+        assert_eq!(contract.get_license_by_serial(2), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Target wallet already holds a license")]
+    fn test_transfer_to_licensed_wallet() {
+        let mut contract = setup_contract();
+        contract.grant_license(recipient().to_string(), 1, None, None);
+
+        setup_transfer_context(&user(), 0, NearToken::from_millinear(100));
+        contract.transfer_license(recipient().to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "No active license to transfer")]
+    fn test_transfer_without_license() {
+        let mut contract = setup_contract();
+
+        setup_transfer_context(&recipient(), 0, NearToken::from_millinear(100));
+        contract.transfer_license(user_str());
+    }
+
+    #[test]
+    #[should_panic(expected = "Amount exceeds collected fees")]
+    fn test_withdraw_fees_limited_to_collected() {
+        let mut contract = setup_contract();
+
+        let _ = contract.withdraw_fees(NearToken::from_yoctonear(1));
+    }
+
+    /// Collect two transfer fees, with a daily withdrawal limit of 1.5 fees
+    fn setup_limited_contract() -> LicenseContract {
+        let mut contract = setup_contract();
+        contract.set_withdrawal_limit(Some(NearToken::from_millinear(150)));
+
+        setup_transfer_context(&user(), 0, NearToken::from_millinear(100));
+        contract.transfer_license(recipient().to_string());
+        setup_transfer_context(&recipient(), 7 * ONE_DAY_NS, NearToken::from_millinear(100));
+        contract.transfer_license(user_str());
+        contract
+    }
+
+    #[test]
+    fn test_withdrawal_limit_rolls_over() {
+        let mut contract = setup_limited_contract();
+
+        setup_context(&admin(), 10 * ONE_DAY_NS);
+        let _ = contract.withdraw_fees(NearToken::from_millinear(100));
+        assert_eq!(contract.get_withdrawal_allowance(), Some(NearToken::from_millinear(50)));
+
+        setup_context(&admin(), 11 * ONE_DAY_NS);
+        assert_eq!(contract.get_withdrawal_allowance(), Some(NearToken::from_millinear(150)));
+        let _ = contract.withdraw_fees(NearToken::from_millinear(100));
+        assert_eq!(contract.get_collected_fees(), NearToken::from_yoctonear(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Withdrawal exceeds the daily limit of 150000000000000000000000 yoctoNEAR")]
+    fn test_withdrawal_above_daily_limit() {
+        let mut contract = setup_limited_contract();
+
+        setup_context(&admin(), 10 * ONE_DAY_NS);
+        let _ = contract.withdraw_fees(NearToken::from_millinear(100));
+        setup_context(&admin(), 11 * ONE_DAY_NS - 1);
+        let _ = contract.withdraw_fees(NearToken::from_millinear(100));
+    }
+}
@@ -0,0 +1,317 @@
+use near_sdk::store::IterableMap;
+use near_sdk::{near, AccountId, env, require, Gas, NearToken, PanicOnDefault, Promise};
+
+/// Gas attached to each pause or transfer freeze call on a license contract
+const PAUSE_GAS: Gas = Gas::from_tgas(5);
+/// Gas reserved for the `on_broadcast` callback
+const CALLBACK_GAS: Gas = Gas::from_tgas(10);
+/// Maximum number of license contracts a single broadcast reaches
+const MAX_BROADCAST_SIZE: u32 = 20;
+/// Maximum number of products returned by `get_products`
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// A product and the license contract serving it.
+#[near(serializers = [json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProductEntry {
+    pub product_id: String,
+    pub contract_id: AccountId,
+}
+
+/// Registry mapping product IDs to their license contracts, and the control
+/// plane for pausing them, or freezing their transfers, all at once. The registry
+/// must be set as the pauser (`set_pauser`) on every registered license contract
+/// for broadcasts to succeed.
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct LicenseRegistry {
+    /// Account that can register products and broadcast pauses
+    owner: AccountId,
+    /// Mapping of product IDs to license contract accounts
+    products: IterableMap<String, AccountId>,
+}
+
+#[near]
+impl LicenseRegistry {
+    /// Initialize the registry.
+    ///
+    /// # Arguments
+    /// * `owner` - The account that can register products and broadcast pauses
+    #[init]
+    pub fn new(owner: AccountId) -> Self {
+        Self {
+            owner,
+            products: IterableMap::new(b"p"),
+        }
+    }
+
+    /// Register the license contract serving a product.
+    ///
+    /// # Arguments
+    /// * `product_id` - The product identifier
+    /// * `contract_id` - The license contract account for the product
+    ///
+    /// # Panics
+    /// Panics if caller is not the owner or the product is already registered
+    pub fn register_product(&mut self, product_id: String, contract_id: AccountId) {
+        self.assert_owner();
+        require!(!self.products.contains_key(&product_id), "Product is already registered");
+        self.products.insert(product_id, contract_id);
+    }
+
+    /// Remove a product from the registry.
+    ///
+    /// # Panics
+    /// Panics if caller is not the owner or the product isn't registered
+    pub fn unregister_product(&mut self, product_id: String) {
+        self.assert_owner();
+        require!(self.products.remove(&product_id).is_some(), "Product is not registered");
+    }
+
+    /// Get the license contract serving a product, if registered.
+    pub fn get_license_contract(&self, product_id: String) -> Option<AccountId> {
+        self.products.get(&product_id).cloned()
+    }
+
+    /// Get the number of registered products.
+    pub fn get_product_count(&self) -> u32 {
+        self.products.len()
+    }
+
+    /// Get a page of registered products.
+    ///
+    /// # Arguments
+    /// * `from_index` - Position to start from (default 0)
+    /// * `limit` - Maximum number of products to return (default and maximum 100)
+    pub fn get_products(&self, from_index: Option<u32>, limit: Option<u32>) -> Vec<ProductEntry> {
+        self.page(from_index, limit.unwrap_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE))
+    }
+
+    /// Pause or unpause a page of registered license contracts.
+    /// Broadcasts are paginated to stay within the gas limit; call again with the
+    /// next `from_index` until every product has been reached.
+    ///
+    /// # Arguments
+    /// * `paused` - Whether to pause (`true`) or unpause (`false`)
+    /// * `from_index` - Position to start from (default 0)
+    /// * `limit` - Maximum number of contracts to reach (default and maximum 20)
+    ///
+    /// # Returns
+    /// A promise resolving to the product IDs whose contracts rejected the call
+    ///
+    /// # Panics
+    /// Panics if caller is not the owner or the page is empty
+    pub fn broadcast_pause(&mut self, paused: bool, from_index: Option<u32>, limit: Option<u32>) -> Promise {
+        let method = if paused { "pause" } else { "unpause" };
+        self.broadcast(method, from_index, limit)
+    }
+
+    /// Freeze or unfreeze license transfers on a page of registered license contracts,
+    /// e.g. during a phishing wave, without pausing grants or license checks.
+    /// Paginated like `broadcast_pause`.
+    ///
+    /// # Arguments
+    /// * `frozen` - Whether to freeze (`true`) or unfreeze (`false`) transfers
+    /// * `from_index` - Position to start from (default 0)
+    /// * `limit` - Maximum number of contracts to reach (default and maximum 20)
+    ///
+    /// # Returns
+    /// A promise resolving to the product IDs whose contracts rejected the call
+    ///
+    /// # Panics
+    /// Panics if caller is not the owner or the page is empty
+    pub fn broadcast_transfer_freeze(&mut self, frozen: bool, from_index: Option<u32>, limit: Option<u32>) -> Promise {
+        let method = if frozen { "freeze_transfers" } else { "unfreeze_transfers" };
+        self.broadcast(method, from_index, limit)
+    }
+
+    /// Report the products whose license contracts rejected a broadcast.
+    /// The broadcast methods return nothing, so any non-empty result also counts as a failure
+    #[private]
+    pub fn on_broadcast(&mut self, method: String, product_ids: Vec<String>) -> Vec<String> {
+        let failed: Vec<String> = product_ids
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| env::promise_result_checked(*i as u64, 0).is_err())
+            .map(|(_, product_id)| product_id)
+            .collect();
+
+        if !failed.is_empty() {
+            env::log_str(&format!("Failed to {} products: {}", method, failed.join(", ")));
+        }
+        failed
+    }
+
+    /// Get the registry owner.
+    pub fn get_owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+
+    /// Panics unless the caller is the owner
+    fn assert_owner(&self) {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Unauthorized: only owner can perform this action"
+        );
+    }
+
+    /// Call an argument-less method on a page of license contracts, reporting failures
+    /// through `on_broadcast`
+    fn broadcast(&self, method: &str, from_index: Option<u32>, limit: Option<u32>) -> Promise {
+        self.assert_owner();
+
+        let entries = self.page(from_index, limit.unwrap_or(MAX_BROADCAST_SIZE).min(MAX_BROADCAST_SIZE));
+        require!(!entries.is_empty(), "No products to broadcast to");
+
+        let calls = entries
+            .iter()
+            .map(|entry| {
+                Promise::new(entry.contract_id.clone()).function_call(
+                    method.to_string(),
+                    Vec::new(),
+                    NearToken::from_yoctonear(0),
+                    PAUSE_GAS,
+                )
+            })
+            .reduce(|all, call| all.and(call))
+            .unwrap();
+
+        calls.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(CALLBACK_GAS)
+                .on_broadcast(method.to_string(), entries.into_iter().map(|entry| entry.product_id).collect()),
+        )
+    }
+
+    /// Registered products from `from_index`, at most `limit` of them
+    fn page(&self, from_index: Option<u32>, limit: u32) -> Vec<ProductEntry> {
+        self.products
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit as usize)
+            .map(|(product_id, contract_id)| ProductEntry {
+                product_id: product_id.clone(),
+                contract_id: contract_id.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{testing_env, PromiseResult};
+
+    fn owner() -> AccountId {
+        "owner.near".parse().unwrap()
+    }
+
+    fn license(product: &str) -> AccountId {
+        format!("{}.factory.near", product).parse().unwrap()
+    }
+
+    fn setup_context(predecessor: &AccountId) -> VMContextBuilder {
+        let mut context = VMContextBuilder::new();
+        context
+            .current_account_id("registry.near".parse().unwrap())
+            .predecessor_account_id(predecessor.clone());
+        testing_env!(context.build());
+        context
+    }
+
+    fn setup_registry() -> LicenseRegistry {
+        setup_context(&owner());
+        let mut registry = LicenseRegistry::new(owner());
+        for product in ["alpha", "beta", "gamma"] {
+            registry.register_product(product.to_string(), license(product));
+        }
+        registry
+    }
+
+    #[test]
+    fn test_register_and_lookup() {
+        let mut registry = setup_registry();
+
+        assert_eq!(registry.get_product_count(), 3);
+        assert_eq!(registry.get_license_contract("beta".to_string()), Some(license("beta")));
+        assert_eq!(registry.get_products(Some(1), Some(1)).len(), 1);
+
+        registry.unregister_product("beta".to_string());
+        assert_eq!(registry.get_license_contract("beta".to_string()), None);
+        assert_eq!(registry.get_product_count(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Product is already registered")]
+    fn test_register_duplicate() {
+        let mut registry = setup_registry();
+
+        registry.register_product("alpha".to_string(), license("other"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only owner can perform this action")]
+    fn test_register_unauthorized() {
+        let mut registry = setup_registry();
+
+        setup_context(&license("alpha"));
+        registry.register_product("delta".to_string(), license("delta"));
+    }
+
+    #[test]
+    fn test_broadcast_pause() {
+        let mut registry = setup_registry();
+
+        let _ = registry.broadcast_pause(true, None, None);
+    }
+
+    #[test]
+    fn test_broadcast_transfer_freeze() {
+        let mut registry = setup_registry();
+
+        let _ = registry.broadcast_transfer_freeze(true, Some(2), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized: only owner can perform this action")]
+    fn test_broadcast_transfer_freeze_unauthorized() {
+        let mut registry = setup_registry();
+
+        setup_context(&license("alpha"));
+        let _ = registry.broadcast_transfer_freeze(true, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "No products to broadcast to")]
+    fn test_broadcast_pause_empty_page() {
+        let mut registry = setup_registry();
+
+        let _ = registry.broadcast_pause(true, Some(3), None);
+    }
+
+    #[test]
+    fn test_on_broadcast_reports_failures() {
+        let mut registry = setup_registry();
+
+        let context = setup_context(&"registry.near".parse().unwrap());
+        testing_env!(
+            context.build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![
+                PromiseResult::Successful(Vec::new()),
+                PromiseResult::Failed,
+                PromiseResult::Successful(Vec::new()),
+            ]
+        );
+
+        let failed = registry.on_broadcast(
+            "pause".to_string(),
+            vec!["alpha".to_string(), "beta".to_string(), "gamma".to_string()],
+        );
+        assert_eq!(failed, vec!["beta".to_string()]);
+        assert_eq!(near_sdk::test_utils::get_logs(), vec!["Failed to pause products: beta"]);
+    }
+}
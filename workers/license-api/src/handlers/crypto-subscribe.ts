@@ -389,7 +389,8 @@ export async function handleCryptoSubscribeConfirm(
     const licenseResult = await grantLicense(
       c.env,
       walletAddress,
-      durationDays
+      durationDays,
+      subscription.intentId
     );
 
     if (!licenseResult.success) {
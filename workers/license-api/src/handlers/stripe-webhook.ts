@@ -195,7 +195,7 @@ async function handleInvoicePaid(
   } else {
     // Wallet user: grant license on NEAR contract (existing flow)
     const accountId = nearAccountId || effectiveUserId;
-    const result = await grantLicense(env, accountId, durationDays);
+    const result = await grantLicense(env, accountId, durationDays, invoice.id);
 
     if (!result.success) {
       console.error(`Failed to grant NEAR license: ${result.error}`);
@@ -151,13 +151,16 @@ async function sha256(data: Uint8Array): Promise<Uint8Array> {
 
 /**
  * Grant license on NEAR contract
- * Calls contract.grant_license(wallet_address, duration_days)
+ * Calls contract.grant_license(wallet_address, duration_days, correlation_id)
  * Supports any wallet address string (NEAR accounts, EVM addresses, Solana pubkeys, etc.)
+ * The optional correlation ID (e.g. invoice or payment intent ID) is echoed in the
+ * contract's license_granted event, so notifications can be matched to the order.
  */
 export async function grantLicense(
   env: Env,
   walletAddress: string,
-  durationDays: number
+  durationDays: number,
+  correlationId?: string
 ): Promise<{ success: boolean; txHash?: string; error?: string }> {
   try {
     const rpcUrl = getRpcUrl(env);
@@ -188,6 +191,7 @@ export async function grantLicense(
     const args = JSON.stringify({
       wallet_address: walletAddress,
       duration_days: durationDays,
+      correlation_id: correlationId ?? null,
     });
 
     // Create the function call action